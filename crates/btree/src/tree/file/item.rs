@@ -2,7 +2,8 @@ use std::rc::Rc;
 use std::{mem, ptr};
 
 use llio::util::record_id::RecordId;
-use trail::deserialize::Deserialize;
+use trail::deserialize::{take, Deserialize};
+use trail::error::Error;
 use trail::field::Field;
 use trail::serialize::Serialize;
 
@@ -62,7 +63,7 @@ impl FileBTreeNodeItem {
             Self::Pair(k, v) => {
                 Self::Pair(Rc::clone(k), v.iter().map(|val| Rc::clone(val)).collect())
             }
-            Self::Pointer(ptr) => Self::Pointer(ptr.clone()),
+            Self::Pointer(ptr) => Self::Pointer(*ptr),
         }
     }
 
@@ -78,14 +79,13 @@ impl FileBTreeNodeItem {
 
 impl Serialize for FileBTreeNodeItem {
     fn size(&self) -> u32 {
-        // type + size + item
+        // type + size + item, pointers are fixed-size and go without a size
         mem::size_of::<u8>() as u32
-            + mem::size_of::<u32>() as u32
             + match self {
-                Self::Key(key) => key.size() - mem::size_of::<u32>() as u32,
+                Self::Key(key) => key.size(),
                 // key field size + value
-                Self::Pair(key, value) => key.size() + value.size(),
-                Self::Pointer(rci) => rci.size() - mem::size_of::<u32>() as u32,
+                Self::Pair(key, value) => mem::size_of::<u32>() as u32 + key.size() + value.size(),
+                Self::Pointer(rci) => rci.size(),
             }
     }
 
@@ -162,39 +162,48 @@ impl Serialize for FileBTreeNodeItem {
 }
 
 impl Deserialize for FileBTreeNodeItem {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
-        let item_type = from[0];
+    fn deserialize(from: &[u8]) -> trail::error::Result<Self> {
+        let item_type = take(from, 0, mem::size_of::<u8>())?[0];
+        let from_item = &from[mem::size_of::<u8>()..];
 
         Ok(match item_type {
-            0 => FileBTreeNodeItem::Key(Rc::new(Field::deserialize(
-                &from[(mem::size_of::<u8>())..],
-            )?)),
+            0 => FileBTreeNodeItem::Key(Rc::new(
+                Field::deserialize(from_item).map_err(|err| err.at(1))?,
+            )),
             1 => {
-                let pair_size = u32::deserialize(
-                    &from[mem::size_of::<u8>()..(mem::size_of::<u8>() + mem::size_of::<u32>())],
-                )? as usize;
-
-                let key = Field::deserialize(
-                    &from[(mem::size_of::<u8>() + mem::size_of::<u32>())
-                        ..(mem::size_of::<u8>() + pair_size)],
-                )?;
+                let pair_size = u32::deserialize(take(from_item, 0, mem::size_of::<u32>())?)
+                    .map_err(|err| err.at(1))? as usize;
+
+                // the pair size counts its own length prefix
+                let pair = pair_size
+                    .checked_sub(mem::size_of::<u32>())
+                    .ok_or_else(|| {
+                        Error::invalid(1, format!("pair size {pair_size} is too small"))
+                    })?;
+                let pair = take(from_item, mem::size_of::<u32>(), pair).map_err(|_| {
+                    Error::LengthOverflow {
+                        offset: 1,
+                        length: pair_size as u64,
+                    }
+                })?;
+                let pair_start = mem::size_of::<u8>() + mem::size_of::<u32>();
+
+                let key = Field::deserialize(pair).map_err(|err| err.at(pair_start))?;
 
                 let key_size = key.size() as usize;
 
-                let value = Vec::<Field>::deserialize(
-                    &from[(mem::size_of::<u8>() + mem::size_of::<u32>() + key_size)
-                        ..(mem::size_of::<u8>() + pair_size)],
-                )?;
+                let value = pair
+                    .get(key_size..)
+                    .ok_or_else(|| Error::invalid(pair_start, "pair key overflows the pair"))?;
+                let value = Vec::<Rc<Field>>::deserialize(value)
+                    .map_err(|err| err.at(pair_start + key_size))?;
 
-                FileBTreeNodeItem::Pair(
-                    Rc::new(key),
-                    value.into_iter().map(|item| Rc::new(item)).collect(),
-                )
-            }
-            2 => {
-                FileBTreeNodeItem::Pointer(RecordId::deserialize(&from[(mem::size_of::<u8>())..])?)
+                FileBTreeNodeItem::Pair(Rc::new(key), value)
             }
-            _ => unreachable!(),
+            2 => FileBTreeNodeItem::Pointer(
+                RecordId::deserialize(from_item).map_err(|err| err.at(1))?,
+            ),
+            tag => return Err(Error::invalid(0, format!("unknown node item type {tag}"))),
         })
     }
 }
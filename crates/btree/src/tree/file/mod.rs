@@ -9,7 +9,12 @@ use std::{
 };
 
 use item::FileBTreeNodeItem;
-use llio::{io::direct::DirectFileIo, page::PAGE_SIZE, pager::Pager, util::record_id::RecordId};
+use llio::{
+    header::FileKind,
+    io::{direct::DirectFileIo, options::FileOptions, PageIo},
+    pager::Pager,
+    util::record_id::{RecordId, RECORD_ID_SIZE},
+};
 use node::FileBTreeNode;
 use trail::{
     deserialize::Deserialize,
@@ -22,7 +27,8 @@ pub struct FileBTree {
     pager: Pager,
     unique: bool,
     max_degree: usize,
-    metadata: DirectFileIo,
+    metadata: Box<dyn PageIo>,
+    file_id: u32,
 }
 
 impl FileBTree {
@@ -32,45 +38,95 @@ impl FileBTree {
         max_degree: usize,
         unique: bool,
     ) -> Result<Self, Box<dyn Error>> {
-        Ok(Self {
-            pager: Pager::new(DirectFileIo::new(path)?),
+        Self::with_options(
+            path,
+            metadata_path,
+            FileOptions::default(),
+            max_degree,
+            unique,
+        )
+    }
+
+    /// Like [`FileBTree::new`], `options` apply to the node and metadata files
+    pub fn with_options(
+        path: &str,
+        metadata_path: &str,
+        options: FileOptions,
+        max_degree: usize,
+        unique: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_io(
+            Pager::new(DirectFileIo::with_options(
+                path,
+                FileKind::BTree,
+                options.clone(),
+            )?),
+            DirectFileIo::with_options(metadata_path, FileKind::BTreeMetadata, options)?,
+            max_degree,
+            unique,
+        ))
+    }
+
+    pub fn from_io(
+        pager: Pager,
+        metadata: impl PageIo + 'static,
+        max_degree: usize,
+        unique: bool,
+    ) -> Self {
+        Self {
+            pager,
             unique,
             max_degree,
-            metadata: DirectFileIo::new(metadata_path)?,
-        })
+            metadata: Box::new(metadata),
+            file_id: 0,
+        }
+    }
+
+    /// Sets the catalog id of the node file, it is part of every node pointer
+    pub fn with_file_id(mut self, file_id: u32) -> Self {
+        self.file_id = file_id;
+        self
+    }
+
+    pub fn file_id(&self) -> u32 {
+        self.file_id
     }
 
     pub fn max_degree(&self) -> usize {
         self.max_degree
     }
+
+    /// Makes the tree and its metadata durable
+    pub fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        self.pager.sync()?;
+        self.metadata.sync()?;
+
+        Ok(())
+    }
 }
 
 impl FileBTree {
     fn root_rci(&self) -> Result<Option<RecordId>, Box<dyn Error>> {
-        let mut root_rci_len = vec![0u8; mem::size_of::<u32>()];
         let mut page = self.metadata.load_page(0)?;
-
-        page.read(&mut root_rci_len)?;
-        let root_rci_len = u32::deserialize(&root_rci_len)?;
-
-        if root_rci_len == 0 {
+        if page.empty() {
             return Ok(None);
         }
 
-        let mut root_rci = vec![0u8; root_rci_len as usize];
-        page.read(&mut root_rci)?;
+        let mut root_rci = [0u8; RECORD_ID_SIZE];
+        page.read_exact(&mut root_rci)?;
         let root_rci = RecordId::deserialize(&root_rci)?;
 
         Ok(Some(root_rci))
     }
 
     fn create_root(&mut self) -> Result<(FileBTreeNode, RecordId), Box<dyn Error>> {
-        let root = FileBTreeNode::empty(false, Some(RecordId::new("".to_string(), 2)));
+        let mut root = FileBTreeNode::empty(false, None);
 
         let root_record_id = self.save_node(&root)?;
+        root.set_record_id(Some(root_record_id));
 
         let mut metadata_page = self.metadata.load_page(0)?;
-        metadata_page.write(&root_record_id.serialize()?)?;
+        metadata_page.write_all(&root_record_id.serialize()?)?;
         self.metadata.flush_page(0, metadata_page)?;
 
         Ok((root, root_record_id))
@@ -97,59 +153,31 @@ impl FileBTree {
     }
 
     fn read_node(&self, record_id: &RecordId) -> Result<FileBTreeNode, Box<dyn Error>> {
-        let mut node_size = vec![0u8; mem::size_of::<u32>()].into_boxed_slice();
-        self.pager.read_at(
-            &mut node_size,
-            (
-                record_id.offset() / PAGE_SIZE as u64,
-                (record_id.offset() % PAGE_SIZE as u64) as u16,
-            ),
-        )?;
-        let node_size = u32::deserialize(&node_size)?;
-
-        let mut node = vec![0u8; node_size as usize].into_boxed_slice();
-        self.pager.read_at(
-            &mut node,
-            (
-                record_id.offset() / PAGE_SIZE as u64,
-                (record_id.offset() % PAGE_SIZE as u64) as u16,
-            ),
-        )?;
+        let node = self.pager.read_record(record_id.position())?;
 
         let mut node = FileBTreeNode::deserialize(&node)?;
 
-        node.set_record_id(Some(record_id.clone()));
+        node.set_record_id(Some(*record_id));
 
         Ok(node)
     }
 
     fn save_node(&mut self, node: &FileBTreeNode) -> Result<RecordId, Box<dyn Error>> {
         let pos = if let Some(record_id) = node.record_id() {
-            (
-                record_id.offset() / PAGE_SIZE as u64,
-                (record_id.offset() % PAGE_SIZE as u64) as u16,
-            )
+            let pos = record_id.position();
+            self.pager.replace_record(pos, &node.serialize()?)?;
+            pos
         } else {
-            self.pager.occupied()?
+            self.pager.write_record(&node.serialize()?)?
         };
-        self.pager.replace_at(&node.serialize()?, pos)?;
 
-        Ok(RecordId::new(
-            "".to_string(),
-            pos.0 * PAGE_SIZE as u64 + pos.1 as u64,
-        ))
+        Ok(RecordId::from_position(self.file_id, pos))
     }
 
     fn remove_node(&mut self, record_id: &RecordId) -> Result<FileBTreeNode, Box<dyn Error>> {
         let node = self.read_node(record_id)?;
 
-        self.pager.erase_at(
-            node.size() as usize,
-            (
-                record_id.offset() / PAGE_SIZE as u64,
-                (record_id.offset() % PAGE_SIZE as u64) as u16,
-            ),
-        )?;
+        self.pager.erase_record(record_id.position())?;
 
         Ok(node)
     }
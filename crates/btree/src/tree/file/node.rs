@@ -1,7 +1,11 @@
 use std::{mem, ptr};
 
 use llio::util::record_id::RecordId;
-use trail::{deserialize::Deserialize, serialize::Serialize};
+use trail::{
+    deserialize::{take, Deserialize},
+    error::Error,
+    serialize::Serialize,
+};
 
 use super::item::FileBTreeNodeItem;
 
@@ -190,31 +194,39 @@ impl Serialize for FileBTreeNode {
 }
 
 impl Deserialize for FileBTreeNode {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
-        let size = u32::deserialize(&from[..mem::size_of::<u32>()])?;
-        let mut node = Self::empty(false, None);
-        node.set_internal(bool::deserialize(
-            &from[mem::size_of::<u32>()..(mem::size_of::<u32>() + mem::size_of::<bool>())],
-        )?);
-        let has_parent = bool::deserialize(
-            &from[(mem::size_of::<u32>() + mem::size_of::<bool>())
-                ..(mem::size_of::<u32>() + mem::size_of::<bool>() * 2)],
-        )?;
+    fn deserialize(from: &[u8]) -> trail::error::Result<Self> {
+        let size = u32::deserialize(take(from, 0, mem::size_of::<u32>())?)?;
+        let from = take(from, 0, size as usize).map_err(|_| Error::LengthOverflow {
+            offset: 0,
+            length: size as u64,
+        })?;
 
+        let mut node = Self::empty(false, None);
+        let flags_start = mem::size_of::<u32>();
+        node.set_internal(
+            bool::deserialize(take(from, flags_start, mem::size_of::<bool>())?)
+                .map_err(|err| err.at(flags_start))?,
+        );
+        let has_parent = bool::deserialize(take(
+            from,
+            flags_start + mem::size_of::<bool>(),
+            mem::size_of::<bool>(),
+        )?)
+        .map_err(|err| err.at(flags_start + mem::size_of::<bool>()))?;
+
+        let parent_start = flags_start + mem::size_of::<bool>() * 2;
         let parent_rci = if has_parent {
-            Some(RecordId::deserialize(
-                &from[(mem::size_of::<u32>() + mem::size_of::<bool>() * 2)..],
-            )?)
+            Some(RecordId::deserialize(&from[parent_start..]).map_err(|err| err.at(parent_start))?)
         } else {
             None
         };
 
-        let mut offset = mem::size_of::<u32>()
-            + mem::size_of::<bool>() * 2
-            + parent_rci.as_ref().map(|rci| rci.size()).unwrap_or(0) as usize;
+        let mut offset =
+            parent_start + parent_rci.as_ref().map(|rci| rci.size()).unwrap_or(0) as usize;
 
-        while offset < size as usize {
-            let item = FileBTreeNodeItem::deserialize(&from[offset..])?;
+        while offset < from.len() {
+            let item =
+                FileBTreeNodeItem::deserialize(&from[offset..]).map_err(|err| err.at(offset))?;
             offset += item.size() as usize;
 
             node.append(item);
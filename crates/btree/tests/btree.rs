@@ -1,4 +1,5 @@
 use btree::tree::mem::BTree;
+use trail::field::{Decimal, Field};
 
 #[test]
 pub fn insertion() {
@@ -58,3 +59,22 @@ pub fn retrieval() {
     assert_eq!(*key_3[0], 4);
     assert_eq!(*key_3[1], 5);
 }
+
+#[test]
+pub fn decimal_keys_match_integer_keys() {
+    let mut btree = BTree::<Field, u32>::new(4, false);
+    let decimal = |s: &str| Field::from(s.parse::<Decimal>().unwrap());
+
+    btree.insert((decimal("1.50"), 1));
+    btree.insert((Field::int32(2), 2));
+    btree.insert((decimal("0.25"), 3));
+    btree.insert((decimal("2.0"), 4));
+    btree.insert((Field::int64(-1), 5));
+    btree.insert((decimal("-0.5"), 6));
+
+    let two = btree.get(&decimal("2.000")).unwrap();
+    assert_eq!(two.iter().map(|v| **v).collect::<Vec<_>>(), [2, 4]);
+    assert_eq!(*btree.get(&decimal("1.5")).unwrap()[0], 1);
+    assert_eq!(*btree.get(&decimal("-1")).unwrap()[0], 5);
+    assert!(btree.get(&decimal("1")).is_none());
+}
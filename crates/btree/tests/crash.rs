@@ -0,0 +1,65 @@
+use std::{collections::HashSet, rc::Rc};
+
+use btree::tree::file::FileBTree;
+use llio::{
+    io::{
+        faulty::{FaultConfig, FaultyFileIo, Rng},
+        memory::MemoryFileIo,
+    },
+    page::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE},
+    pager::Pager,
+};
+use trail::field::Field;
+
+#[test]
+pub fn durable_keys_survive_crashes() {
+    for seed in 0..64 {
+        let mut rng = Rng::new(seed);
+        let page_size = [DEFAULT_PAGE_SIZE, 16384, MAX_PAGE_SIZE][seed as usize % 3];
+        let tree_disk = MemoryFileIo::with_page_size(page_size);
+        let metadata_disk = MemoryFileIo::new();
+
+        let config = FaultConfig::builder()
+            .crash_after_writes(1 + rng.below(60))
+            .drop_unsynced(true)
+            .build();
+        let io = FaultyFileIo::new(tree_disk.clone(), config);
+        let mut tree = FileBTree::from_io(Pager::new(io), metadata_disk.clone(), 4, false);
+
+        // the root is created with the first write
+        tree.root().unwrap();
+        tree.flush().unwrap();
+
+        let mut inserted = Vec::new();
+        let mut seen = HashSet::new();
+        let mut durable = 0;
+
+        loop {
+            let key = rng.below(u32::MAX as u64) as u32;
+            if !seen.insert(key) {
+                continue;
+            }
+
+            let kv = (Field::uint32(key), Rc::new(Field::uint64(key as u64 * 2)));
+            if tree.insert(kv).is_err() {
+                break;
+            }
+            inserted.push(key);
+
+            if rng.below(3) == 0 {
+                if tree.flush().is_err() {
+                    break;
+                }
+                durable = inserted.len();
+            }
+        }
+        drop(tree);
+
+        let mut tree = FileBTree::from_io(Pager::new(tree_disk), metadata_disk, 4, false);
+        for key in &inserted[..durable] {
+            let values = tree.get(&Field::uint32(*key)).unwrap().unwrap();
+            assert_eq!(values.len(), 1);
+            assert!(*values[0] == Field::uint64(*key as u64 * 2));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{ptr, rc::Rc};
+use std::rc::Rc;
 
 use btree::tree::file::item::FileBTreeNodeItem;
 use llio::util::record_id::RecordId;
@@ -43,7 +43,7 @@ pub fn item_pair_serialization_works() {
 
 #[test]
 pub fn item_pointer_serialization_works() {
-    let item = FileBTreeNodeItem::Pointer(RecordId::new("/hello/world".to_string(), 512));
+    let item = FileBTreeNodeItem::Pointer(RecordId::new(1, 2, 512));
 
     let buffer = item.serialize();
     assert!(buffer.is_ok());
@@ -51,10 +51,7 @@ pub fn item_pointer_serialization_works() {
     let buffer = buffer.unwrap();
     assert_eq!(
         &buffer[..],
-        [
-            2, 24, 0, 0, 0, 47, 104, 101, 108, 108, 111, 47, 119, 111, 114, 108, 100, 0, 2, 0, 0,
-            0, 0, 0, 0
-        ]
+        [2, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0]
     );
 }
 
@@ -66,14 +63,7 @@ pub fn item_key_deserialization_works() {
 
     let item = item.unwrap();
     assert!(item.is_key());
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(item.as_key().value() as *const Box<_>)) as *const String)
-                .as_ref()
-                .unwrap()
-        },
-        "username"
-    );
+    assert_eq!(item.as_key().value_as_string().unwrap(), "username");
 }
 
 #[test]
@@ -87,45 +77,19 @@ pub fn item_pair_deserialization_works() {
 
     let item = item.unwrap();
     assert!(item.is_pair());
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(item.as_pair().0.value() as *const Box<_>)) as *const String)
-                .as_ref()
-                .unwrap()
-        },
-        "cities"
-    );
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(item.as_pair().1[0].value() as *const Box<_>))
-                as *const String)
-                .as_ref()
-                .unwrap()
-        },
-        "NY"
-    );
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(item.as_pair().1[1].value() as *const Box<_>))
-                as *const String)
-                .as_ref()
-                .unwrap()
-        },
-        "LA"
-    );
+    assert_eq!(item.as_pair().0.value_as_string().unwrap(), "cities");
+    assert_eq!(item.as_pair().1[0].value_as_string().unwrap(), "NY");
+    assert_eq!(item.as_pair().1[1].value_as_string().unwrap(), "LA");
 }
 
 #[test]
 pub fn item_pointer_deserialization_works() {
-    let buffer = [
-        2, 24, 0, 0, 0, 47, 104, 101, 108, 108, 111, 47, 119, 111, 114, 108, 100, 0, 2, 0, 0, 0, 0,
-        0, 0,
-    ];
+    let buffer = [2, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0];
     let item = FileBTreeNodeItem::deserialize(&buffer);
     assert!(item.is_ok());
 
     let item = item.unwrap();
     assert!(item.is_pointer());
-    assert_eq!(item.as_pointer().path(), "/hello/world");
-    assert_eq!(item.as_pointer().offset(), 512);
+    assert_eq!(*item.as_pointer(), RecordId::new(1, 2, 512));
+    assert_eq!(item.size(), 17);
 }
@@ -0,0 +1,37 @@
+use std::{fs, rc::Rc};
+
+use btree::tree::file::FileBTree;
+use llio::{
+    header::FileKind,
+    io::mmap::{AccessPattern, MmapFileIo},
+    pager::Pager,
+};
+use trail::field::Field;
+
+#[test]
+pub fn lookups_run_on_mapped_files() {
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let path = format!("{dir}/mmap_tree.db");
+    let metadata_path = format!("{dir}/mmap_tree_metadata.db");
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(&metadata_path);
+
+    let mut tree = FileBTree::new(&path, &metadata_path, 4, false).unwrap();
+    for key in 0..200u32 {
+        let kv = (Field::uint32(key), Rc::new(Field::uint64(key as u64 * 3)));
+        tree.insert(kv).unwrap();
+    }
+    tree.flush().unwrap();
+    drop(tree);
+
+    let io = MmapFileIo::new(&path, FileKind::BTree).unwrap();
+    io.advise(AccessPattern::Random).unwrap();
+    let metadata = MmapFileIo::new(&metadata_path, FileKind::BTreeMetadata).unwrap();
+    let mut tree = FileBTree::from_io(Pager::new(io), metadata, 4, false);
+
+    for key in 0..200u32 {
+        let values = tree.get(&Field::uint32(key)).unwrap().unwrap();
+        assert!(*values[0] == Field::uint64(key as u64 * 3));
+    }
+    assert!(tree.get(&Field::uint32(500)).unwrap().is_none());
+}
@@ -1,4 +1,4 @@
-use std::{ptr, rc::Rc};
+use std::rc::Rc;
 
 use btree::tree::file::{item::FileBTreeNodeItem, node::FileBTreeNode};
 use llio::util::record_id::RecordId;
@@ -12,7 +12,7 @@ pub fn node_serialization_works() {
         Rc::new(Field::ubyte(11)),
         vec![Rc::new(Field::string("value".to_string()))],
     ));
-    node.set_parent(Some(RecordId::new("path".to_string(), 400)));
+    node.set_parent(Some(RecordId::new(3, 1, 400)));
 
     let buffer = node.serialize();
     assert!(buffer.is_ok());
@@ -21,8 +21,8 @@ pub fn node_serialization_works() {
     assert_eq!(
         &buffer[..],
         [
-            50, 0, 0, 0, 0, 1, 16, 0, 0, 0, 112, 97, 116, 104, 144, 1, 0, 0, 0, 0, 0, 0, 0, 2, 1,
-            0, 0, 0, 10, 1, 20, 0, 0, 0, 2, 1, 0, 0, 0, 11, 0, 5, 0, 0, 0, 118, 97, 108, 117, 101
+            50, 0, 0, 0, 0, 1, 3, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 144, 1, 0, 0, 0, 2, 1, 0, 0, 0,
+            10, 1, 20, 0, 0, 0, 2, 1, 0, 0, 0, 11, 0, 5, 0, 0, 0, 118, 97, 108, 117, 101
         ]
     );
 }
@@ -30,8 +30,8 @@ pub fn node_serialization_works() {
 #[test]
 pub fn node_deserialization_works() {
     let buffer = [
-        50, 0, 0, 0, 0, 1, 16, 0, 0, 0, 112, 97, 116, 104, 144, 1, 0, 0, 0, 0, 0, 0, 0, 2, 1, 0, 0,
-        0, 10, 1, 20, 0, 0, 0, 2, 1, 0, 0, 0, 11, 0, 5, 0, 0, 0, 118, 97, 108, 117, 101,
+        50, 0, 0, 0, 0, 1, 3, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 144, 1, 0, 0, 0, 2, 1, 0, 0, 0, 10,
+        1, 20, 0, 0, 0, 2, 1, 0, 0, 0, 11, 0, 5, 0, 0, 0, 118, 97, 108, 117, 101,
     ];
     let node = FileBTreeNode::deserialize(&buffer);
     assert!(node.is_ok());
@@ -39,39 +39,15 @@ pub fn node_deserialization_works() {
     let node = node.unwrap();
     assert_eq!(node.is_internal(), false);
     assert!(node.items()[0].is_key());
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(node.items()[0].as_key().value() as *const Box<_>))
-                as *const u8)
-                .as_ref()
-                .unwrap()
-        },
-        &10
-    );
+    assert_eq!(node.items()[0].as_key().value_as_ubyte().unwrap(), &10);
 
     assert!(node.items()[1].is_pair());
+    assert_eq!(node.items()[1].as_pair().0.value_as_ubyte().unwrap(), &11);
     assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(
-                node.items()[1].as_pair().0.value() as *const Box<_>
-            )) as *const u8)
-                .as_ref()
-                .unwrap()
-        },
-        &11
-    );
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(
-                node.items()[1].as_pair().1[0].value() as *const Box<_>
-            )) as *const String)
-                .as_ref()
-                .unwrap()
-        },
+        node.items()[1].as_pair().1[0].value_as_string().unwrap(),
         "value"
     );
 
     assert!(node.parent().is_some());
-    assert_eq!(node.parent().unwrap().path(), "path");
-    assert_eq!(node.parent().unwrap().offset(), 400);
+    assert_eq!(*node.parent().unwrap(), RecordId::new(3, 1, 400));
 }
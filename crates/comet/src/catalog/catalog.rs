@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use llio::error::Error;
+
+/// Name of the catalog file in a database directory
+pub const CATALOG_FILE_NAME: &str = "CATALOG";
+
+/// Registry of the files of a database, so that record ids refer to a file by a small id instead of its path.
+///
+/// Ids start at 1 and are never reused, 0 stands for a file that was not registered.
+/// Every entry is stored as file id (u32) + name length (u32) + name.
+#[derive(Debug)]
+pub struct Catalog {
+    path: PathBuf,
+    files: BTreeMap<u32, String>,
+    read_only: bool,
+}
+
+impl Catalog {
+    /// Reads the catalog at `path`, a missing file is an empty catalog
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(path.as_ref(), false)
+    }
+
+    /// Reads the catalog at `path` without ever writing it, no new files can be registered
+    pub fn open_read_only(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(path.as_ref(), true)
+    }
+
+    fn read(path: &Path, read_only: bool) -> io::Result<Self> {
+        let path = path.to_path_buf();
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "the catalog is corrupted");
+        let mut files = BTreeMap::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let header = bytes.get(offset..(offset + 8)).ok_or_else(invalid)?;
+            let file_id = u32::from_le_bytes(header[0..4].try_into().unwrap());
+            let len = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+            let name = bytes
+                .get((offset + 8)..(offset + 8 + len))
+                .ok_or_else(invalid)?;
+            let name = String::from_utf8(name.to_vec()).map_err(|_| invalid())?;
+
+            files.insert(file_id, name);
+            offset += 8 + len;
+        }
+
+        Ok(Self {
+            path,
+            files,
+            read_only,
+        })
+    }
+
+    /// Id of the file called `name`, it is registered if it is not known yet
+    pub fn register(&mut self, name: &str) -> io::Result<u32> {
+        if let Some(file_id) = self.file_id(name) {
+            return Ok(file_id);
+        }
+        if self.read_only {
+            return Err(Error::ReadOnly.into());
+        }
+
+        let file_id = self.files.keys().next_back().map(|id| id + 1).unwrap_or(1);
+        self.files.insert(file_id, name.to_string());
+        if let Err(err) = self.persist() {
+            self.files.remove(&file_id);
+            return Err(err);
+        }
+
+        Ok(file_id)
+    }
+
+    pub fn file_id(&self, name: &str) -> Option<u32> {
+        self.files
+            .iter()
+            .find(|(_, file)| file.as_str() == name)
+            .map(|(file_id, _)| *file_id)
+    }
+
+    pub fn file_name(&self, file_id: u32) -> Option<&str> {
+        self.files.get(&file_id).map(|name| name.as_str())
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.files
+            .iter()
+            .map(|(file_id, name)| (*file_id, name.as_str()))
+    }
+
+    /// Replaces the catalog file as a whole, so that a crash leaves either the old or the new one
+    fn persist(&self) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        for (file_id, name) in &self.files {
+            bytes.extend_from_slice(&file_id.to_le_bytes());
+            bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(name.as_bytes());
+        }
+
+        let temp_path = self.path.with_extension("tmp");
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &self.path)?;
+
+        // the rename itself is only durable once the directory holding the catalog is synced
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::File::open(dir)?.sync_all()
+    }
+}
@@ -1,40 +1,115 @@
-use std::{cell::RefCell, error::Error, io::Write, path::PathBuf, rc::Rc};
+use std::{error::Error, io, path::PathBuf, sync::Arc};
 
-use llio::io::direct::DirectFileIo;
+use llio::{
+    header::{Checkpoint, FileKind},
+    io::{direct::DirectFileIo, options::FileOptions},
+};
 
 use crate::{cursor::cursor::Cursor, document::document::Document, io::io_config::IoConfig};
 
-use llio::pager::Pager;
+use llio::{pager::Pager, stats::PagerStats, util::record_id::RecordId};
 
 pub struct Collection {
-    pager: Rc<RefCell<Pager>>,
+    pager: Arc<Pager>,
     name: String,
+    file_id: u32,
 }
 
 impl Collection {
-    pub fn new(db: &str, name: String, config: IoConfig) -> Result<Self, Box<dyn Error>> {
+    /// `file_id` is the id of the collection file in the database catalog
+    pub fn new(
+        db: &str,
+        name: String,
+        file_id: u32,
+        config: IoConfig,
+    ) -> Result<Self, Box<dyn Error>> {
         let collection_file_path = PathBuf::from(&config.data_dir()[..]).join(db).join(&name);
-        let io = DirectFileIo::new(collection_file_path.to_str().unwrap())?;
+        let mut options = FileOptions::builder()
+            .page_size(config.page_size())
+            .compression(config.compression())
+            .lock_mode(config.lock_mode());
+        if let Some(key) = config.encryption_key() {
+            options = options.encryption_key(key.clone());
+        }
+        let options = options.build();
+        let io = DirectFileIo::with_options(
+            collection_file_path.to_str().unwrap(),
+            FileKind::Collection,
+            options,
+        )?;
         let pager = Pager::new(io);
 
-        Ok(Collection {
-            pager: Rc::new(RefCell::new(pager)),
+        let mut collection = Self::from_pager(name, pager);
+        collection.file_id = file_id;
+        Ok(collection)
+    }
+
+    /// A collection that is not registered in a catalog, its record ids have file id 0
+    pub fn from_pager(name: String, pager: Pager) -> Self {
+        Collection {
+            pager: Arc::new(pager),
             name,
-        })
+            file_id: 0,
+        }
+    }
+
+    pub fn insert_document(&self, document: &Document) -> Result<RecordId, Box<dyn Error>> {
+        // the document is written straight into the pages of its record
+        let pos = self
+            .pager
+            .write_record_with(document.serialized_size(), |w| document.serialize_into(w))?;
+
+        Ok(RecordId::from_position(self.file_id, pos))
     }
 
-    pub fn insert_document(&mut self, document: &Document) -> Result<(), Box<dyn Error>> {
-        let bytes = document.serialize()?;
-        self.pager.borrow_mut().write(&bytes)?;
+    /// Reads the document a record id points at, it has to belong to this collection
+    pub fn document(&self, record_id: &RecordId) -> Result<Document, Box<dyn Error>> {
+        if record_id.file_id() != self.file_id {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the record belongs to another file",
+            )));
+        }
+
+        self.pager
+            .with_record(record_id.position(), Document::deserialize)?
+    }
+
+    /// Makes all the inserted documents durable
+    pub fn flush(&self) -> Result<(), Box<dyn Error>> {
+        self.pager.sync()?;
 
         Ok(())
     }
 
+    /// Makes all the inserted documents durable and records a checkpoint in the collection file
+    pub fn checkpoint(&self) -> Result<Checkpoint, Box<dyn Error>> {
+        Ok(self.pager.checkpoint()?)
+    }
+
+    /// Gives the space left behind by removed documents back to the file system
+    pub fn reclaim_space(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(self.pager.reclaim_space()?)
+    }
+
+    /// I/O counters of the collection file, to tell storage time apart from deserialization
+    pub fn stats(&self) -> PagerStats {
+        self.pager.stats()
+    }
+
+    pub fn reset_stats(&self) {
+        self.pager.reset_stats();
+    }
+
     pub fn cursor(&self) -> Cursor {
-        Cursor::new(Rc::clone(&self.pager))
+        Cursor::new(Arc::clone(&self.pager), self.file_id)
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn file_id(&self) -> u32 {
+        self.file_id
+    }
 }
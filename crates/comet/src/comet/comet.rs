@@ -3,26 +3,40 @@ use std::{
     error::Error,
     fs,
     io::{self},
+    path::PathBuf,
 };
 
+use llio::io::lock::FileLock;
+
 use crate::{database::database::Database, io::io_config::IoConfig};
 
 pub struct Comet {
     databases: Vec<Database>,
     config: IoConfig,
+    lock: Option<FileLock>,
 }
 
+/// Name of the file in the data directory that is locked while it is in use
+pub const LOCK_FILE_NAME: &str = "LOCK";
+
 impl Comet {
     pub fn new(config: IoConfig) -> Self {
         Comet {
             databases: Vec::new(),
             config,
+            lock: None,
         }
     }
 
     pub fn initialize(&mut self) -> Result<(), Box<dyn Error>> {
         fs::create_dir_all(&self.config.data_dir()[..])?;
 
+        let lock_path = PathBuf::from(&self.config.data_dir()[..]).join(LOCK_FILE_NAME);
+        self.lock = Some(FileLock::acquire(
+            lock_path.to_str().unwrap(),
+            self.config.lock_mode(),
+        )?);
+
         Ok(())
     }
 
@@ -1,26 +1,38 @@
 use std::{error::Error, fs, io, path::PathBuf};
 
-use crate::{collection::collection::Collection, io::io_config::IoConfig};
+use crate::{
+    catalog::catalog::{Catalog, CATALOG_FILE_NAME},
+    collection::collection::Collection,
+    io::io_config::IoConfig,
+};
 
 pub struct Database {
     collections: Vec<Collection>,
     name: String,
     config: IoConfig,
+    catalog: Catalog,
 }
 
 impl Database {
     pub fn new(name: String, config: IoConfig) -> io::Result<Self> {
-        fs::create_dir_all(PathBuf::from(&config.data_dir()[..]).join(&name));
+        let path = PathBuf::from(&config.data_dir()[..]).join(&name);
+        fs::create_dir_all(&path)?;
+        let catalog = match config.read_only() {
+            true => Catalog::open_read_only(path.join(CATALOG_FILE_NAME))?,
+            false => Catalog::open(path.join(CATALOG_FILE_NAME))?,
+        };
 
         Ok(Self {
             name,
             collections: Vec::new(),
             config,
+            catalog,
         })
     }
 
     pub fn create_collection(&mut self, name: String) -> Result<&mut Collection, Box<dyn Error>> {
-        let collection = Collection::new(&self.name, name, self.config.clone())?;
+        let file_id = self.catalog.register(&name)?;
+        let collection = Collection::new(&self.name, name, file_id, self.config.clone())?;
         self.collections.push(collection);
         Ok(self.collections.last_mut().unwrap())
     }
@@ -37,6 +49,11 @@ impl Database {
         &mut self.collections
     }
 
+    /// Ids of the collection files, as used in record ids
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -1,7 +1,12 @@
 use core::str;
-use std::{collections::HashMap, error::Error, mem, ptr};
+use std::{collections::HashMap, error::Error, io::Write, mem};
 
-use trail::{deserialize::Deserialize, field::Field, serialize::Serialize};
+use trail::{
+    deserialize::{take, Deserialize},
+    error,
+    field::{Field, FieldType, FieldValue, FromField, ToField, TypeMismatch},
+    serialize::Serialize,
+};
 
 #[derive(Debug)]
 pub struct Document {
@@ -32,50 +37,86 @@ impl Document {
         Document { map }
     }
 
+    /// A typed value as a document, e.g. a struct deriving trail's `Serialize`
+    pub fn from_value<T: ToField + ?Sized>(value: &T) -> Result<Self, TypeMismatch> {
+        Self::try_from(value.to_field())
+    }
+
+    /// The typed value stored in the document, e.g. a struct deriving trail's `Deserialize`
+    pub fn into_value<T: FromField>(self) -> Result<T, Box<dyn Error>> {
+        T::from_field(self.into())
+    }
+
     pub fn size(&self) -> u32 {
         self.map.size()
     }
 
+    /// Number of bytes [`Document::serialize`] writes, the size of the fields included
+    pub fn serialized_size(&self) -> usize {
+        mem::size_of::<u32>() + self.map.size() as usize
+    }
+
     pub fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
-        let size = self.map.size();
-        let mut buffer = vec![0u8; mem::size_of::<u32>() + size as usize].into_boxed_slice();
+        let mut buffer = Vec::with_capacity(self.serialized_size());
+        self.serialize_into(&mut buffer)?;
 
-        let size = size.to_le_bytes();
-        unsafe { ptr::copy_nonoverlapping(size.as_ptr(), buffer.as_mut_ptr(), size.len()) };
+        Ok(buffer.into_boxed_slice())
+    }
 
-        let data = self.map.serialize()?;
-        unsafe {
-            ptr::copy_nonoverlapping(
-                data.as_ptr(),
-                buffer.as_mut_ptr().add(mem::size_of::<u32>()),
-                data.len(),
-            )
-        };
+    /// Writes the size of the fields and the fields, each of them straight into `w`
+    pub fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        w.write_all(&self.map.size().to_le_bytes())?;
+        self.map.serialize_into(w)
+    }
 
-        Ok(buffer)
+    /// Fails if the buffer is smaller than the serialized document
+    pub fn serialize_into_buffer(&self, mut buffer: &mut [u8]) -> Result<(), Box<dyn Error>> {
+        self.serialize_into(&mut buffer)
     }
 
-    pub fn serialize_into_buffer(&self, buffer: &mut [u8]) -> Result<(), Box<dyn Error>> {
-        let size = self.map.size();
+    pub fn deserialize(src: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let map = HashMap::<String, Field>::deserialize(serialized_fields(src)?)
+            .map_err(|err| err.at(mem::size_of::<u32>()))?;
 
-        let size = size.to_le_bytes();
-        unsafe { ptr::copy_nonoverlapping(size.as_ptr(), buffer.as_mut_ptr(), size.len()) };
+        Ok(Document { map })
+    }
+}
 
-        let data = self.map.serialize()?;
-        unsafe {
-            ptr::copy_nonoverlapping(
-                data.as_ptr(),
-                buffer.as_mut_ptr().add(mem::size_of::<u32>()),
-                data.len(),
-            )
-        };
+/// The fields written by [`Document::serialize`], they must take as many bytes as the size
+/// in front of them says
+pub(crate) fn serialized_fields(src: &[u8]) -> Result<&[u8], Box<dyn Error>> {
+    let offset = mem::size_of::<u32>();
+    let size = u32::deserialize(take(src, 0, offset)?)?;
+    let fields = take(src, offset, size as usize).map_err(|_| error::Error::LengthOverflow {
+        offset: 0,
+        length: size as u64,
+    })?;
+    let end = offset + fields.len();
+    if end < src.len() {
+        let reason = format!("{} bytes follow {size} bytes of fields", src.len() - end);
+        return Err(error::Error::invalid(end, reason).into());
+    }
+
+    Ok(fields)
+}
 
-        Ok(())
+/// A document nested in another one, e.g. as an element of an array of sub-objects
+impl From<Document> for Field {
+    fn from(document: Document) -> Self {
+        Field::map(document.map)
     }
+}
 
-    pub fn deserialize(src: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let map = HashMap::<String, Field>::deserialize(&src[mem::size_of::<u32>()..])?;
+impl TryFrom<Field> for Document {
+    type Error = TypeMismatch;
 
-        Ok(Document { map })
+    fn try_from(field: Field) -> Result<Self, Self::Error> {
+        match field.into_value() {
+            FieldValue::Map(map) => Ok(Document { map }),
+            value => Err(TypeMismatch {
+                expected: FieldType::Map,
+                found: value.field_type(),
+            }),
+        }
     }
 }
@@ -0,0 +1,44 @@
+use std::error::Error;
+
+use trail::field::{FieldRef, MapRef, Members};
+
+use super::document::{serialized_fields, Document};
+
+/// A serialized document read in place, its fields are decoded only when asked for
+///
+/// Looking fields up allocates nothing, so a document can be filtered on a few of its fields
+/// without building all of them.
+#[derive(Debug, Clone, Copy)]
+pub struct DocumentRef<'a> {
+    map: MapRef<'a>,
+}
+
+impl<'a> DocumentRef<'a> {
+    /// A view of the bytes written by [`Document::serialize`]
+    pub fn new(src: &'a [u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            map: MapRef::new(serialized_fields(src)?),
+        })
+    }
+
+    pub fn get_field(&self, key: &str) -> Result<Option<FieldRef<'a>>, Box<dyn Error>> {
+        Ok(self.map.get(key)?)
+    }
+
+    /// The fields in the order they are stored
+    pub fn fields(&self) -> Members<'a> {
+        self.map.iter()
+    }
+
+    /// Decodes the whole document
+    pub fn to_document(&self) -> Result<Document, Box<dyn Error>> {
+        Ok(Document::new_with_fields(self.map.to_map()?))
+    }
+}
+
+/// A sub-document, e.g. read from a map field with [`FieldRef::as_map`]
+impl<'a> From<MapRef<'a>> for DocumentRef<'a> {
+    fn from(map: MapRef<'a>) -> Self {
+        Self { map }
+    }
+}
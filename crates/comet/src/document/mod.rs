@@ -1,3 +1,5 @@
 pub mod document;
+pub mod document_ref;
 
 pub use document::*;
+pub use document_ref::*;
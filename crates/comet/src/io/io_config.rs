@@ -1,6 +1,12 @@
+use llio::{encryption::EncryptionKey, io::lock::LockMode, page::DEFAULT_PAGE_SIZE};
+
 #[derive(Debug, Clone)]
 pub struct IoConfig {
     data_dir: String,
+    page_size: usize,
+    compression: bool,
+    encryption_key: Option<EncryptionKey>,
+    read_only: bool,
 }
 
 impl IoConfig {
@@ -11,15 +17,53 @@ impl IoConfig {
     pub fn data_dir(&self) -> Box<str> {
         Box::from(self.data_dir.as_str())
     }
+
+    /// Page size of newly created collection files
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Whether newly created collection files compress their pages
+    pub fn compression(&self) -> bool {
+        self.compression
+    }
+
+    /// Key the collection files are encrypted with
+    pub fn encryption_key(&self) -> Option<&EncryptionKey> {
+        self.encryption_key.as_ref()
+    }
+
+    /// Whether the data directory is shared with other readers instead of owned exclusively
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn lock_mode(&self) -> LockMode {
+        if self.read_only {
+            LockMode::Shared
+        } else {
+            LockMode::Exclusive
+        }
+    }
 }
 
 pub struct IoConfigBuilder {
     data_dir: Option<String>,
+    page_size: Option<usize>,
+    compression: bool,
+    encryption_key: Option<EncryptionKey>,
+    read_only: bool,
 }
 
 impl IoConfigBuilder {
     pub fn new() -> Self {
-        Self { data_dir: None }
+        Self {
+            data_dir: None,
+            page_size: None,
+            compression: false,
+            encryption_key: None,
+            read_only: false,
+        }
     }
 
     pub fn data_dir(mut self, data_dir: String) -> Self {
@@ -27,9 +71,36 @@ impl IoConfigBuilder {
         self
     }
 
+    /// A power of two between 4 KiB and 64 KiB, existing collections keep their page size
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Encrypts newly created collections, the same key has to be supplied to open them again
+    pub fn encryption_key(mut self, key: EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Opens the data directory alongside other readers, writes are refused
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
     pub fn build(self) -> IoConfig {
         IoConfig {
             data_dir: self.data_dir.unwrap(),
+            page_size: self.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+            compression: self.compression,
+            encryption_key: self.encryption_key,
+            read_only: self.read_only,
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod catalog;
 pub mod collection;
 pub mod comet;
 pub mod cursor;
@@ -0,0 +1,73 @@
+use std::fs;
+
+use comet::{
+    catalog::catalog::{Catalog, CATALOG_FILE_NAME},
+    comet::Comet,
+    document::Document,
+    io::io_config::IoConfig,
+};
+use trail::field::Field;
+
+mod common;
+
+use common::data_dir;
+
+#[test]
+pub fn file_ids_are_kept_across_restarts() {
+    let dir = data_dir("catalog_registry");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(CATALOG_FILE_NAME);
+
+    let mut catalog = Catalog::open(&path).unwrap();
+    assert_eq!(catalog.register("users").unwrap(), 1);
+    assert_eq!(catalog.register("orders").unwrap(), 2);
+    assert_eq!(catalog.register("users").unwrap(), 1);
+
+    let mut catalog = Catalog::open(&path).unwrap();
+    assert_eq!(catalog.file_id("orders"), Some(2));
+    assert_eq!(catalog.file_name(1), Some("users"));
+    assert_eq!(catalog.register("items").unwrap(), 3);
+    assert_eq!(catalog.files().count(), 3);
+
+    // a read-only catalog knows the files, but does not take new ones
+    let mut catalog = Catalog::open_read_only(&path).unwrap();
+    assert_eq!(catalog.register("orders").unwrap(), 2);
+    assert!(catalog.register("payments").is_err());
+    assert_eq!(Catalog::open(&path).unwrap().file_id("payments"), None);
+
+    fs::write(&path, [1, 0, 0, 0, 9, 0, 0, 0, b'x']).unwrap();
+    assert!(Catalog::open(&path).is_err());
+}
+
+#[test]
+pub fn documents_are_found_by_record_id() {
+    let dir = data_dir("catalog_documents");
+    let config = IoConfig::builder()
+        .data_dir(dir.to_str().unwrap().to_string())
+        .build();
+    let mut comet = Comet::new(config);
+    comet.initialize().unwrap();
+
+    let database = comet.create_database("shop".to_string()).unwrap();
+    database.create_collection("users".to_string()).unwrap();
+    let orders = database.create_collection("orders".to_string()).unwrap();
+    assert_eq!(orders.file_id(), 2);
+
+    let mut ids = Vec::new();
+    for id in 0..20u32 {
+        let mut document = Document::new();
+        document.append_field("id".to_string(), Field::uint32(id));
+        ids.push(orders.insert_document(&document).unwrap());
+    }
+
+    let document = orders.document(&ids[13]).unwrap();
+    assert!(document.get_field("id").unwrap() == &Field::uint32(13));
+
+    let mut cursor = orders.cursor();
+    cursor.next_document().unwrap();
+    assert_eq!(cursor.record_id(), ids[1]);
+
+    let users = database.collection("users").unwrap();
+    assert_eq!(users.file_id(), 1);
+    assert!(users.document(&ids[0]).is_err());
+}
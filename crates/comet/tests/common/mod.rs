@@ -0,0 +1,8 @@
+use std::{fs, path::PathBuf};
+
+/// A data directory for a test in the target directory, the one of an earlier run is removed
+pub fn data_dir(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&path);
+    path
+}
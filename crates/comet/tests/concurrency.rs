@@ -0,0 +1,49 @@
+use std::{sync::Arc, thread};
+
+use comet::{collection::Collection, document::Document};
+use llio::{io::memory::MemoryFileIo, pager::Pager};
+use trail::field::Field;
+
+#[test]
+pub fn collections_are_read_from_several_threads() {
+    let collection = Collection::from_pager("users".to_string(), Pager::new(MemoryFileIo::new()));
+    let record_ids = (0..300u32)
+        .map(|id| {
+            let mut document = Document::new();
+            document
+                .append_field("id".to_string(), Field::uint32(id))
+                .append_field(
+                    "name".to_string(),
+                    Field::string("x".repeat((id as usize % 30) * 200)),
+                );
+            collection.insert_document(&document).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let collection = Arc::new(collection);
+    let record_ids = Arc::new(record_ids);
+
+    let handlers = (0..4)
+        .map(|handler| {
+            let collection = Arc::clone(&collection);
+            let record_ids = Arc::clone(&record_ids);
+            thread::spawn(move || {
+                if handler % 2 == 0 {
+                    let mut cursor = collection.cursor();
+                    for id in 0..300u32 {
+                        let document = cursor.read_current_document().unwrap();
+                        assert!(document.get_field("id").unwrap() == &Field::uint32(id));
+                        cursor.next_document().unwrap();
+                    }
+                } else {
+                    for (id, record_id) in record_ids.iter().enumerate().rev() {
+                        let document = collection.document(record_id).unwrap();
+                        assert!(document.get_field("id").unwrap() == &Field::uint32(id as u32));
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handler in handlers {
+        handler.join().unwrap();
+    }
+}
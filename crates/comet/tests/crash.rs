@@ -0,0 +1,112 @@
+use std::error::Error;
+
+use comet::{collection::Collection, document::Document};
+use llio::{
+    io::{
+        faulty::{FaultConfig, FaultyFileIo, Rng},
+        memory::MemoryFileIo,
+    },
+    pager::Pager,
+};
+use trail::field::Field;
+
+fn document(id: u32, payload: &str) -> Document {
+    let mut document = Document::new();
+    document
+        .append_field("id".to_string(), Field::uint32(id))
+        .append_field("payload".to_string(), Field::string(payload.to_string()));
+    document
+}
+
+/// Inserts random documents until the storage fails, returns the documents that were made
+/// durable before and the error that stopped the workload
+fn run_workload(
+    collection: &mut Collection,
+    rng: &mut Rng,
+) -> (Vec<(u32, String)>, Box<dyn Error>) {
+    let mut inserted = Vec::new();
+    let mut durable = 0;
+
+    let err = loop {
+        let id = inserted.len() as u32;
+        // every eighth document spans several pages
+        let max_len = if rng.below(8) == 0 { 12_000 } else { 600 };
+        let payload = (0..rng.below(max_len))
+            .map(|_| (b'a' + rng.below(26) as u8) as char)
+            .collect::<String>();
+
+        if let Err(err) = collection.insert_document(&document(id, &payload)) {
+            break err;
+        }
+        inserted.push((id, payload));
+
+        if rng.below(4) == 0 {
+            if let Err(err) = collection.flush() {
+                break err;
+            }
+            durable = inserted.len();
+        }
+    };
+
+    inserted.truncate(durable);
+    (inserted, err)
+}
+
+fn verify(disk: &MemoryFileIo, durable: &[(u32, String)]) {
+    let collection = Collection::from_pager("users".to_string(), Pager::new(disk.clone()));
+    let mut cursor = collection.cursor();
+
+    for (id, payload) in durable {
+        let document = cursor.read_current_document().unwrap();
+        assert!(document.get_field("id").unwrap() == &Field::uint32(*id));
+        assert!(document.get_field("payload").unwrap() == &Field::string(payload.clone()));
+
+        cursor.next_document().unwrap();
+    }
+}
+
+#[test]
+pub fn durable_documents_survive_crashes() {
+    for seed in 0..64 {
+        let mut rng = Rng::new(seed);
+        let disk = MemoryFileIo::new();
+
+        let mut config = FaultConfig::builder()
+            .crash_after_writes(1 + rng.below(80))
+            .drop_unsynced(rng.below(2) == 0);
+        if rng.below(2) == 0 {
+            config = config.torn_write(512 * (1 + rng.below(7)) as usize);
+        }
+
+        let io = FaultyFileIo::new(disk.clone(), config.build());
+        let mut collection = Collection::from_pager("users".to_string(), Pager::new(io));
+
+        let (durable, _) = run_workload(&mut collection, &mut rng);
+        drop(collection);
+
+        verify(&disk, &durable);
+    }
+}
+
+#[test]
+pub fn read_errors_are_reported() {
+    for seed in 0..16 {
+        let mut rng = Rng::new(seed);
+        let disk = MemoryFileIo::new();
+
+        let config = FaultConfig::builder()
+            .read_error_every(2 + rng.below(30))
+            .build();
+        let io = FaultyFileIo::new(disk.clone(), config);
+        // without a buffer pool, every page is read from the storage
+        let pager = Pager::with_pool_size(io, 0);
+        let mut collection = Collection::from_pager("users".to_string(), pager);
+
+        let (durable, err) = run_workload(&mut collection, &mut rng);
+        drop(collection);
+
+        // the workload was stopped by an injected read failure, not by anything else
+        assert!(err.to_string().contains("simulated read error"), "{err}");
+        verify(&disk, &durable);
+    }
+}
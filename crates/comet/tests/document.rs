@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use comet::{
+    collection::Collection,
+    document::{Document, DocumentRef},
+};
+use llio::{io::memory::MemoryFileIo, pager::Pager};
+use trail::{
+    deserialize::Deserialize,
+    field::{Field, FieldType},
+    serialize::Serialize,
+};
+
+fn comment(author: &str, likes: u32) -> Document {
+    let mut comment = Document::new();
+    comment
+        .append_field("author".to_string(), Field::from(author))
+        .append_field("likes".to_string(), Field::from(likes));
+    comment
+}
+
+#[test]
+pub fn documents_hold_arrays_of_values_and_sub_documents() {
+    let collection = Collection::from_pager("posts".to_string(), Pager::new(MemoryFileIo::new()));
+
+    let mut post = Document::new();
+    post.append_field(
+        "tags".to_string(),
+        Field::array(vec![Field::from("rust"), Field::from("storage")]),
+    )
+    .append_field(
+        "comments".to_string(),
+        Field::array(vec![comment("alice", 3).into(), comment("bob", 0).into()]),
+    )
+    .append_field("empty".to_string(), Field::array(Vec::new()));
+    let record_id = collection.insert_document(&post).unwrap();
+
+    let post = collection.document(&record_id).unwrap();
+    let tags = post.get_field("tags").unwrap().value_as_array().unwrap();
+    assert_eq!(tags, &vec![Field::from("rust"), Field::from("storage")]);
+    assert_eq!(
+        post.get_field("empty")
+            .unwrap()
+            .value_as_array()
+            .map(Vec::len),
+        Some(0)
+    );
+
+    let comments = post
+        .get_field("comments")
+        .unwrap()
+        .value_as_array()
+        .unwrap();
+    let bob = Document::try_from(comments[1].clone()).unwrap();
+    assert_eq!(bob.get_field("author"), Some(&Field::from("bob")));
+    assert_eq!(bob.get_field("likes").unwrap().get::<u32>(), Ok(0));
+
+    assert!(Document::try_from(Field::from("not a document")).is_err());
+}
+
+#[test]
+pub fn documents_are_read_in_place() {
+    let collection = Collection::from_pager("posts".to_string(), Pager::new(MemoryFileIo::new()));
+
+    for id in 0..20u32 {
+        let mut post = Document::new();
+        post.append_field("id".to_string(), Field::from(id))
+            .append_field("title".to_string(), Field::from(format!("post {id}")))
+            .append_field(
+                "comments".to_string(),
+                Field::array(vec![comment("alice", id).into()]),
+            );
+        collection.insert_document(&post).unwrap();
+    }
+
+    // pick the posts whose first comment has over 15 likes, decoding only the matches
+    let mut cursor = collection.cursor();
+    let mut titles = Vec::new();
+    for _ in 0..20 {
+        let title = cursor
+            .with_current_document(|post| {
+                let comments = post.get_field("comments").unwrap()?.as_array()?;
+                let comment = DocumentRef::from(comments.get(0).unwrap()?.as_map()?);
+                let likes = comment.get_field("likes").unwrap()?.to_field().unwrap();
+                (likes.get::<u32>().unwrap() > 15).then(|| {
+                    post.get_field("title")
+                        .unwrap()?
+                        .as_str()
+                        .map(str::to_string)
+                })?
+            })
+            .unwrap();
+        titles.extend(title);
+        cursor.next_document().unwrap();
+    }
+    assert_eq!(titles, ["post 16", "post 17", "post 18", "post 19"]);
+
+    let buffer = comment("bob", 1).serialize().unwrap();
+    let post = DocumentRef::new(&buffer).unwrap();
+    let fields: Vec<_> = post.fields().map(Result::unwrap).collect();
+    assert_eq!(fields.len(), 2);
+    assert!(fields
+        .iter()
+        .any(|(name, field)| *name == "likes" && field.field_type() == FieldType::UInt32));
+    assert!(post.get_field("missing").unwrap().is_none());
+    assert_eq!(
+        post.to_document().unwrap().get_field("author"),
+        Some(&Field::from("bob"))
+    );
+
+    assert!(DocumentRef::new(&buffer[..buffer.len() - 1]).is_err());
+
+    // both decoders expect the fields to take exactly as many bytes as the size in front says
+    let decoded = |src: &[u8]| {
+        let by_ref = DocumentRef::new(src).and_then(|document| document.to_document());
+        (by_ref.is_ok(), Document::deserialize(src).is_ok())
+    };
+    assert_eq!(decoded(&buffer), (true, true));
+    let mut padded = buffer.to_vec();
+    padded.push(0);
+    assert_eq!(decoded(&padded), (false, false));
+    let mut resized = buffer.to_vec();
+    for size in [0, buffer.len() as u32 - 5, buffer.len() as u32] {
+        resized[..4].copy_from_slice(&size.to_le_bytes());
+        assert_eq!(decoded(&resized), (false, false));
+    }
+
+    let mut page = vec![0u8; buffer.len()];
+    comment("bob", 1).serialize_into_buffer(&mut page).unwrap();
+    assert_eq!(DocumentRef::new(&page).unwrap().fields().count(), 2);
+    assert!(comment("bob", 1)
+        .serialize_into_buffer(&mut page[..buffer.len() - 1])
+        .is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Post {
+    title: String,
+    #[trail(rename = "tag_list")]
+    tags: Vec<String>,
+    comments: Vec<Comment>,
+    #[trail(default)]
+    views: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Comment {
+    author: String,
+    likes: u32,
+}
+
+#[test]
+pub fn typed_values_are_stored_as_documents() {
+    let collection = Collection::from_pager("posts".to_string(), Pager::new(MemoryFileIo::new()));
+
+    let post = Post {
+        title: "hello".to_string(),
+        tags: vec!["rust".to_string()],
+        comments: vec![Comment {
+            author: "alice".to_string(),
+            likes: 3,
+        }],
+        views: 0,
+    };
+    let record_id = collection
+        .insert_document(&Document::from_value(&post).unwrap())
+        .unwrap();
+
+    let document = collection.document(&record_id).unwrap();
+    assert_eq!(
+        document.get_field("tag_list"),
+        Some(&Field::array(vec![Field::from("rust")]))
+    );
+    assert_eq!(document.into_value::<Post>().unwrap(), post);
+
+    // documents written before a member was added still read
+    let mut comment = comment("bob", 1);
+    comment.remove_field("likes");
+    let mut old = Document::new();
+    old.append_field("title".to_string(), Field::from("old"))
+        .append_field("tag_list".to_string(), Field::array(Vec::new()))
+        .append_field("comments".to_string(), Field::array(Vec::new()));
+    assert_eq!(old.into_value::<Post>().unwrap().views, 0);
+    assert!(comment.into_value::<Comment>().is_err());
+    assert!(Document::from_value(&3u32).is_err());
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Profile {
+    name: String,
+    #[serde(flatten)]
+    settings: HashMap<String, bool>,
+}
+
+#[test]
+pub fn serde_types_are_stored_as_documents() {
+    let collection =
+        Collection::from_pager("profiles".to_string(), Pager::new(MemoryFileIo::new()));
+
+    let profile = Profile {
+        name: "alice".to_string(),
+        settings: HashMap::from([("dark_mode".to_string(), true)]),
+    };
+    let document = Document::try_from(trail::serde::to_field(&profile).unwrap()).unwrap();
+    let record_id = collection.insert_document(&document).unwrap();
+
+    let document = collection.document(&record_id).unwrap();
+    assert_eq!(document.get_field("dark_mode"), Some(&Field::bool(true)));
+    let stored: Profile = trail::serde::from_field(document.into()).unwrap();
+    assert_eq!(stored, profile);
+}
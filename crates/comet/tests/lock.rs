@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use comet::{comet::Comet, io::io_config::IoConfig};
+
+mod common;
+
+use common::data_dir;
+
+fn comet(data_dir: &Path, read_only: bool) -> Comet {
+    Comet::new(
+        IoConfig::builder()
+            .data_dir(data_dir.to_str().unwrap().to_string())
+            .read_only(read_only)
+            .build(),
+    )
+}
+
+#[test]
+pub fn data_directory_is_used_by_one_writer() {
+    let data_dir = data_dir("comet_lock_writer");
+
+    let mut first = comet(&data_dir, false);
+    first.initialize().unwrap();
+
+    let mut second = comet(&data_dir, false);
+    let err = second.initialize().unwrap_err();
+    assert!(err.to_string().starts_with("database is in use"));
+    assert!(comet(&data_dir, true).initialize().is_err());
+
+    drop(first);
+    assert!(second.initialize().is_ok());
+}
+
+#[test]
+pub fn readers_share_the_data_directory() {
+    let data_dir = data_dir("comet_lock_readers");
+    comet(&data_dir, false).initialize().unwrap();
+
+    let mut first = comet(&data_dir, true);
+    first.initialize().unwrap();
+    let mut second = comet(&data_dir, true);
+    second.initialize().unwrap();
+
+    assert!(comet(&data_dir, false).initialize().is_err());
+}
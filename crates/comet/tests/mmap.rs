@@ -0,0 +1,48 @@
+use std::{fs, path::PathBuf};
+
+use comet::{collection::Collection, document::Document};
+use llio::{
+    header::FileKind,
+    io::{
+        direct::DirectFileIo,
+        mmap::{AccessPattern, MmapFileIo},
+    },
+    pager::Pager,
+};
+use trail::field::Field;
+
+#[test]
+pub fn cursors_run_on_mapped_collections() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("mmap_collection");
+    let _ = fs::remove_file(&path);
+    let path = path.to_str().unwrap();
+
+    let io = DirectFileIo::new(path, FileKind::Collection).unwrap();
+    let collection = Collection::from_pager("users".to_string(), Pager::new(io));
+    for id in 0..100u32 {
+        let mut document = Document::new();
+        document
+            .append_field("id".to_string(), Field::uint32(id))
+            .append_field(
+                "name".to_string(),
+                Field::string("x".repeat(id as usize * 50)),
+            );
+        collection.insert_document(&document).unwrap();
+    }
+    collection.flush().unwrap();
+    drop(collection);
+
+    let io = MmapFileIo::new(path, FileKind::Collection).unwrap();
+    io.advise(AccessPattern::Sequential).unwrap();
+    let collection = Collection::from_pager("users".to_string(), Pager::new(io));
+
+    let mut cursor = collection.cursor();
+    for id in 0..100u32 {
+        let document = cursor.read_current_document().unwrap();
+        assert!(document.get_field("id").unwrap() == &Field::uint32(id));
+        cursor.next_document().unwrap();
+    }
+
+    // mapped collections are read-only
+    assert!(collection.cursor().remove_current_document().is_err());
+}
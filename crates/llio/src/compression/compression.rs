@@ -0,0 +1,149 @@
+//! A small LZ77 codec in the spirit of the LZ4 block format.
+//!
+//! The input is encoded as a sequence of `[token][literal length][literals][offset][match length]`
+//! entries, where the token holds 4-bit literal and match lengths that are extended by runs of
+//! 255-valued bytes. The last sequence only carries literals.
+
+const MIN_MATCH: usize = 4;
+const MAX_OFFSET: usize = u16::MAX as usize;
+const HASH_BITS: u32 = 12;
+
+fn read_u32(input: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(input[pos..(pos + 4)].try_into().unwrap())
+}
+
+fn hash(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+fn write_length(output: &mut Vec<u8>, mut len: usize) {
+    while len >= 255 {
+        output.push(255);
+        len -= 255;
+    }
+    output.push(len as u8);
+}
+
+fn write_sequence(output: &mut Vec<u8>, literals: &[u8], last_match: Option<(usize, usize)>) {
+    let match_len = last_match.map(|(_, len)| len - MIN_MATCH).unwrap_or(0);
+    let token = ((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8;
+    output.push(token);
+
+    if literals.len() >= 15 {
+        write_length(output, literals.len() - 15);
+    }
+    output.extend_from_slice(literals);
+
+    if let Some((offset, _)) = last_match {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            write_length(output, match_len - 15);
+        }
+    }
+}
+
+/// Compresses `input`, returns `None` if the result would not be smaller
+pub fn compress(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() / 2);
+    let mut table = vec![0usize; 1 << HASH_BITS];
+
+    let mut anchor = 0;
+    let mut pos = 0;
+    while pos + MIN_MATCH <= input.len() {
+        let sequence = read_u32(input, pos);
+        let slot = &mut table[hash(sequence)];
+        // positions are stored off by one, so that zero means "empty"
+        let candidate = slot.checked_sub(1);
+        *slot = pos + 1;
+
+        let candidate = candidate.filter(|&candidate| {
+            pos - candidate <= MAX_OFFSET && read_u32(input, candidate) == sequence
+        });
+
+        let Some(candidate) = candidate else {
+            pos += 1;
+            continue;
+        };
+
+        let mut len = MIN_MATCH;
+        while pos + len < input.len() && input[candidate + len] == input[pos + len] {
+            len += 1;
+        }
+
+        write_sequence(
+            &mut output,
+            &input[anchor..pos],
+            Some((pos - candidate, len)),
+        );
+        pos += len;
+        anchor = pos;
+
+        if output.len() >= input.len() {
+            return None;
+        }
+    }
+
+    write_sequence(&mut output, &input[anchor..], None);
+
+    (output.len() < input.len()).then_some(output)
+}
+
+fn read_length(input: &[u8], pos: &mut usize, mut len: usize) -> Option<usize> {
+    loop {
+        let byte = *input.get(*pos)?;
+        *pos += 1;
+        len = len.checked_add(byte as usize)?;
+        if byte != 255 {
+            return Some(len);
+        }
+    }
+}
+
+/// Decompresses data produced by [`compress`], `None` means the input is malformed
+/// or does not decompress into exactly `len` bytes
+pub fn decompress(input: &[u8], len: usize) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(len);
+
+    let mut pos = 0;
+    while pos < input.len() {
+        let token = input[pos];
+        pos += 1;
+
+        let mut literals = (token >> 4) as usize;
+        if literals == 15 {
+            literals = read_length(input, &mut pos, literals)?;
+        }
+        let literals = input.get(pos..pos.checked_add(literals)?)?;
+        if output.len() + literals.len() > len {
+            return None;
+        }
+        output.extend_from_slice(literals);
+        pos += literals.len();
+
+        // the last sequence has no match
+        if pos == input.len() {
+            break;
+        }
+
+        let offset = u16::from_le_bytes(input.get(pos..(pos + 2))?.try_into().unwrap()) as usize;
+        pos += 2;
+
+        let mut match_len = (token & 0x0f) as usize;
+        if match_len == 15 {
+            match_len = read_length(input, &mut pos, match_len)?;
+        }
+        let match_len = match_len + MIN_MATCH;
+
+        if offset == 0 || offset > output.len() || output.len() + match_len > len {
+            return None;
+        }
+
+        // matches may overlap the bytes they produce
+        let start = output.len() - offset;
+        for i in 0..match_len {
+            output.push(output[start + i]);
+        }
+    }
+
+    (output.len() == len).then_some(output)
+}
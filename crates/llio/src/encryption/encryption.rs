@@ -0,0 +1,103 @@
+use std::fmt;
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+
+pub const ENCRYPTION_KEY_SIZE: usize = 32;
+/// Page index (u64) followed by 16 random bytes, so that rewriting a page never reuses a nonce
+const NONCE_SIZE: usize = 24;
+const TAG_SIZE: usize = 16;
+/// Bytes an encrypted page takes on top of its plaintext
+pub const ENCRYPTION_OVERHEAD: usize = NONCE_SIZE + TAG_SIZE;
+
+const KEY_CHECK_PLAINTEXT: &[u8; 16] = b"vanilladb key ok";
+/// Encrypted `KEY_CHECK_PLAINTEXT` stored in the file header
+pub const KEY_CHECK_SIZE: usize = ENCRYPTION_OVERHEAD + KEY_CHECK_PLAINTEXT.len();
+
+/// Page index the key check is encrypted for, real pages never get that far
+const KEY_CHECK_PAGE: u64 = u64::MAX;
+
+/// A 256-bit key, kept out of debug output
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; ENCRYPTION_KEY_SIZE]);
+
+impl EncryptionKey {
+    pub fn new(key: [u8; ENCRYPTION_KEY_SIZE]) -> Self {
+        Self(key)
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptionKey(..)")
+    }
+}
+
+/// Authenticated page encryption with XChaCha20-Poly1305
+#[derive(Clone)]
+pub struct Cipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl Cipher {
+    pub fn new(key: &EncryptionKey) -> Self {
+        Self {
+            cipher: XChaCha20Poly1305::new(&key.0.into()),
+        }
+    }
+
+    /// Encrypts a page, the page index is bound to the result and checked on decryption
+    pub fn encrypt(&self, idx: u64, plaintext: &[u8]) -> Vec<u8> {
+        let mut nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        nonce[..8].copy_from_slice(&idx.to_le_bytes());
+
+        let aad = idx.to_le_bytes();
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .expect("page is too large to be encrypted");
+
+        let mut bytes = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        bytes
+    }
+
+    /// Returns `None` if the data was tampered with, encrypted for another page or with another key
+    pub fn decrypt(&self, idx: u64, bytes: &[u8]) -> Option<Vec<u8>> {
+        if bytes.len() < ENCRYPTION_OVERHEAD || bytes[..8] != idx.to_le_bytes() {
+            return None;
+        }
+
+        let aad = idx.to_le_bytes();
+        self.cipher
+            .decrypt(
+                XNonce::from_slice(&bytes[..NONCE_SIZE]),
+                Payload {
+                    msg: &bytes[NONCE_SIZE..],
+                    aad: &aad,
+                },
+            )
+            .ok()
+    }
+
+    pub fn key_check(&self) -> [u8; KEY_CHECK_SIZE] {
+        self.encrypt(KEY_CHECK_PAGE, KEY_CHECK_PLAINTEXT)
+            .try_into()
+            .unwrap()
+    }
+
+    /// Whether `key_check` was produced with the same key
+    pub fn verify_key_check(&self, key_check: &[u8]) -> bool {
+        self.decrypt(KEY_CHECK_PAGE, key_check)
+            .is_some_and(|plaintext| plaintext == KEY_CHECK_PLAINTEXT)
+    }
+}
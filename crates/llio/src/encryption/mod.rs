@@ -0,0 +1,3 @@
+pub mod encryption;
+
+pub use encryption::*;
@@ -0,0 +1,184 @@
+use std::{fmt, io};
+
+use crate::header::FileKind;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors produced by the page I/O layer
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be opened or created
+    Open {
+        path: String,
+        source: io::Error,
+    },
+    /// The file metadata could not be read
+    Stat {
+        path: String,
+        source: io::Error,
+    },
+    /// Another process holds a conflicting lock on the file
+    Locked {
+        path: String,
+    },
+    /// The file could not be locked
+    Lock {
+        path: String,
+        source: io::Error,
+    },
+    /// The file was opened with a shared lock, which only allows reading
+    ReadOnly,
+    /// The file could not be mapped into memory
+    Map(io::Error),
+    /// The io_uring instance could not be created or submitted to
+    Ring(io::Error),
+    /// The submission queue has no room for another operation
+    RingFull,
+    Read {
+        page: u64,
+        source: io::Error,
+    },
+    /// The page was read only partially
+    ShortRead {
+        page: u64,
+        read: usize,
+    },
+    Write {
+        page: u64,
+        source: io::Error,
+    },
+    /// The page was written only partially
+    ShortWrite {
+        page: u64,
+        written: usize,
+    },
+    Sync(io::Error),
+    Truncate(io::Error),
+    /// File space could not be allocated or given back
+    Allocate(io::Error),
+    /// The file does not start with a data file header
+    InvalidMagic,
+    /// The file was written by a newer (or unknown) version of the format
+    UnsupportedVersion(u16),
+    /// The file holds something else than what it was opened as
+    FileKindMismatch {
+        expected: FileKind,
+        found: u8,
+    },
+    /// The page size is not a power of two between 4 KiB and 64 KiB
+    InvalidPageSize(usize),
+    /// The file is shorter than the header pages its page size calls for
+    TruncatedFile {
+        len: usize,
+    },
+    /// The file uses features this build does not know about
+    UnsupportedFeatures(u64),
+    /// The stored page does not decompress into a whole page
+    Decompress {
+        page: u64,
+    },
+    /// The page map of a compressed or encrypted file could not be parsed
+    CorruptPageMap,
+    /// The file is encrypted but no key was given
+    KeyRequired,
+    /// The key does not match the one the file was encrypted with
+    WrongKey,
+    /// The stored page failed authentication
+    Decrypt {
+        page: u64,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open { path, source } => write!(f, "failed to open \"{path}\": {source}"),
+            Self::Stat { path, source } => write!(f, "failed to stat \"{path}\": {source}"),
+            Self::Locked { path } => {
+                write!(
+                    f,
+                    "database is in use: \"{path}\" is locked by another process"
+                )
+            }
+            Self::Lock { path, source } => write!(f, "failed to lock \"{path}\": {source}"),
+            Self::ReadOnly => write!(f, "the file is opened for reading only"),
+            Self::Map(source) => write!(f, "failed to map the file: {source}"),
+            Self::Ring(source) => write!(f, "io_uring failure: {source}"),
+            Self::RingFull => write!(f, "io_uring submission queue is full"),
+            Self::Read { page, source } => write!(f, "failed to read page {page}: {source}"),
+            Self::ShortRead { page, read } => {
+                write!(f, "short read of page {page}: got {read} bytes")
+            }
+            Self::Write { page, source } => write!(f, "failed to write page {page}: {source}"),
+            Self::ShortWrite { page, written } => {
+                write!(f, "short write of page {page}: wrote {written} bytes")
+            }
+            Self::Sync(source) => write!(f, "failed to sync: {source}"),
+            Self::Truncate(source) => write!(f, "failed to truncate: {source}"),
+            Self::Allocate(source) => write!(f, "failed to allocate file space: {source}"),
+            Self::InvalidMagic => write!(f, "not a data file: invalid magic number"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            }
+            Self::FileKindMismatch { expected, found } => match FileKind::try_from(*found) {
+                Ok(found) => write!(f, "expected a {expected} file, found a {found} file"),
+                Err(found) => write!(f, "expected a {expected} file, found unknown kind {found}"),
+            },
+            Self::InvalidPageSize(page_size) => write!(f, "invalid page size {page_size}"),
+            Self::TruncatedFile { len } => {
+                write!(
+                    f,
+                    "the file is truncated: {len} bytes do not hold its header"
+                )
+            }
+            Self::UnsupportedFeatures(features) => {
+                write!(f, "unsupported file features {features:#x}")
+            }
+            Self::Decompress { page } => {
+                write!(f, "page {page} is corrupted: decompression failed")
+            }
+            Self::CorruptPageMap => write!(f, "the page map is corrupted"),
+            Self::KeyRequired => write!(f, "the file is encrypted, an encryption key is required"),
+            Self::WrongKey => write!(f, "wrong encryption key"),
+            Self::Decrypt { page } => {
+                write!(f, "page {page} is corrupted: authentication failed")
+            }
+            Self::Io(source) => source.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Open { source, .. }
+            | Self::Stat { source, .. }
+            | Self::Lock { source, .. }
+            | Self::Read { source, .. }
+            | Self::Write { source, .. }
+            | Self::Map(source)
+            | Self::Ring(source)
+            | Self::Sync(source)
+            | Self::Truncate(source)
+            | Self::Allocate(source)
+            | Self::Io(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Io(err) => err,
+            err => io::Error::other(err),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/// Sequence number (u64) + total pages (u64) + last data page (u64) + first free list page (u64)
+pub const CHECKPOINT_SIZE: usize = 32;
+
+/// A point at which every page of a file was durable, recovery starts from the latest one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    sequence: u64,
+    total_pages: u64,
+    last_data_page: u64,
+    free_list: u64,
+}
+
+impl Checkpoint {
+    /// Sequence numbers start at 1, a zeroed marker means that no checkpoint was taken
+    pub fn new(sequence: u64, total_pages: u64, last_data_page: u64, free_list: u64) -> Self {
+        Self {
+            sequence: sequence.max(1),
+            total_pages,
+            last_data_page,
+            free_list,
+        }
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Pages the file had when the checkpoint was taken
+    pub fn total_pages(&self) -> u64 {
+        self.total_pages
+    }
+
+    /// The page new records were appended to
+    pub fn last_data_page(&self) -> u64 {
+        self.last_data_page
+    }
+
+    /// The first of the pages listing the free pages at the time of the checkpoint,
+    /// page 0 is always a data page, so it means that there were none
+    pub fn free_list(&self) -> u64 {
+        self.free_list
+    }
+
+    pub fn to_bytes(&self) -> [u8; CHECKPOINT_SIZE] {
+        let mut bytes = [0u8; CHECKPOINT_SIZE];
+        bytes[0..8].copy_from_slice(&self.sequence.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.total_pages.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.last_data_page.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.free_list.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; CHECKPOINT_SIZE]) -> Option<Self> {
+        let sequence = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        (sequence != 0).then(|| Self {
+            sequence,
+            total_pages: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            last_data_page: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            free_list: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    encryption::KEY_CHECK_SIZE,
+    error::{Error, Result},
+    page::is_valid_page_size,
+};
+
+use super::{
+    checkpoint::{Checkpoint, CHECKPOINT_SIZE},
+    kind::FileKind,
+};
+
+pub const FILE_MAGIC: [u8; 8] = *b"VANILLA\0";
+/// Version of the on-disk format written by this build
+pub const FORMAT_VERSION: u16 = 2;
+/// Oldest format this build reads, version 1 pages had a 4-byte header
+pub const MIN_FORMAT_VERSION: u16 = 2;
+/// Pages are compressed and stored in extents tracked by a page map
+pub const FEATURE_COMPRESSION: u64 = 1 << 0;
+/// Pages are encrypted and stored in extents tracked by a page map
+pub const FEATURE_ENCRYPTION: u64 = 1 << 1;
+/// Feature flags this build knows how to read
+pub const SUPPORTED_FEATURES: u64 = FEATURE_COMPRESSION | FEATURE_ENCRYPTION;
+/// Number of pages reserved for the header at the start of every data file
+pub const HEADER_PAGES: u64 = 1;
+
+/// Magic (8 bytes) + format version (u16) + file kind (u8) + reserved byte + page size (u32)
+/// + creation time (u64) + feature flags (u64) + page map offset (u64) + page map length (u32)
+/// + encryption key check + last checkpoint
+const FILE_HEADER_SIZE: usize = 44 + KEY_CHECK_SIZE + CHECKPOINT_SIZE;
+
+/// Contents of page 0 of a data file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHeader {
+    kind: FileKind,
+    format_version: u16,
+    page_size: u32,
+    created_at: u64,
+    features: u64,
+    page_map: (u64, u32),
+    key_check: [u8; KEY_CHECK_SIZE],
+    checkpoint: Option<Checkpoint>,
+}
+
+impl FileHeader {
+    pub fn new(kind: FileKind, page_size: usize, features: u64) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            kind,
+            format_version: FORMAT_VERSION,
+            page_size: page_size as u32,
+            created_at,
+            features,
+            page_map: (0, 0),
+            key_check: [0u8; KEY_CHECK_SIZE],
+            checkpoint: None,
+        }
+    }
+
+    pub fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    pub fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Seconds since the unix epoch
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    pub fn features(&self) -> u64 {
+        self.features
+    }
+
+    pub fn has_feature(&self, feature: u64) -> bool {
+        self.features & feature != 0
+    }
+
+    /// Offset and length of the last committed page map, zero-sized if there is none
+    pub fn page_map(&self) -> (u64, u32) {
+        self.page_map
+    }
+
+    pub fn set_page_map(&mut self, offset: u64, len: u32) {
+        self.page_map = (offset, len);
+    }
+
+    /// Lets the encryption key be verified before any page is read
+    pub fn key_check(&self) -> &[u8] {
+        &self.key_check
+    }
+
+    pub fn set_key_check(&mut self, key_check: [u8; KEY_CHECK_SIZE]) {
+        self.key_check = key_check;
+    }
+
+    /// The last checkpoint taken, files written before checkpoints existed have none
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.checkpoint
+    }
+
+    pub fn set_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.checkpoint = Some(checkpoint);
+    }
+
+    pub fn to_bytes(&self) -> [u8; FILE_HEADER_SIZE] {
+        let mut bytes = [0u8; FILE_HEADER_SIZE];
+        bytes[0..8].copy_from_slice(&FILE_MAGIC);
+        bytes[8..10].copy_from_slice(&self.format_version.to_le_bytes());
+        bytes[10] = self.kind as u8;
+        bytes[12..16].copy_from_slice(&self.page_size.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.created_at.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.features.to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.page_map.0.to_le_bytes());
+        bytes[40..44].copy_from_slice(&self.page_map.1.to_le_bytes());
+        let checkpoint = 44 + KEY_CHECK_SIZE;
+        bytes[44..checkpoint].copy_from_slice(&self.key_check);
+        if let Some(marker) = self.checkpoint {
+            bytes[checkpoint..].copy_from_slice(&marker.to_bytes());
+        }
+        bytes
+    }
+
+    /// Parses and validates a header, `expected` is the kind of file the caller is opening
+    pub fn from_bytes(bytes: &[u8], expected: FileKind) -> Result<Self> {
+        if bytes.len() < FILE_HEADER_SIZE || bytes[0..8] != FILE_MAGIC {
+            return Err(Error::InvalidMagic);
+        }
+
+        let format_version = u16::from_le_bytes(bytes[8..10].try_into().unwrap());
+        if !(MIN_FORMAT_VERSION..=FORMAT_VERSION).contains(&format_version) {
+            return Err(Error::UnsupportedVersion(format_version));
+        }
+
+        let kind = FileKind::try_from(bytes[10])
+            .ok()
+            .filter(|kind| *kind == expected)
+            .ok_or(Error::FileKindMismatch {
+                expected,
+                found: bytes[10],
+            })?;
+
+        let page_size = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        if !is_valid_page_size(page_size as usize) {
+            return Err(Error::InvalidPageSize(page_size as usize));
+        }
+
+        let features = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+        if features & !SUPPORTED_FEATURES != 0 {
+            return Err(Error::UnsupportedFeatures(features & !SUPPORTED_FEATURES));
+        }
+
+        Ok(Self {
+            kind,
+            format_version,
+            page_size,
+            created_at: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            features,
+            page_map: (
+                u64::from_le_bytes(bytes[32..40].try_into().unwrap()),
+                u32::from_le_bytes(bytes[40..44].try_into().unwrap()),
+            ),
+            key_check: bytes[44..(44 + KEY_CHECK_SIZE)].try_into().unwrap(),
+            checkpoint: Checkpoint::from_bytes(
+                bytes[(44 + KEY_CHECK_SIZE)..FILE_HEADER_SIZE]
+                    .try_into()
+                    .unwrap(),
+            ),
+        })
+    }
+}
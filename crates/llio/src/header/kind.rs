@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// What a data file stores, recorded in its header so that files cannot be mixed up
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// Documents of a comet collection
+    Collection = 1,
+    /// Nodes of a file-based B+ tree
+    BTree = 2,
+    /// Root pointer and key type of a file-based B+ tree
+    BTreeMetadata = 3,
+}
+
+impl TryFrom<u8> for FileKind {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(FileKind::Collection),
+            2 => Ok(FileKind::BTree),
+            3 => Ok(FileKind::BTreeMetadata),
+            value => Err(value),
+        }
+    }
+}
+
+impl fmt::Display for FileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileKind::Collection => write!(f, "collection"),
+            FileKind::BTree => write!(f, "b-tree"),
+            FileKind::BTreeMetadata => write!(f, "b-tree metadata"),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod checkpoint;
+pub mod header;
+pub mod kind;
+
+pub use checkpoint::*;
+pub use header::*;
+pub use kind::*;
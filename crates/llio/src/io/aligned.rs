@@ -0,0 +1,37 @@
+use std::{
+    alloc::{self, Layout},
+    ptr::NonNull,
+    slice,
+};
+
+use crate::page::MIN_PAGE_SIZE;
+
+/// A zeroed heap buffer aligned for `O_DIRECT` transfers
+pub struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl AlignedBuffer {
+    pub fn new(size: usize) -> Self {
+        let layout = Layout::from_size_align(size.max(1), MIN_PAGE_SIZE).unwrap();
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+
+        Self { ptr, layout }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.layout.size()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
@@ -1,91 +1,729 @@
 use std::{
+    cell::RefCell,
     ffi::{c_void, CString},
     io::{self, Write},
+    ops::Range,
     os::fd::RawFd,
-    path::PathBuf,
-    ptr,
+    time::Instant,
 };
 
 use io_uring::{opcode, types, IoUring};
-use libc::{close, fstat, open, pread, stat, O_CREAT, O_DIRECT, O_RDWR, S_IRUSR, S_IWUSR};
+use libc::{
+    close, fallocate, fstat, fsync, ftruncate, open, pread, pwrite, stat, EOPNOTSUPP,
+    FALLOC_FL_KEEP_SIZE, FALLOC_FL_PUNCH_HOLE, O_CREAT, O_DIRECT, O_RDONLY, O_RDWR, S_IRUSR,
+    S_IWUSR,
+};
 
-use crate::page::{Page, PAGE_SIZE};
+use crate::{
+    compression::{compress, decompress},
+    encryption::Cipher,
+    error::{Error, Result},
+    header::{
+        Checkpoint, FileHeader, FileKind, FEATURE_COMPRESSION, FEATURE_ENCRYPTION, HEADER_PAGES,
+    },
+    page::{is_valid_page_size, Page, MIN_PAGE_SIZE},
+    stats::IoStats,
+};
+
+use super::{
+    aligned::AlignedBuffer,
+    lock::{lock_fd, LockMode},
+    options::FileOptions,
+    page_map::{Extent, PageMap, EXTENT_BLOCK_SIZE},
+    PageIo,
+};
 
 pub const IO_FLUSH_BUFFER_SIZE: usize = 16;
+pub const IO_PREFETCH_BUFFER_SIZE: usize = 32;
 
+/// Page storage backed by a file opened with `O_DIRECT`.
+///
+/// The first page of the file holds the [`FileHeader`], page indices passed to
+/// [`PageIo`] methods start right after it. Compressed and encrypted files store
+/// their pages in extents tracked by a [`PageMap`] instead, which is committed on `sync`.
+/// The file is locked for as long as it is open, see [`LockMode`].
 pub struct DirectFileIo {
     fd: RawFd,
+    header: FileHeader,
+    lock_mode: LockMode,
+    page_size: usize,
+    page_map: Option<PageMap>,
+    cipher: Option<Cipher>,
     total_pages: u64,
+    preallocation: u64,
+    allocated_pages: u64,
     flush_buffer: Vec<(Page, u64)>,
-    ring: IoUring,
+    prefetch_buffer: RefCell<Vec<(Page, u64)>>,
+    ring: RefCell<IoUring>,
+    stats: RefCell<IoStats>,
 }
 
 impl DirectFileIo {
-    pub fn new(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let (fd, size) = DirectFileIo::get_file_data(path);
-        let total_pages = size / PAGE_SIZE as u64;
+    /// Opens the data file at `path`, creating it with a fresh header if it is empty
+    pub fn new(path: &str, kind: FileKind) -> Result<Self> {
+        Self::with_options(path, kind, FileOptions::default())
+    }
+
+    /// Like [`DirectFileIo::new`], `options` only apply if the file gets created,
+    /// except for the encryption key that encrypted files require
+    pub fn with_options(path: &str, kind: FileKind, options: FileOptions) -> Result<Self> {
+        if !is_valid_page_size(options.page_size()) {
+            return Err(Error::InvalidPageSize(options.page_size()));
+        }
+
+        let (fd, size) = DirectFileIo::get_file_data(path, options.lock_mode())?;
+
+        let cipher = options.encryption_key().map(Cipher::new);
+        let header = if size == 0 && options.lock_mode() == LockMode::Shared {
+            // a file that was never written has no header to read
+            Err(Error::ReadOnly)
+        } else if size == 0 {
+            let mut features = 0;
+            if options.compression() {
+                features |= FEATURE_COMPRESSION;
+            }
+            if cipher.is_some() {
+                features |= FEATURE_ENCRYPTION;
+            }
+
+            let mut header = FileHeader::new(kind, options.page_size(), features);
+            if let Some(cipher) = &cipher {
+                header.set_key_check(cipher.key_check());
+            }
+            DirectFileIo::write_header(fd, header)
+        } else {
+            DirectFileIo::read_header(fd, kind)
+        };
+        let opened = header.and_then(|header| {
+            // the key of an unencrypted file is ignored
+            let cipher = match cipher {
+                _ if !header.has_feature(FEATURE_ENCRYPTION) => None,
+                Some(cipher) if cipher.verify_key_check(header.key_check()) => Some(cipher),
+                Some(_) => return Err(Error::WrongKey),
+                None => return Err(Error::KeyRequired),
+            };
+
+            if !header.has_feature(FEATURE_COMPRESSION | FEATURE_ENCRYPTION) {
+                return Ok((header, None, cipher));
+            }
+
+            let page_map = DirectFileIo::read_page_map(fd, &header)?;
+            Ok((header, Some(page_map), cipher))
+        });
+        let (header, page_map, cipher) = match opened {
+            Ok(opened) => opened,
+            Err(err) => {
+                unsafe { close(fd) };
+                return Err(err);
+            }
+        };
+
+        let page_size = header.page_size() as usize;
+        let total_pages = match &page_map {
+            Some(page_map) => page_map.total_pages(),
+            None => (size / page_size as u64).saturating_sub(HEADER_PAGES),
+        };
+
+        let ring_size = IO_FLUSH_BUFFER_SIZE.max(IO_PREFETCH_BUFFER_SIZE) as u32;
+        let ring = match IoUring::new(ring_size) {
+            Ok(ring) => ring,
+            Err(err) => {
+                unsafe { close(fd) };
+                return Err(Error::Ring(err));
+            }
+        };
 
         Ok(Self {
             fd,
+            header,
+            lock_mode: options.lock_mode(),
+            page_size,
+            page_map,
+            cipher,
             total_pages,
+            preallocation: options.preallocation(),
+            allocated_pages: total_pages,
             flush_buffer: Vec::with_capacity(IO_FLUSH_BUFFER_SIZE),
-            ring: IoUring::new(IO_FLUSH_BUFFER_SIZE as u32)?,
+            prefetch_buffer: RefCell::new(Vec::with_capacity(IO_PREFETCH_BUFFER_SIZE)),
+            ring: RefCell::new(ring),
+            stats: RefCell::default(),
         })
     }
 
-    fn get_file_data(path: &str) -> (RawFd, u64) {
-        let path = CString::new(path).unwrap();
+    fn get_file_data(path: &str, lock_mode: LockMode) -> Result<(RawFd, u64)> {
+        let c_path = CString::new(path).map_err(|err| Error::Open {
+            path: path.to_string(),
+            source: io::Error::new(io::ErrorKind::InvalidInput, err),
+        })?;
         let fd = unsafe {
             open(
-                path.as_ptr(),
-                O_CREAT | O_RDWR | O_DIRECT,
+                c_path.as_ptr(),
+                match lock_mode {
+                    LockMode::Shared => O_RDONLY | O_DIRECT,
+                    LockMode::Exclusive => O_CREAT | O_RDWR | O_DIRECT,
+                },
                 S_IRUSR | S_IWUSR,
             )
         };
+        if fd < 0 {
+            return Err(Error::Open {
+                path: path.to_string(),
+                source: io::Error::last_os_error(),
+            });
+        }
+
+        if let Err(err) = lock_fd(fd, lock_mode, path) {
+            unsafe { close(fd) };
+            return Err(err);
+        }
 
         let mut file_stat: stat = unsafe { std::mem::zeroed() };
-        unsafe { fstat(fd, &mut file_stat) };
+        if unsafe { fstat(fd, &mut file_stat) } < 0 {
+            let source = io::Error::last_os_error();
+            unsafe { close(fd) };
+            return Err(Error::Stat {
+                path: path.to_string(),
+                source,
+            });
+        }
+
+        Ok((fd, file_stat.st_size as u64))
+    }
+
+    fn read_header(fd: RawFd, kind: FileKind) -> Result<FileHeader> {
+        // the page size is not known yet, but the header fits into the smallest page
+        let mut buffer = AlignedBuffer::new(MIN_PAGE_SIZE);
+        let read = unsafe {
+            pread(
+                fd,
+                buffer.as_mut_slice().as_mut_ptr() as *mut c_void,
+                MIN_PAGE_SIZE,
+                0,
+            )
+        };
+
+        if read < 0 {
+            return Err(Error::Read {
+                page: 0,
+                source: io::Error::last_os_error(),
+            });
+        }
 
-        (fd, file_stat.st_size as u64)
+        FileHeader::from_bytes(&buffer.as_slice()[..read as usize], kind)
+    }
+
+    fn write_header(fd: RawFd, header: FileHeader) -> Result<FileHeader> {
+        let page_size = header.page_size() as usize;
+        let mut buffer = AlignedBuffer::new(page_size);
+        let bytes = header.to_bytes();
+        buffer.as_mut_slice()[..bytes.len()].copy_from_slice(&bytes);
+
+        let written = unsafe {
+            pwrite(
+                fd,
+                buffer.as_slice().as_ptr() as *const c_void,
+                page_size,
+                0,
+            )
+        };
+
+        if written < 0 {
+            return Err(Error::Write {
+                page: 0,
+                source: io::Error::last_os_error(),
+            });
+        }
+        if (written as usize) < page_size {
+            return Err(Error::ShortWrite {
+                page: 0,
+                written: written as usize,
+            });
+        }
+
+        Ok(header)
+    }
+
+    fn read_page_map(fd: RawFd, header: &FileHeader) -> Result<PageMap> {
+        let data_offset = HEADER_PAGES * header.page_size() as u64;
+        let (offset, len) = header.page_map();
+        if len == 0 {
+            return Ok(PageMap::new(data_offset));
+        }
+
+        let extent = Extent { offset, len };
+        let bytes = DirectFileIo::read_extent(fd, 0, extent)?;
+        PageMap::from_bytes(data_offset, &bytes, extent).ok_or(Error::CorruptPageMap)
+    }
+
+    pub fn lock_mode(&self) -> LockMode {
+        self.lock_mode
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    /// Position of a page in the file, past the header
+    fn file_offset(&self, idx: u64) -> u64 {
+        (idx + HEADER_PAGES) * self.page_size as u64
     }
 }
 
+// compressed files
 impl DirectFileIo {
-    pub fn total_pages(&self) -> u64 {
-        self.total_pages
+    /// Reads the blocks covering `extent`, `page` is the page reported on failure
+    fn read_extent(fd: RawFd, page: u64, extent: Extent) -> Result<Vec<u8>> {
+        let start = extent.offset / EXTENT_BLOCK_SIZE * EXTENT_BLOCK_SIZE;
+        let end =
+            (extent.offset + extent.len as u64).div_ceil(EXTENT_BLOCK_SIZE) * EXTENT_BLOCK_SIZE;
+
+        let mut buffer = AlignedBuffer::new((end - start) as usize);
+        let read = unsafe {
+            pread(
+                fd,
+                buffer.as_mut_slice().as_mut_ptr() as *mut c_void,
+                (end - start) as usize,
+                start as i64,
+            )
+        };
+
+        if read < 0 {
+            return Err(Error::Read {
+                page,
+                source: io::Error::last_os_error(),
+            });
+        }
+
+        let skip = (extent.offset - start) as usize;
+        if (read as usize) < skip + extent.len as usize {
+            return Err(Error::ShortRead {
+                page,
+                read: read as usize,
+            });
+        }
+
+        Ok(buffer.as_slice()[skip..(skip + extent.len as usize)].to_vec())
     }
 
-    fn flush_pages(&mut self) -> io::Result<()> {
-        let mut ops_len = 0;
+    /// Writes `bytes` at a block-aligned `offset`, `page` is the page reported on failure
+    fn write_extent(&self, page: u64, offset: u64, bytes: &[u8]) -> Result<()> {
+        let len = (bytes.len() as u64).div_ceil(EXTENT_BLOCK_SIZE) * EXTENT_BLOCK_SIZE;
+        let mut buffer = AlignedBuffer::new(len as usize);
+        buffer.as_mut_slice()[..bytes.len()].copy_from_slice(bytes);
 
-        let ops = self
-            .flush_buffer
-            .iter_mut()
-            .filter(|(page, _)| page.is_dirty())
-            .map(|(page, idx)| {
-                page.flush().unwrap();
-                opcode::Write::new(
+        let written = unsafe {
+            pwrite(
+                self.fd,
+                buffer.as_slice().as_ptr() as *const c_void,
+                len as usize,
+                offset as i64,
+            )
+        };
+
+        if written < 0 {
+            return Err(Error::Write {
+                page,
+                source: io::Error::last_os_error(),
+            });
+        }
+        self.stats.borrow_mut().bytes_written += written as u64;
+        if (written as u64) < len {
+            return Err(Error::ShortWrite {
+                page,
+                written: written as usize,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn load_mapped_page(&self, page_map: &PageMap, idx: u64) -> Result<Page> {
+        let Some(extent) = page_map.get(idx) else {
+            return Ok(Page::new(self.page_size));
+        };
+
+        let started = Instant::now();
+        let mut bytes = DirectFileIo::read_extent(self.fd, idx, extent)?;
+        self.record_reads(1, extent.len as u64, started);
+
+        if let Some(cipher) = &self.cipher {
+            bytes = cipher
+                .decrypt(idx, &bytes)
+                .ok_or(Error::Decrypt { page: idx })?;
+        }
+
+        // pages that do not compress are stored as they are
+        let buffer = if bytes.len() == self.page_size {
+            bytes
+        } else {
+            decompress(&bytes, self.page_size).ok_or(Error::Decompress { page: idx })?
+        };
+
+        Ok(Page::from_buffer(buffer.into_boxed_slice()))
+    }
+
+    /// Compresses and encrypts the dirty pages, then writes them next to each other into a single extent
+    fn flush_mapped_pages(&mut self) -> Result<()> {
+        let compression = self.header.has_feature(FEATURE_COMPRESSION);
+
+        let mut pack = Vec::new();
+        let mut extents = Vec::new();
+        for (page, idx) in self.flush_buffer.iter().filter(|(page, _)| page.is_dirty()) {
+            let mut stored = compression
+                .then(|| compress(page.buffer()))
+                .flatten()
+                .unwrap_or_else(|| page.buffer().to_vec());
+            if let Some(cipher) = &self.cipher {
+                stored = cipher.encrypt(*idx, &stored);
+            }
+
+            extents.push((*idx, pack.len() as u64, stored.len() as u32));
+            pack.extend_from_slice(&stored);
+        }
+
+        if extents.is_empty() {
+            self.flush_buffer.clear();
+            return Ok(());
+        }
+
+        let page_map = self.page_map.as_mut().unwrap();
+        let offset = page_map.allocate(pack.len() as u64);
+        self.write_extent(extents[0].0, offset, &pack)?;
+
+        let stats = self.stats.get_mut();
+        stats.flush_batches += 1;
+        stats.pages_written += extents.len() as u64;
+
+        let page_map = self.page_map.as_mut().unwrap();
+        for (idx, start, len) in extents {
+            let extent = Extent {
+                offset: offset + start,
+                len,
+            };
+            page_map.insert(idx, extent);
+        }
+        self.flush_buffer.clear();
+
+        Ok(())
+    }
+
+    /// Writes the page map and points the header at it, the previous map stays valid until then
+    fn commit_page_map(&mut self) -> Result<()> {
+        let page_map = self.page_map.as_mut().unwrap();
+        let bytes = page_map.to_bytes();
+        let extent = Extent {
+            offset: page_map.allocate(bytes.len() as u64),
+            len: bytes.len() as u32,
+        };
+        self.write_extent(0, extent.offset, &bytes)?;
+        self.fsync()?;
+
+        let (offset, len) = self.header.page_map();
+        let mut header = self.header.clone();
+        header.set_page_map(extent.offset, extent.len);
+        self.header = DirectFileIo::write_header(self.fd, header)?;
+        self.fsync()?;
+
+        let page_map = self.page_map.as_mut().unwrap();
+        page_map.reference(extent);
+        if len > 0 {
+            page_map.release(Extent { offset, len });
+        }
+        page_map.commit();
+        if let Some(end) = page_map.shrink() {
+            self.set_len(end)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_len(&self, len: u64) -> Result<()> {
+        if unsafe { ftruncate(self.fd, len as i64) } < 0 {
+            return Err(Error::Truncate(io::Error::last_os_error()));
+        }
+
+        Ok(())
+    }
+
+    /// Runs `fallocate`, file systems that do not support `mode` are left as they are
+    fn fallocate(&self, mode: i32, offset: u64, len: u64) -> Result<()> {
+        if len == 0 || unsafe { fallocate(self.fd, mode, offset as i64, len as i64) } == 0 {
+            return Ok(());
+        }
+
+        match io::Error::last_os_error() {
+            err if err.raw_os_error() == Some(EOPNOTSUPP) => Ok(()),
+            err => Err(Error::Allocate(err)),
+        }
+    }
+
+    fn fsync(&self) -> Result<()> {
+        self.stats.borrow_mut().syncs += 1;
+        if unsafe { fsync(self.fd) } < 0 {
+            return Err(Error::Sync(io::Error::last_os_error()));
+        }
+
+        Ok(())
+    }
+}
+
+impl DirectFileIo {
+    /// Returns whether the page holds data, `false` means it lies past the end of the file
+    fn check_read(&self, idx: u64, read: usize) -> Result<bool> {
+        match read {
+            0 if idx >= self.total_pages => Ok(false),
+            read if read < self.page_size => Err(Error::ShortRead { page: idx, read }),
+            _ => Ok(true),
+        }
+    }
+
+    fn record_reads(&self, pages: u64, bytes: u64, started: Instant) {
+        let latency = started.elapsed();
+        let mut stats = self.stats.borrow_mut();
+        stats.pages_read += pages;
+        stats.bytes_read += bytes;
+        for _ in 0..pages {
+            stats.read_latency.record(latency);
+        }
+    }
+
+    fn cached_page(&self, idx: u64) -> Option<Page> {
+        self.flush_buffer
+            .iter()
+            .chain(self.prefetch_buffer.borrow().iter())
+            .find(|(_, page_idx)| *page_idx == idx)
+            .map(|(page, _)| page.clone())
+    }
+
+    /// Reads a batch of pages from the file through the ring, completing them together
+    fn read_pages(&self, indices: &[u64]) -> Result<Vec<Page>> {
+        if let Some(page_map) = &self.page_map {
+            return indices
+                .iter()
+                .map(|idx| self.load_mapped_page(page_map, *idx))
+                .collect();
+        }
+
+        let mut ring = self.ring.borrow_mut();
+        let mut pages = Vec::with_capacity(indices.len());
+
+        for batch in indices.chunks(ring.params().sq_entries() as usize) {
+            let free_entries = {
+                let submission = ring.submission();
+                submission.capacity() - submission.len()
+            };
+            if free_entries < batch.len() {
+                return Err(Error::RingFull);
+            }
+
+            let mut buffers = Vec::with_capacity(batch.len());
+            let started = Instant::now();
+
+            for (pos, idx) in batch.iter().enumerate() {
+                let mut buffer = AlignedBuffer::new(self.page_size);
+                let op = opcode::Read::new(
                     types::Fd(self.fd),
-                    page.buffer().as_ptr(),
-                    page.buffer().len() as u32,
+                    buffer.as_mut_slice().as_mut_ptr(),
+                    self.page_size as u32,
                 )
-                .offset(*idx * PAGE_SIZE as u64)
+                .offset(self.file_offset(*idx))
                 .build()
-            });
+                .user_data(pos as u64);
+
+                unsafe { ring.submission().push(&op).map_err(|_| Error::RingFull)? };
+                buffers.push(buffer);
+            }
+
+            self.stats.borrow_mut().ring_submissions += 1;
+            if let Err(err) = ring.submit_and_wait(buffers.len()) {
+                // the kernel may still own the buffers, leaking them is the only safe option
+                std::mem::forget(buffers);
+                return Err(Error::Ring(err));
+            }
+
+            let mut results = vec![0; batch.len()];
+            for cqe in ring.completion() {
+                results[cqe.user_data() as usize] = cqe.result();
+            }
+            // every page of a batch waits for the whole batch
+            let bytes = results.iter().map(|read| (*read).max(0) as u64).sum();
+            self.record_reads(batch.len() as u64, bytes, started);
 
-        for op in ops {
-            ops_len += 1;
-            unsafe { self.ring.submission().push(&op).unwrap() };
+            for ((idx, buffer), read) in batch.iter().zip(buffers).zip(results) {
+                if read < 0 {
+                    return Err(Error::Read {
+                        page: *idx,
+                        source: io::Error::from_raw_os_error(-read),
+                    });
+                }
+
+                let mut page_buffer = vec![0u8; self.page_size].into_boxed_slice();
+                if self.check_read(*idx, read as usize)? {
+                    page_buffer.copy_from_slice(buffer.as_slice());
+                }
+                pages.push(Page::from_buffer(page_buffer));
+            }
         }
 
-        self.ring.submit_and_wait(ops_len).unwrap();
+        Ok(pages)
+    }
+
+    /// Loads a range of pages, reading the ones that are not buffered in a single batch
+    pub fn load_pages(&self, range: Range<u64>) -> Result<Vec<Page>> {
+        let mut pages = range
+            .clone()
+            .map(|idx| (idx, self.cached_page(idx)))
+            .collect::<Vec<_>>();
 
-        self.flush_buffer.clear();
+        let missing = pages
+            .iter()
+            .filter(|(_, page)| page.is_none())
+            .map(|(idx, _)| *idx)
+            .collect::<Vec<_>>();
+        let mut read = self.read_pages(&missing)?.into_iter();
+
+        Ok(pages
+            .iter_mut()
+            .map(|(_, page)| page.take().or_else(|| read.next()).unwrap())
+            .collect())
+    }
+
+    /// Reserves space for the buffered pages in whole chunks, so that the file grows in large steps
+    fn preallocate(&mut self) -> Result<()> {
+        let Some(last) = self.flush_buffer.iter().map(|(_, idx)| *idx).max() else {
+            return Ok(());
+        };
+        if self.preallocation == 0 || last < self.allocated_pages {
+            return Ok(());
+        }
+
+        let allocated = (last + 1).next_multiple_of(self.preallocation);
+        self.fallocate(
+            FALLOC_FL_KEEP_SIZE,
+            self.file_offset(self.allocated_pages),
+            (allocated - self.allocated_pages) * self.page_size as u64,
+        )?;
+        self.allocated_pages = allocated;
+
+        Ok(())
+    }
+
+    /// Forgets the buffered copies of the pages in `range`
+    fn drop_buffered(&mut self, range: Range<u64>) {
+        self.flush_buffer.retain(|(_, idx)| !range.contains(idx));
+        self.prefetch_buffer
+            .get_mut()
+            .retain(|(_, idx)| !range.contains(idx));
+    }
+
+    fn flush_pages(&mut self) -> Result<()> {
+        if self.page_map.is_some() {
+            return self.flush_mapped_pages();
+        }
+
+        self.preallocate()?;
+
+        // O_DIRECT requires aligned buffers, they must stay alive until the ring completes
+        let mut buffers = Vec::with_capacity(self.flush_buffer.len());
+
+        for (page, idx) in self.flush_buffer.iter().filter(|(page, _)| page.is_dirty()) {
+            let mut buffer = AlignedBuffer::new(self.page_size);
+            buffer.as_mut_slice().copy_from_slice(page.buffer());
+
+            let op = opcode::Write::new(
+                types::Fd(self.fd),
+                buffer.as_slice().as_ptr(),
+                self.page_size as u32,
+            )
+            .offset(self.file_offset(*idx))
+            .build()
+            .user_data(*idx);
+
+            if unsafe { self.ring.get_mut().submission().push(&op) }.is_err() {
+                break;
+            }
+            buffers.push(buffer);
+        }
+
+        if buffers.is_empty() {
+            self.flush_buffer.clear();
+            return Ok(());
+        }
+
+        // submit whatever was queued before reporting a full ring, so nothing is left dangling
+        let submitted = buffers.len();
+        let stats = self.stats.get_mut();
+        stats.flush_batches += 1;
+        stats.ring_submissions += 1;
+        let ring = self.ring.get_mut();
+        if let Err(err) = ring.submit_and_wait(submitted) {
+            // the kernel may still own the buffers, leaking them is the only safe option
+            std::mem::forget(buffers);
+            return Err(Error::Ring(err));
+        }
+
+        let mut error = None;
+        let mut written = Vec::with_capacity(submitted);
+        for cqe in ring.completion() {
+            let idx = cqe.user_data();
+            match cqe.result() {
+                res if res < 0 => {
+                    error.get_or_insert(Error::Write {
+                        page: idx,
+                        source: io::Error::from_raw_os_error(-res),
+                    });
+                }
+                res if (res as usize) < self.page_size => {
+                    error.get_or_insert(Error::ShortWrite {
+                        page: idx,
+                        written: res as usize,
+                    });
+                }
+                _ => written.push(idx),
+            }
+        }
+
+        let stats = self.stats.get_mut();
+        stats.pages_written += written.len() as u64;
+        stats.bytes_written += (written.len() * self.page_size) as u64;
+
+        for (page, idx) in self.flush_buffer.iter_mut() {
+            if written.contains(idx) {
+                page.flush()?;
+            }
+        }
+        self.flush_buffer.retain(|(page, _)| page.is_dirty());
+
+        if let Some(err) = error {
+            return Err(err);
+        }
+
+        if !self.flush_buffer.is_empty() {
+            return Err(Error::RingFull);
+        }
 
         Ok(())
     }
+}
+
+impl PageIo for DirectFileIo {
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn total_pages(&self) -> u64 {
+        self.total_pages
+    }
+
+    fn read_only(&self) -> bool {
+        self.lock_mode == LockMode::Shared
+    }
+
+    fn flush_page(&mut self, idx: u64, page: Page) -> Result<()> {
+        if self.lock_mode == LockMode::Shared {
+            return Err(Error::ReadOnly);
+        }
 
-    pub fn flush_page(&mut self, idx: u64, page: crate::page::Page) -> std::io::Result<()> {
         if self.flush_buffer.len() == self.flush_buffer.capacity() {
             self.flush_pages()?;
         }
@@ -100,39 +738,187 @@ impl DirectFileIo {
             self.flush_buffer.push((page, idx))
         }
 
+        // the prefetched copy is stale now
+        self.prefetch_buffer
+            .get_mut()
+            .retain(|(_, page_idx)| *page_idx != idx);
+
+        self.total_pages = self.total_pages.max(idx + 1);
+
         Ok(())
     }
 
-    pub fn load_page(&self, idx: u64) -> io::Result<Page> {
-        let flush_buffer_page = self
-            .flush_buffer
-            .iter()
-            .find(|(_, page_idx)| *page_idx == idx);
+    fn load_page(&self, idx: u64) -> Result<Page> {
+        if let Some(page) = self.cached_page(idx) {
+            self.stats.borrow_mut().cache_hits += 1;
+            return Ok(page);
+        }
+        self.stats.borrow_mut().cache_misses += 1;
 
-        let mut buffer = Box::new([0u8; PAGE_SIZE]);
-        if let Some((page, _)) = flush_buffer_page {
-            let page_buffer = page.buffer();
-            unsafe { ptr::copy(page_buffer.as_ptr(), buffer.as_mut_ptr(), PAGE_SIZE) };
-        } else {
-            unsafe {
-                pread(
-                    self.fd,
-                    buffer.as_mut_ptr() as *mut c_void,
-                    PAGE_SIZE,
-                    (PAGE_SIZE as u64 * idx) as i64,
-                )
-            };
+        if let Some(page_map) = &self.page_map {
+            return self.load_mapped_page(page_map, idx);
+        }
+
+        let mut buffer = vec![0u8; self.page_size].into_boxed_slice();
+        let mut aligned = AlignedBuffer::new(self.page_size);
+        let started = Instant::now();
+        let read = unsafe {
+            pread(
+                self.fd,
+                aligned.as_mut_slice().as_mut_ptr() as *mut c_void,
+                self.page_size,
+                self.file_offset(idx) as i64,
+            )
+        };
+
+        if read < 0 {
+            return Err(Error::Read {
+                page: idx,
+                source: io::Error::last_os_error(),
+            });
+        }
+
+        self.record_reads(1, read as u64, started);
+
+        if self.check_read(idx, read as usize)? {
+            buffer.copy_from_slice(aligned.as_slice());
         }
 
         let page = Page::from_buffer(buffer);
 
         Ok(page)
     }
+
+    fn prefetch(&self, range: Range<u64>) -> Result<()> {
+        let missing = range
+            .filter(|idx| self.cached_page(*idx).is_none())
+            .take(IO_PREFETCH_BUFFER_SIZE)
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let pages = self.read_pages(&missing)?;
+
+        let mut prefetch_buffer = self.prefetch_buffer.borrow_mut();
+        let overflow =
+            (prefetch_buffer.len() + pages.len()).saturating_sub(IO_PREFETCH_BUFFER_SIZE);
+        prefetch_buffer.drain(..overflow);
+        prefetch_buffer.extend(pages.into_iter().zip(missing));
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        // nothing could have been written
+        if self.lock_mode == LockMode::Shared {
+            return Ok(());
+        }
+
+        self.flush_pages()?;
+
+        if self.page_map.is_some() {
+            return self.commit_page_map();
+        }
+
+        self.fsync()
+    }
+
+    /// Pages of compressed and encrypted files are only reachable once the page map is committed,
+    /// so for them this is a `sync`
+    fn write_back(&mut self) -> Result<()> {
+        if self.lock_mode == LockMode::Shared {
+            return Ok(());
+        }
+
+        if self.page_map.is_some() {
+            return self.sync();
+        }
+
+        self.flush_pages()
+    }
+
+    /// The marker is written to the header once every page is durable
+    fn checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        if self.lock_mode == LockMode::Shared {
+            return Err(Error::ReadOnly);
+        }
+
+        self.sync()?;
+
+        let mut header = self.header.clone();
+        header.set_checkpoint(checkpoint);
+        self.header = DirectFileIo::write_header(self.fd, header)?;
+        self.fsync()
+    }
+
+    fn last_checkpoint(&self) -> Option<Checkpoint> {
+        self.header.checkpoint()
+    }
+
+    /// Compressed and encrypted files give their trailing space back once the page map is committed
+    fn truncate(&mut self, total_pages: u64) -> Result<()> {
+        if self.lock_mode == LockMode::Shared {
+            return Err(Error::ReadOnly);
+        }
+        if total_pages >= self.total_pages {
+            return Ok(());
+        }
+
+        self.drop_buffered(total_pages..u64::MAX);
+        match &mut self.page_map {
+            Some(page_map) => page_map.truncate(total_pages),
+            None => {
+                self.set_len(self.file_offset(total_pages))?;
+                self.allocated_pages = self.allocated_pages.min(total_pages);
+            }
+        }
+        self.total_pages = total_pages;
+
+        Ok(())
+    }
+
+    fn discard_pages(&mut self, range: Range<u64>) -> Result<()> {
+        if self.lock_mode == LockMode::Shared {
+            return Err(Error::ReadOnly);
+        }
+
+        let range = range.start..range.end.min(self.total_pages);
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        self.drop_buffered(range.clone());
+        if let Some(page_map) = &mut self.page_map {
+            range.for_each(|idx| page_map.remove(idx));
+            return Ok(());
+        }
+
+        self.fallocate(
+            FALLOC_FL_PUNCH_HOLE | FALLOC_FL_KEEP_SIZE,
+            self.file_offset(range.start),
+            (range.end - range.start) * self.page_size as u64,
+        )
+    }
+
+    fn stats(&self) -> IoStats {
+        self.stats.borrow().clone()
+    }
+
+    fn reset_stats(&mut self) {
+        *self.stats.get_mut() = IoStats::default();
+    }
 }
 
 impl Drop for DirectFileIo {
     fn drop(&mut self) {
-        self.flush_pages().unwrap();
+        // errors cannot be reported from here, call `sync` beforehand to observe them
+        if self.page_map.is_some() {
+            // pages of compressed files are unreachable until the page map is committed
+            let _ = self.sync();
+        } else {
+            let _ = self.flush_pages();
+        }
         unsafe { close(self.fd) };
     }
 }
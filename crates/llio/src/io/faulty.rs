@@ -0,0 +1,313 @@
+use std::{cell::Cell, collections::BTreeMap, io, ops::Range, ptr};
+
+use crate::{
+    error::{Error, Result},
+    header::Checkpoint,
+    page::Page,
+    stats::IoStats,
+};
+
+use super::PageIo;
+
+/// Faults injected by [`FaultyFileIo`]
+#[derive(Debug, Clone, Default)]
+pub struct FaultConfig {
+    crash_after_writes: Option<u64>,
+    torn_write: Option<usize>,
+    drop_unsynced: bool,
+    lose_unsynced_every: Option<u64>,
+    read_error_every: Option<u64>,
+}
+
+impl FaultConfig {
+    pub fn builder() -> FaultConfigBuilder {
+        FaultConfigBuilder::new()
+    }
+
+    pub fn crash_after_writes(&self) -> Option<u64> {
+        self.crash_after_writes
+    }
+
+    pub fn torn_write(&self) -> Option<usize> {
+        self.torn_write
+    }
+
+    pub fn drop_unsynced(&self) -> bool {
+        self.drop_unsynced
+    }
+
+    pub fn lose_unsynced_every(&self) -> Option<u64> {
+        self.lose_unsynced_every
+    }
+
+    pub fn read_error_every(&self) -> Option<u64> {
+        self.read_error_every
+    }
+}
+
+#[derive(Default)]
+pub struct FaultConfigBuilder {
+    config: FaultConfig,
+}
+
+impl FaultConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crash on the `n + 1`-th page write
+    pub fn crash_after_writes(mut self, n: u64) -> Self {
+        self.config.crash_after_writes = Some(n);
+        self
+    }
+
+    /// Let only the first `bytes` of the page write that crashes reach the storage
+    pub fn torn_write(mut self, bytes: usize) -> Self {
+        self.config.torn_write = Some(bytes);
+        self
+    }
+
+    /// Lose every page write that was not synced when the crash happens
+    pub fn drop_unsynced(mut self, drop_unsynced: bool) -> Self {
+        self.config.drop_unsynced = drop_unsynced;
+        self
+    }
+
+    /// Lose every `n`-th of the unsynced pages when the crash happens and keep the others,
+    /// as if the writes had reached the storage in any order
+    pub fn lose_unsynced_every(mut self, n: u64) -> Self {
+        self.config.lose_unsynced_every = Some(n.max(1));
+        self
+    }
+
+    /// Fail every `n`-th page read
+    pub fn read_error_every(mut self, n: u64) -> Self {
+        self.config.read_error_every = Some(n.max(1));
+        self
+    }
+
+    pub fn build(self) -> FaultConfig {
+        self.config
+    }
+}
+
+/// xorshift64* generator, so that a fault-injection workload is reproducible from its seed
+#[cfg(feature = "testing")]
+pub struct Rng(u64);
+
+#[cfg(feature = "testing")]
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E3779B97F4A7C15) | 1)
+    }
+
+    /// A number in `0..n`
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545F4914F6CDD1D) % n
+    }
+}
+
+/// A storage backend that wraps another one and simulates crashes and I/O failures.
+///
+/// Page writes are kept in a volatile buffer until `sync` is called,
+/// mimicking the OS page cache. Once crashed, every operation fails
+/// and the wrapped storage keeps only what made it to "disk".
+pub struct FaultyFileIo<I: PageIo> {
+    inner: I,
+    config: FaultConfig,
+    unsynced: BTreeMap<u64, Page>,
+    writes: u64,
+    reads: Cell<u64>,
+    crashed: bool,
+}
+
+impl<I: PageIo> FaultyFileIo<I> {
+    pub fn new(inner: I, config: FaultConfig) -> Self {
+        Self {
+            inner,
+            config,
+            unsynced: BTreeMap::new(),
+            writes: 0,
+            reads: Cell::new(0),
+            crashed: false,
+        }
+    }
+
+    pub fn writes(&self) -> u64 {
+        self.writes
+    }
+
+    pub fn is_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    /// Simulates a process crash: unsynced writes are either lost or persisted,
+    /// depending on the configuration
+    pub fn crash(&mut self) -> Result<()> {
+        self.crashed = true;
+
+        let unsynced = std::mem::take(&mut self.unsynced);
+        if !self.config.drop_unsynced {
+            for (i, (idx, page)) in unsynced.into_iter().enumerate() {
+                let lost = self
+                    .config
+                    .lose_unsynced_every
+                    .is_some_and(|every| (i as u64 + 1).is_multiple_of(every));
+                if !lost {
+                    self.inner.flush_page(idx, page)?;
+                }
+            }
+        }
+
+        self.inner.sync()
+    }
+
+    fn crash_with_torn_write(&mut self, idx: u64, page: Page) -> Result<()> {
+        if let Some(bytes) = self.config.torn_write {
+            let previous = match self.unsynced.get(&idx) {
+                Some(page) => page.clone(),
+                None => self.inner.load_page(idx)?,
+            };
+
+            let bytes = bytes.min(page.size());
+            let mut buffer = Box::<[u8]>::from(previous.buffer());
+            unsafe { ptr::copy_nonoverlapping(page.buffer().as_ptr(), buffer.as_mut_ptr(), bytes) };
+
+            self.unsynced.remove(&idx);
+            self.inner.flush_page(idx, Page::from_buffer(buffer))?;
+        }
+
+        self.crash()
+    }
+
+    fn crashed_error() -> Error {
+        Error::Io(io::Error::other("simulated crash"))
+    }
+}
+
+impl<I: PageIo> PageIo for FaultyFileIo<I> {
+    fn page_size(&self) -> usize {
+        self.inner.page_size()
+    }
+
+    fn total_pages(&self) -> u64 {
+        self.unsynced
+            .keys()
+            .next_back()
+            .map(|idx| idx + 1)
+            .unwrap_or(0)
+            .max(self.inner.total_pages())
+    }
+
+    fn load_page(&self, idx: u64) -> Result<Page> {
+        if self.crashed {
+            return Err(Self::crashed_error());
+        }
+
+        let reads = self.reads.get() + 1;
+        self.reads.set(reads);
+
+        if self
+            .config
+            .read_error_every
+            .is_some_and(|every| reads.is_multiple_of(every))
+        {
+            return Err(Error::Read {
+                page: idx,
+                source: io::Error::other("simulated read error"),
+            });
+        }
+
+        match self.unsynced.get(&idx) {
+            Some(page) => Ok(page.clone()),
+            None => self.inner.load_page(idx),
+        }
+    }
+
+    fn flush_page(&mut self, idx: u64, page: Page) -> Result<()> {
+        if self.crashed {
+            return Err(Self::crashed_error());
+        }
+
+        self.writes += 1;
+
+        if self
+            .config
+            .crash_after_writes
+            .is_some_and(|n| self.writes > n)
+        {
+            self.crash_with_torn_write(idx, page)?;
+            return Err(Error::Write {
+                page: idx,
+                source: io::Error::other("simulated crash"),
+            });
+        }
+
+        self.unsynced.insert(idx, page);
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        if self.crashed {
+            return Err(Self::crashed_error());
+        }
+
+        for (idx, page) in std::mem::take(&mut self.unsynced) {
+            self.inner.flush_page(idx, page)?;
+        }
+
+        self.inner.sync()
+    }
+
+    fn checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        self.sync()?;
+        self.inner.checkpoint(checkpoint)
+    }
+
+    fn last_checkpoint(&self) -> Option<Checkpoint> {
+        self.inner.last_checkpoint()
+    }
+
+    fn truncate(&mut self, total_pages: u64) -> Result<()> {
+        if self.crashed {
+            return Err(Self::crashed_error());
+        }
+
+        self.unsynced.retain(|idx, _| *idx < total_pages);
+        self.inner.truncate(total_pages)
+    }
+
+    fn discard_pages(&mut self, range: Range<u64>) -> Result<()> {
+        if self.crashed {
+            return Err(Self::crashed_error());
+        }
+
+        self.unsynced.retain(|idx, _| !range.contains(idx));
+        self.inner.discard_pages(range)
+    }
+
+    fn read_only(&self) -> bool {
+        self.inner.read_only()
+    }
+
+    fn stats(&self) -> IoStats {
+        self.inner.stats()
+    }
+
+    fn reset_stats(&mut self) {
+        self.inner.reset_stats()
+    }
+}
+
+impl<I: PageIo> Drop for FaultyFileIo<I> {
+    fn drop(&mut self) {
+        // a clean shutdown persists everything, just like `DirectFileIo` does
+        if !self.crashed {
+            let _ = self.sync();
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::{ffi::CString, io, os::fd::RawFd};
+
+use libc::{
+    close, flock, open, EWOULDBLOCK, LOCK_EX, LOCK_NB, LOCK_SH, O_CLOEXEC, O_CREAT, O_RDWR,
+    S_IRUSR, S_IWUSR,
+};
+
+use crate::error::{Error, Result};
+
+/// How a file is locked against other processes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LockMode {
+    /// Any number of readers, writes are refused
+    Shared,
+    /// A single reader and writer
+    #[default]
+    Exclusive,
+}
+
+/// Takes an advisory lock on `fd` without waiting, `path` is only used for error reporting
+pub(crate) fn lock_fd(fd: RawFd, mode: LockMode, path: &str) -> Result<()> {
+    let operation = match mode {
+        LockMode::Shared => LOCK_SH,
+        LockMode::Exclusive => LOCK_EX,
+    };
+
+    if unsafe { flock(fd, operation | LOCK_NB) } < 0 {
+        let source = io::Error::last_os_error();
+        if source.raw_os_error() == Some(EWOULDBLOCK) {
+            return Err(Error::Locked {
+                path: path.to_string(),
+            });
+        }
+
+        return Err(Error::Lock {
+            path: path.to_string(),
+            source,
+        });
+    }
+
+    Ok(())
+}
+
+/// A lock on a dedicated lock file, held until the value is dropped
+#[derive(Debug)]
+pub struct FileLock {
+    fd: RawFd,
+    mode: LockMode,
+}
+
+impl FileLock {
+    /// Creates the lock file if needed and locks it, fails right away if another process holds a conflicting lock
+    pub fn acquire(path: &str, mode: LockMode) -> Result<Self> {
+        let c_path = CString::new(path).map_err(|err| Error::Open {
+            path: path.to_string(),
+            source: io::Error::new(io::ErrorKind::InvalidInput, err),
+        })?;
+        let fd = unsafe {
+            open(
+                c_path.as_ptr(),
+                O_CREAT | O_RDWR | O_CLOEXEC,
+                S_IRUSR | S_IWUSR,
+            )
+        };
+        if fd < 0 {
+            return Err(Error::Open {
+                path: path.to_string(),
+                source: io::Error::last_os_error(),
+            });
+        }
+
+        if let Err(err) = lock_fd(fd, mode, path) {
+            unsafe { close(fd) };
+            return Err(err);
+        }
+
+        Ok(Self { fd, mode })
+    }
+
+    pub fn mode(&self) -> LockMode {
+        self.mode
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // closing the descriptor releases the lock
+        unsafe { close(self.fd) };
+    }
+}
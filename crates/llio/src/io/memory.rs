@@ -0,0 +1,113 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    error::Result,
+    header::Checkpoint,
+    page::{Page, DEFAULT_PAGE_SIZE},
+};
+
+use super::PageIo;
+
+/// An in-memory page store.
+///
+/// Clones share the same pages and checkpoint, so a clone can be used to "reopen" the storage
+/// after the original handle was dropped.
+#[derive(Debug, Clone)]
+pub struct MemoryFileIo {
+    page_size: usize,
+    pages: Arc<Mutex<HashMap<u64, Box<[u8]>>>>,
+    checkpoint: Arc<Mutex<Option<Checkpoint>>>,
+}
+
+impl MemoryFileIo {
+    pub fn new() -> Self {
+        Self::with_page_size(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn with_page_size(page_size: usize) -> Self {
+        Self {
+            page_size,
+            pages: Arc::default(),
+            checkpoint: Arc::default(),
+        }
+    }
+}
+
+impl Default for MemoryFileIo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PageIo for MemoryFileIo {
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn total_pages(&self) -> u64 {
+        self.pages
+            .lock()
+            .unwrap()
+            .keys()
+            .max()
+            .map(|idx| idx + 1)
+            .unwrap_or(0)
+    }
+
+    fn load_page(&self, idx: u64) -> Result<Page> {
+        let buffer = self
+            .pages
+            .lock()
+            .unwrap()
+            .get(&idx)
+            .cloned()
+            .unwrap_or_else(|| vec![0u8; self.page_size].into_boxed_slice());
+
+        Ok(Page::from_buffer(buffer))
+    }
+
+    fn flush_page(&mut self, idx: u64, page: Page) -> Result<()> {
+        self.pages
+            .lock()
+            .unwrap()
+            .insert(idx, Box::from(page.buffer()));
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        *self.checkpoint.lock().unwrap() = Some(checkpoint);
+
+        Ok(())
+    }
+
+    fn last_checkpoint(&self) -> Option<Checkpoint> {
+        *self.checkpoint.lock().unwrap()
+    }
+
+    fn truncate(&mut self, total_pages: u64) -> Result<()> {
+        self.pages
+            .lock()
+            .unwrap()
+            .retain(|idx, _| *idx < total_pages);
+
+        Ok(())
+    }
+
+    fn discard_pages(&mut self, range: Range<u64>) -> Result<()> {
+        self.pages
+            .lock()
+            .unwrap()
+            .retain(|idx, _| !range.contains(idx));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    cell::RefCell,
+    ffi::{c_void, CString},
+    io,
+    ops::Range,
+    os::fd::RawFd,
+    ptr, slice,
+};
+
+use libc::{
+    close, fstat, madvise, mmap, munmap, open, stat, MADV_NORMAL, MADV_RANDOM, MADV_SEQUENTIAL,
+    MADV_WILLNEED, MAP_FAILED, MAP_SHARED, O_CLOEXEC, O_RDONLY, PROT_READ,
+};
+
+use crate::{
+    error::{Error, Result},
+    header::{Checkpoint, FileHeader, FileKind, HEADER_PAGES},
+    page::{Page, PageView, MIN_PAGE_SIZE},
+    stats::IoStats,
+};
+
+use super::{
+    lock::{lock_fd, LockMode},
+    PageIo,
+};
+
+/// Expected order of page accesses, passed on to the kernel as a `madvise` hint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccessPattern {
+    #[default]
+    Normal,
+    /// Pages are read in order, e.g. by a collection cursor
+    Sequential,
+    /// Pages are read in no particular order, e.g. by index lookups
+    Random,
+}
+
+/// Read-only page storage that maps the whole file into memory.
+///
+/// Pages are handed out as [`PageView`]s borrowed from the mapping instead of being copied
+/// out of a file. The file is locked with
+/// [`LockMode::Shared`] so that no writer can change it while it is mapped.
+/// Compressed and encrypted files cannot be mapped, their pages have to be decoded first.
+pub struct MmapFileIo {
+    fd: RawFd,
+    header: FileHeader,
+    page_size: usize,
+    total_pages: u64,
+    map: *mut u8,
+    len: usize,
+    stats: RefCell<IoStats>,
+}
+
+impl MmapFileIo {
+    /// Maps the existing data file at `path`
+    pub fn new(path: &str, kind: FileKind) -> Result<Self> {
+        let c_path = CString::new(path).map_err(|err| Error::Open {
+            path: path.to_string(),
+            source: io::Error::new(io::ErrorKind::InvalidInput, err),
+        })?;
+        let fd = unsafe { open(c_path.as_ptr(), O_RDONLY | O_CLOEXEC) };
+        if fd < 0 {
+            return Err(Error::Open {
+                path: path.to_string(),
+                source: io::Error::last_os_error(),
+            });
+        }
+
+        match Self::map(fd, path, kind) {
+            Ok(io) => Ok(io),
+            Err(err) => {
+                unsafe { close(fd) };
+                Err(err)
+            }
+        }
+    }
+
+    fn map(fd: RawFd, path: &str, kind: FileKind) -> Result<Self> {
+        lock_fd(fd, LockMode::Shared, path)?;
+
+        let mut file_stat: stat = unsafe { std::mem::zeroed() };
+        if unsafe { fstat(fd, &mut file_stat) } < 0 {
+            return Err(Error::Stat {
+                path: path.to_string(),
+                source: io::Error::last_os_error(),
+            });
+        }
+        let len = file_stat.st_size as usize;
+        if len == 0 {
+            // there is nothing to map, not even a header
+            return Err(Error::InvalidMagic);
+        }
+
+        let map = unsafe { mmap(ptr::null_mut(), len, PROT_READ, MAP_SHARED, fd, 0) };
+        if map == MAP_FAILED {
+            return Err(Error::Map(io::Error::last_os_error()));
+        }
+        let map = map as *mut u8;
+
+        let bytes = unsafe { slice::from_raw_parts(map, len.min(MIN_PAGE_SIZE)) };
+        let header = match FileHeader::from_bytes(bytes, kind) {
+            Ok(header) if header.features() != 0 => {
+                Err(Error::UnsupportedFeatures(header.features()))
+            }
+            header => header,
+        };
+        // a header that claims larger pages than the file holds
+        let header = header.and_then(|header| {
+            let pages = (len / header.page_size() as usize) as u64;
+            match pages.checked_sub(HEADER_PAGES) {
+                Some(total_pages) => Ok((header, total_pages)),
+                None => Err(Error::TruncatedFile { len }),
+            }
+        });
+        let (header, total_pages) = match header {
+            Ok(header) => header,
+            Err(err) => {
+                unsafe { munmap(map as *mut c_void, len) };
+                return Err(err);
+            }
+        };
+
+        let page_size = header.page_size() as usize;
+        Ok(Self {
+            fd,
+            header,
+            page_size,
+            total_pages,
+            map,
+            len,
+            stats: RefCell::default(),
+        })
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    /// Tells the kernel how the pages are going to be read, to tune its read-ahead
+    pub fn advise(&self, access: AccessPattern) -> Result<()> {
+        let advice = match access {
+            AccessPattern::Normal => MADV_NORMAL,
+            AccessPattern::Sequential => MADV_SEQUENTIAL,
+            AccessPattern::Random => MADV_RANDOM,
+        };
+
+        self.madvise(0..self.len, advice)
+    }
+
+    fn madvise(&self, range: Range<usize>, advice: i32) -> Result<()> {
+        if unsafe {
+            madvise(
+                self.map.add(range.start) as *mut c_void,
+                range.len(),
+                advice,
+            )
+        } < 0
+        {
+            return Err(Error::Map(io::Error::last_os_error()));
+        }
+
+        Ok(())
+    }
+
+    fn page_range(&self, range: Range<u64>) -> Range<usize> {
+        let start = range.start.min(self.total_pages) + HEADER_PAGES;
+        let end = range.end.min(self.total_pages) + HEADER_PAGES;
+        (start as usize * self.page_size)..(end as usize * self.page_size)
+    }
+}
+
+// SAFETY: the mapping is read-only and owned by this value, it is unmapped only on drop
+unsafe impl Send for MmapFileIo {}
+
+impl PageIo for MmapFileIo {
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn total_pages(&self) -> u64 {
+        self.total_pages
+    }
+
+    fn load_page(&self, idx: u64) -> Result<Page> {
+        // pages past the end of the file were never written
+        let buffer = match self.view_page(idx) {
+            Some(page) => Box::from(page.buffer()),
+            None => vec![0u8; self.page_size].into_boxed_slice(),
+        };
+
+        Ok(Page::from_buffer(buffer))
+    }
+
+    fn view_page(&self, idx: u64) -> Option<PageView<'_>> {
+        if idx >= self.total_pages {
+            return None;
+        }
+
+        let range = self.page_range(idx..(idx + 1));
+        let mut stats = self.stats.borrow_mut();
+        stats.pages_read += 1;
+        stats.bytes_read += range.len() as u64;
+
+        let bytes = unsafe { slice::from_raw_parts(self.map.add(range.start), range.len()) };
+        Some(PageView::new(bytes))
+    }
+
+    fn flush_page(&mut self, _idx: u64, _page: Page) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    fn last_checkpoint(&self) -> Option<Checkpoint> {
+        self.header.checkpoint()
+    }
+
+    fn prefetch(&self, range: Range<u64>) -> Result<()> {
+        let range = self.page_range(range);
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        self.madvise(range, MADV_WILLNEED)
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn truncate(&mut self, _total_pages: u64) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn discard_pages(&mut self, _range: Range<u64>) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    /// Pages are counted as read when they are viewed, the kernel does the actual reading
+    fn stats(&self) -> IoStats {
+        self.stats.borrow().clone()
+    }
+
+    fn reset_stats(&mut self) {
+        *self.stats.get_mut() = IoStats::default();
+    }
+}
+
+impl Drop for MmapFileIo {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.map as *mut c_void, self.len);
+            close(self.fd);
+        }
+    }
+}
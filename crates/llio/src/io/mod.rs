@@ -1 +1,11 @@
+pub(crate) mod aligned;
 pub mod direct;
+pub mod faulty;
+pub mod lock;
+pub mod memory;
+pub mod mmap;
+pub mod options;
+pub mod page_io;
+pub mod page_map;
+
+pub use page_io::*;
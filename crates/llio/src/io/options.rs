@@ -0,0 +1,98 @@
+use crate::{encryption::EncryptionKey, page::DEFAULT_PAGE_SIZE};
+
+use super::lock::LockMode;
+
+/// Settings applied when a data file is created, existing files keep the ones recorded in their header.
+///
+/// The encryption key and the lock mode are the exception: they apply every time a file is opened.
+#[derive(Debug, Clone)]
+pub struct FileOptions {
+    page_size: usize,
+    compression: bool,
+    encryption_key: Option<EncryptionKey>,
+    lock_mode: LockMode,
+    preallocation: u64,
+}
+
+impl FileOptions {
+    pub fn builder() -> FileOptionsBuilder {
+        FileOptionsBuilder::new()
+    }
+
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    pub fn compression(&self) -> bool {
+        self.compression
+    }
+
+    pub fn encryption_key(&self) -> Option<&EncryptionKey> {
+        self.encryption_key.as_ref()
+    }
+
+    pub fn lock_mode(&self) -> LockMode {
+        self.lock_mode
+    }
+
+    pub fn preallocation(&self) -> u64 {
+        self.preallocation
+    }
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        Self {
+            page_size: DEFAULT_PAGE_SIZE,
+            compression: false,
+            encryption_key: None,
+            lock_mode: LockMode::Exclusive,
+            preallocation: 0,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FileOptionsBuilder {
+    options: FileOptions,
+}
+
+impl FileOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A power of two between 4 KiB and 64 KiB
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.options.page_size = page_size;
+        self
+    }
+
+    /// Compress pages before they are written
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.options.compression = compression;
+        self
+    }
+
+    /// Encrypt pages with the given key, files created with a key can only be opened with it
+    pub fn encryption_key(mut self, key: EncryptionKey) -> Self {
+        self.options.encryption_key = Some(key);
+        self
+    }
+
+    /// `LockMode::Shared` opens existing files for reading only
+    pub fn lock_mode(mut self, lock_mode: LockMode) -> Self {
+        self.options.lock_mode = lock_mode;
+        self
+    }
+
+    /// Reserves disk space `pages` pages at a time ahead of the writes growing the file, 0 disables it
+    pub fn preallocation(mut self, pages: u64) -> Self {
+        self.options.preallocation = pages;
+        self
+    }
+
+    pub fn build(self) -> FileOptions {
+        self.options
+    }
+}
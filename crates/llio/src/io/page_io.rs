@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+use crate::{
+    error::Result,
+    header::Checkpoint,
+    page::{Page, PageView},
+    stats::IoStats,
+};
+
+/// A storage backend that pages are loaded from and flushed to.
+///
+/// Storages are `Send`, a [`Pager`](crate::pager::Pager) moves its storage to a background thread.
+pub trait PageIo: Send {
+    /// Size of every page of this storage, in bytes
+    fn page_size(&self) -> usize;
+
+    fn total_pages(&self) -> u64;
+
+    fn load_page(&self, idx: u64) -> Result<Page>;
+
+    /// Borrows page `idx` without copying it, for storages that keep their pages in memory
+    fn view_page(&self, idx: u64) -> Option<PageView<'_>> {
+        let _ = idx;
+        None
+    }
+
+    fn flush_page(&mut self, idx: u64, page: Page) -> Result<()>;
+
+    /// Whether `flush_page` and the other writes are refused
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// Hints that the pages in `range` are about to be loaded
+    fn prefetch(&self, range: Range<u64>) -> Result<()> {
+        let _ = range;
+        Ok(())
+    }
+
+    /// Writes the pages buffered by `flush_page` out, without waiting for them to become durable
+    fn write_back(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Makes every page passed to `flush_page` so far durable
+    fn sync(&mut self) -> Result<()>;
+
+    /// Syncs and records `checkpoint`, storages without a place for the marker only sync
+    fn checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        let _ = checkpoint;
+        self.sync()
+    }
+
+    /// The last checkpoint recorded by `checkpoint`
+    fn last_checkpoint(&self) -> Option<Checkpoint> {
+        None
+    }
+
+    /// Cuts the storage down to its first `total_pages` pages
+    fn truncate(&mut self, total_pages: u64) -> Result<()>;
+
+    /// Gives the space of the pages in `range` back, they read back either unchanged or zeroed
+    fn discard_pages(&mut self, range: Range<u64>) -> Result<()>;
+
+    /// Snapshot of the I/O counters, storages that do not keep any report zeroes
+    fn stats(&self) -> IoStats {
+        IoStats::default()
+    }
+
+    fn reset_stats(&mut self) {}
+}
@@ -0,0 +1,201 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::page::MIN_PAGE_SIZE;
+
+/// Extents are allocated in blocks, so that every transfer stays aligned for `O_DIRECT`
+pub const EXTENT_BLOCK_SIZE: u64 = MIN_PAGE_SIZE as u64;
+/// Page index (u64) + extent offset (u64) + extent length (u32)
+const PAGE_MAP_ENTRY_SIZE: usize = 20;
+
+/// Location of a stored page in the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+    pub offset: u64,
+    pub len: u32,
+}
+
+impl Extent {
+    fn blocks(&self) -> std::ops::Range<u64> {
+        (self.offset / EXTENT_BLOCK_SIZE)
+            ..(self.offset + self.len as u64).div_ceil(EXTENT_BLOCK_SIZE)
+    }
+}
+
+/// Tracks where every page of a file with variable-sized pages lives.
+///
+/// Several pages share a block when they are flushed together, a block is reused only
+/// after no page references it and the map that stopped referencing it was committed.
+#[derive(Debug, Default)]
+pub struct PageMap {
+    extents: HashMap<u64, Extent>,
+    block_refs: HashMap<u64, u32>,
+    free_blocks: BTreeSet<u64>,
+    released_blocks: Vec<u64>,
+    first_block: u64,
+    end_block: u64,
+}
+
+impl PageMap {
+    /// An empty map of a file whose extents start at `data_offset`
+    pub fn new(data_offset: u64) -> Self {
+        let first_block = data_offset.div_ceil(EXTENT_BLOCK_SIZE);
+        Self {
+            first_block,
+            end_block: first_block,
+            ..Default::default()
+        }
+    }
+
+    /// Restores a committed map, `stored_at` is the extent the map itself was read from
+    pub fn from_bytes(data_offset: u64, bytes: &[u8], stored_at: Extent) -> Option<Self> {
+        let mut map = Self::new(data_offset);
+
+        let count = u64::from_le_bytes(bytes.get(..8)?.try_into().unwrap()) as usize;
+        let entries = bytes.get(8..(8 + count.checked_mul(PAGE_MAP_ENTRY_SIZE)?))?;
+        for entry in entries.chunks(PAGE_MAP_ENTRY_SIZE) {
+            let idx = u64::from_le_bytes(entry[0..8].try_into().unwrap());
+            let extent = Extent {
+                offset: u64::from_le_bytes(entry[8..16].try_into().unwrap()),
+                len: u32::from_le_bytes(entry[16..20].try_into().unwrap()),
+            };
+            if extent.offset < data_offset {
+                return None;
+            }
+            map.insert(idx, extent);
+        }
+
+        map.reference(stored_at);
+
+        // every block below the last used one that nothing references is free
+        map.end_block = map
+            .block_refs
+            .keys()
+            .max()
+            .map(|block| block + 1)
+            .unwrap_or(map.first_block)
+            .max(map.first_block);
+        map.free_blocks = (map.first_block..map.end_block)
+            .filter(|block| !map.block_refs.contains_key(block))
+            .collect();
+
+        Some(map)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.extents.len() * PAGE_MAP_ENTRY_SIZE);
+        bytes.extend_from_slice(&(self.extents.len() as u64).to_le_bytes());
+        for (idx, extent) in &self.extents {
+            bytes.extend_from_slice(&idx.to_le_bytes());
+            bytes.extend_from_slice(&extent.offset.to_le_bytes());
+            bytes.extend_from_slice(&extent.len.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn total_pages(&self) -> u64 {
+        self.extents.keys().max().map(|idx| idx + 1).unwrap_or(0)
+    }
+
+    pub fn get(&self, idx: u64) -> Option<Extent> {
+        self.extents.get(&idx).copied()
+    }
+
+    /// Points the page at a new extent, the blocks of the previous one are released
+    pub fn insert(&mut self, idx: u64, extent: Extent) {
+        self.reference(extent);
+        if let Some(previous) = self.extents.insert(idx, extent) {
+            self.release(previous);
+        }
+    }
+
+    /// Drops the page, its blocks are released
+    pub fn remove(&mut self, idx: u64) {
+        if let Some(extent) = self.extents.remove(&idx) {
+            self.release(extent);
+        }
+    }
+
+    /// Drops every page from `total_pages` on
+    pub fn truncate(&mut self, total_pages: u64) {
+        let removed = self
+            .extents
+            .keys()
+            .filter(|idx| **idx >= total_pages)
+            .copied()
+            .collect::<Vec<_>>();
+        for idx in removed {
+            self.remove(idx);
+        }
+    }
+
+    pub fn reference(&mut self, extent: Extent) {
+        for block in extent.blocks() {
+            *self.block_refs.entry(block).or_default() += 1;
+        }
+    }
+
+    pub fn release(&mut self, extent: Extent) {
+        for block in extent.blocks() {
+            if let Some(refs) = self.block_refs.get_mut(&block) {
+                *refs -= 1;
+                if *refs == 0 {
+                    self.block_refs.remove(&block);
+                    self.released_blocks.push(block);
+                }
+            }
+        }
+    }
+
+    /// Finds room for `len` bytes, the extent is not referenced until it is inserted
+    pub fn allocate(&mut self, len: u64) -> u64 {
+        let blocks = len.div_ceil(EXTENT_BLOCK_SIZE).max(1);
+
+        let mut run_start = None;
+        let mut run_len = 0;
+        let mut found = None;
+        for &block in &self.free_blocks {
+            match run_start {
+                Some(start) if start + run_len == block => run_len += 1,
+                _ => {
+                    run_start = Some(block);
+                    run_len = 1;
+                }
+            }
+            if run_len == blocks {
+                found = run_start;
+                break;
+            }
+        }
+
+        let start = match found {
+            Some(start) => {
+                for block in start..(start + blocks) {
+                    self.free_blocks.remove(&block);
+                }
+                start
+            }
+            None => {
+                let start = self.end_block;
+                self.end_block += blocks;
+                start
+            }
+        };
+
+        start * EXTENT_BLOCK_SIZE
+    }
+
+    /// Called once the map was made durable, blocks released since the last commit become reusable
+    pub fn commit(&mut self) {
+        self.free_blocks.extend(self.released_blocks.drain(..));
+    }
+
+    /// Forgets the free blocks at the end of the file, returns the new end of the file if it moved
+    pub fn shrink(&mut self) -> Option<u64> {
+        let end_block = self.end_block;
+        while self.end_block > self.first_block && self.free_blocks.remove(&(self.end_block - 1)) {
+            self.end_block -= 1;
+        }
+
+        (self.end_block < end_block).then_some(self.end_block * EXTENT_BLOCK_SIZE)
+    }
+}
@@ -1,4 +1,9 @@
+pub mod compression;
+pub mod encryption;
+pub mod error;
+pub mod header;
 pub mod io;
 pub mod page;
 pub mod pager;
+pub mod stats;
 pub mod util;
@@ -1 +1,11 @@
-pub const PAGE_SIZE: usize = 4096; // 4 KiB
+/// Page size of files created without choosing one
+pub const DEFAULT_PAGE_SIZE: usize = 4096; // 4 KiB
+pub const MIN_PAGE_SIZE: usize = 4096; // 4 KiB
+pub const MAX_PAGE_SIZE: usize = 65536; // 64 KiB
+/// Occupied space (u32) + page kind (u8) + 3 reserved bytes
+pub const PAGE_HEADER_SIZE: usize = 8;
+
+/// Page sizes are powers of two between `MIN_PAGE_SIZE` and `MAX_PAGE_SIZE`
+pub fn is_valid_page_size(page_size: usize) -> bool {
+    page_size.is_power_of_two() && (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size)
+}
@@ -0,0 +1,20 @@
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageKind {
+    /// Holds record slots
+    Data = 0,
+    /// Holds a fragment of a record that did not fit into its data page
+    Overflow = 1,
+    /// Lists free pages, saved with a checkpoint so that they are not searched for on open
+    FreeList = 2,
+}
+
+impl From<u8> for PageKind {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => PageKind::Overflow,
+            2 => PageKind::FreeList,
+            _ => PageKind::Data,
+        }
+    }
+}
@@ -1,5 +1,9 @@
 pub mod constants;
+pub mod kind;
 pub mod page;
+pub mod view;
 
 pub use constants::*;
+pub use kind::*;
 pub use page::*;
+pub use view::*;
@@ -3,30 +3,41 @@ use std::{
     ptr,
 };
 
-use super::constants::PAGE_SIZE;
+use super::{constants::PAGE_HEADER_SIZE, kind::PageKind, view::PageView};
 
 #[derive(Debug, Clone)]
 pub struct Page {
-    buffer: Box<[u8; PAGE_SIZE]>,
-    occupied: u16,
+    buffer: Box<[u8]>,
+    occupied: u32,
+    kind: PageKind,
     dirty: bool,
 }
 
 impl Page {
-    pub fn new() -> Self {
+    pub fn new(page_size: usize) -> Self {
         Page {
-            buffer: Box::new([0u8; PAGE_SIZE]),
-            // first two bytes store the occupied space
-            occupied: 2,
+            buffer: vec![0u8; page_size].into_boxed_slice(),
+            // the header stores the occupied space and the page kind
+            occupied: PAGE_HEADER_SIZE as u32,
+            kind: PageKind::Data,
             dirty: false,
         }
     }
 
-    pub fn from_buffer(buffer: Box<[u8; PAGE_SIZE]>) -> Self {
-        let occupied = u16::from_le_bytes((&buffer[..2].try_into()).unwrap()).max(2);
+    pub fn with_kind(page_size: usize, kind: PageKind) -> Self {
+        let mut page = Self::new(page_size);
+        page.set_kind(kind);
+        page
+    }
+
+    pub fn from_buffer(buffer: Box<[u8]>) -> Self {
+        let occupied = u32::from_le_bytes(buffer[..4].try_into().unwrap())
+            .clamp(PAGE_HEADER_SIZE as u32, buffer.len() as u32);
+        let kind = PageKind::from(buffer[4]);
 
         let mut page = Self {
             occupied,
+            kind,
             buffer,
             dirty: false,
         };
@@ -36,44 +47,62 @@ impl Page {
         page
     }
 
-    fn update_occupied(&mut self, occupied: u16) {
+    fn update_occupied(&mut self, occupied: u32) {
         self.occupied = occupied;
         let bytes = self.occupied.to_le_bytes();
         unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), self.buffer.as_mut_ptr(), bytes.len()) };
     }
 
-    pub fn buffer(&self) -> &[u8; PAGE_SIZE] {
+    pub fn kind(&self) -> PageKind {
+        self.kind
+    }
+
+    pub fn set_kind(&mut self, kind: PageKind) {
+        self.kind = kind;
+        self.buffer[4] = kind as u8;
+        self.dirty = true;
+    }
+
+    pub fn buffer(&self) -> &[u8] {
         &self.buffer
     }
 
-    pub fn free(&self) -> u16 {
-        PAGE_SIZE as u16 - self.occupied
+    pub fn size(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn view(&self) -> PageView<'_> {
+        PageView::new(&self.buffer)
+    }
+
+    pub fn free(&self) -> u32 {
+        self.buffer.len() as u32 - self.occupied
     }
 
-    pub fn occupied(&self) -> u16 {
+    pub fn occupied(&self) -> u32 {
         self.occupied
     }
 
     pub fn empty(&self) -> bool {
-        self.occupied == 2
+        self.occupied == PAGE_HEADER_SIZE as u32
     }
 
     pub fn is_full(&self) -> bool {
-        self.occupied == PAGE_SIZE as u16
+        self.occupied as usize == self.buffer.len()
     }
 
     pub fn is_dirty(&self) -> bool {
         self.dirty
     }
 
-    pub fn write_at(&mut self, buf: &[u8], offset: u16) -> io::Result<usize> {
+    pub fn write_at(&mut self, buf: &[u8], offset: u32) -> io::Result<usize> {
         let offset = if offset < self.occupied {
             self.occupied
         } else {
             offset
         };
 
-        let bytes_to_write = buf.len().min(PAGE_SIZE - offset as usize);
+        let bytes_to_write = buf.len().min(self.buffer.len() - offset as usize);
         unsafe {
             ptr::copy(
                 buf.as_ptr(),
@@ -84,13 +113,13 @@ impl Page {
 
         self.dirty = true;
 
-        self.update_occupied(offset + bytes_to_write as u16);
+        self.update_occupied(offset + bytes_to_write as u32);
 
         Ok(bytes_to_write)
     }
 
-    pub fn replace_at(&mut self, buf: &[u8], offset: u16) -> io::Result<usize> {
-        let bytes_to_write = buf.len().min(PAGE_SIZE - offset as usize);
+    pub fn replace_at(&mut self, buf: &[u8], offset: u32) -> io::Result<usize> {
+        let bytes_to_write = buf.len().min(self.buffer.len() - offset as usize);
         unsafe {
             ptr::copy(
                 buf.as_ptr(),
@@ -101,13 +130,13 @@ impl Page {
 
         self.dirty = true;
 
-        self.update_occupied(offset + bytes_to_write as u16);
+        self.update_occupied(self.occupied.max(offset + bytes_to_write as u32));
 
         Ok(bytes_to_write)
     }
 
-    pub fn erase_at(&mut self, size: usize, offset: u16) -> io::Result<usize> {
-        let bytes_to_erase = size.min(PAGE_SIZE - offset as usize);
+    pub fn erase_at(&mut self, size: usize, offset: u32) -> io::Result<usize> {
+        let bytes_to_erase = size.min(self.buffer.len() - offset as usize);
 
         unsafe {
             ptr::copy(
@@ -122,8 +151,8 @@ impl Page {
         Ok(bytes_to_erase)
     }
 
-    pub fn read_at(&mut self, buf: &mut [u8], offset: u16) -> io::Result<usize> {
-        let bytes_to_read = buf.len().min(PAGE_SIZE - offset as usize);
+    pub fn read_at(&mut self, buf: &mut [u8], offset: u32) -> io::Result<usize> {
+        let bytes_to_read = buf.len().min(self.buffer.len() - offset as usize);
         if bytes_to_read == 0 {
             return Ok(0);
         }
@@ -154,6 +183,6 @@ impl Write for Page {
 
 impl Read for Page {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.read_at(buf, 2)
+        self.read_at(buf, PAGE_HEADER_SIZE as u32)
     }
 }
@@ -0,0 +1,48 @@
+use super::{constants::PAGE_HEADER_SIZE, kind::PageKind};
+
+/// A read-only view of a page that borrows its bytes instead of owning them
+#[derive(Debug, Clone, Copy)]
+pub struct PageView<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> PageView<'a> {
+    /// `buffer` has to hold a whole page, header included
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    pub fn kind(&self) -> PageKind {
+        PageKind::from(self.buffer[4])
+    }
+
+    pub fn buffer(&self) -> &'a [u8] {
+        self.buffer
+    }
+
+    pub fn size(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn occupied(&self) -> u32 {
+        u32::from_le_bytes(self.buffer[..4].try_into().unwrap())
+            .clamp(PAGE_HEADER_SIZE as u32, self.buffer.len() as u32)
+    }
+
+    pub fn empty(&self) -> bool {
+        self.occupied() == PAGE_HEADER_SIZE as u32
+    }
+
+    /// Borrows up to `len` bytes starting at `offset`, clamped to the end of the page
+    pub fn bytes_at(&self, offset: u32, len: usize) -> &'a [u8] {
+        let start = (offset as usize).min(self.buffer.len());
+        let end = start.saturating_add(len).min(self.buffer.len());
+        &self.buffer[start..end]
+    }
+
+    pub fn read_at(&self, buf: &mut [u8], offset: u32) -> usize {
+        let bytes = self.bytes_at(offset, buf.len());
+        buf[..bytes.len()].copy_from_slice(bytes);
+        bytes.len()
+    }
+}
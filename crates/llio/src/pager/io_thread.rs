@@ -0,0 +1,210 @@
+use std::{
+    collections::HashSet,
+    io,
+    ops::Range,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{header::Checkpoint, io::PageIo, page::Page, stats::IoStats};
+
+use super::options::PagerOptions;
+
+/// When the thread writes changed pages back on its own
+#[derive(Debug, Clone, Copy)]
+struct FlushPolicy {
+    interval: Duration,
+    dirty_pages: usize,
+}
+
+enum Request {
+    Load(u64, Sender<io::Result<Page>>),
+    Flush(u64, Page),
+    Prefetch(Range<u64>),
+    Sync(Sender<io::Result<()>>),
+    Checkpoint(Checkpoint, Sender<io::Result<()>>),
+    Truncate(u64, Sender<io::Result<()>>),
+    Discard(Range<u64>, Sender<io::Result<()>>),
+    Stats(Sender<IoStats>),
+    ResetStats,
+}
+
+/// A background thread that owns a storage, pages are loaded and written through it.
+///
+/// Writes are queued without waiting for them, a failed write is reported by every following
+/// `sync`, `truncate` and `discard_pages`. Requests are served in order, so a load always
+/// sees the pages flushed before it. Between requests, the thread writes changed pages back
+/// once enough of them piled up or the oldest one waited for the flush interval.
+pub struct IoThread {
+    requests: Option<Sender<Request>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl IoThread {
+    pub fn spawn(io: Box<dyn PageIo>, options: &PagerOptions) -> Self {
+        let policy = options.background_flush().then(|| FlushPolicy {
+            interval: options.flush_interval(),
+            dirty_pages: options.dirty_pages(),
+        });
+
+        let (requests, receiver) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("llio-io".to_string())
+            .spawn(move || Self::run(io, receiver, policy))
+            .expect("failed to spawn the storage thread");
+
+        Self {
+            requests: Some(requests),
+            handle: Some(handle),
+        }
+    }
+
+    fn run(mut io: Box<dyn PageIo>, requests: Receiver<Request>, policy: Option<FlushPolicy>) {
+        let mut failed: Option<io::Error> = None;
+        let check = |failed: &Option<io::Error>| match failed {
+            Some(err) => Err(io::Error::new(
+                err.kind(),
+                format!("a page could not be written: {err}"),
+            )),
+            None => Ok(()),
+        };
+
+        // distinct pages flushed since the last write-back and when the first of them was
+        let mut dirty = HashSet::new();
+        let mut dirty_since = Instant::now();
+
+        loop {
+            let request = match policy {
+                Some(policy) if !dirty.is_empty() => {
+                    let timeout = policy.interval.saturating_sub(dirty_since.elapsed());
+                    match requests.recv_timeout(timeout) {
+                        Ok(request) => Some(request),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                _ => match requests.recv() {
+                    Ok(request) => Some(request),
+                    Err(_) => break,
+                },
+            };
+
+            match request {
+                None => {}
+                Some(Request::Load(idx, reply)) => {
+                    reply.send(io.load_page(idx).map_err(Into::into)).ok();
+                }
+                Some(Request::Flush(idx, page)) => {
+                    if failed.is_none() {
+                        failed = io.flush_page(idx, page).err().map(Into::into);
+                    }
+                    if dirty.is_empty() {
+                        dirty_since = Instant::now();
+                    }
+                    dirty.insert(idx);
+                }
+                Some(Request::Prefetch(range)) => {
+                    // only a hint, a failed prefetch is retried by the loads that follow
+                    io.prefetch(range).ok();
+                }
+                Some(Request::Sync(reply)) => {
+                    let result = check(&failed).and_then(|_| Ok(io.sync()?));
+                    dirty.clear();
+                    reply.send(result).ok();
+                }
+                Some(Request::Checkpoint(checkpoint, reply)) => {
+                    let result = check(&failed).and_then(|_| Ok(io.checkpoint(checkpoint)?));
+                    dirty.clear();
+                    reply.send(result).ok();
+                }
+                Some(Request::Truncate(total_pages, reply)) => {
+                    let result = check(&failed).and_then(|_| Ok(io.truncate(total_pages)?));
+                    reply.send(result).ok();
+                }
+                Some(Request::Discard(range, reply)) => {
+                    let result = check(&failed).and_then(|_| Ok(io.discard_pages(range)?));
+                    reply.send(result).ok();
+                }
+                Some(Request::Stats(reply)) => {
+                    reply.send(io.stats()).ok();
+                }
+                Some(Request::ResetStats) => io.reset_stats(),
+            }
+
+            if let Some(policy) = policy {
+                let due = dirty.len() >= policy.dirty_pages
+                    || (!dirty.is_empty() && dirty_since.elapsed() >= policy.interval);
+                if due {
+                    if failed.is_none() {
+                        failed = io.write_back().err().map(Into::into);
+                    }
+                    dirty.clear();
+                }
+            }
+        }
+    }
+
+    fn stopped() -> io::Error {
+        io::Error::other("the storage thread has stopped")
+    }
+
+    fn send(&self, request: Request) -> io::Result<()> {
+        self.requests
+            .as_ref()
+            .and_then(|requests| requests.send(request).ok())
+            .ok_or_else(Self::stopped)
+    }
+
+    fn call<T>(&self, request: impl FnOnce(Sender<T>) -> Request) -> io::Result<T> {
+        let (reply, receiver) = mpsc::channel();
+        self.send(request(reply))?;
+        receiver.recv().map_err(|_| Self::stopped())
+    }
+
+    pub fn load_page(&self, idx: u64) -> io::Result<Page> {
+        self.call(|reply| Request::Load(idx, reply))?
+    }
+
+    pub fn flush_page(&self, idx: u64, page: Page) -> io::Result<()> {
+        self.send(Request::Flush(idx, page))
+    }
+
+    pub fn prefetch(&self, range: Range<u64>) -> io::Result<()> {
+        self.send(Request::Prefetch(range))
+    }
+
+    pub fn sync(&self) -> io::Result<()> {
+        self.call(Request::Sync)?
+    }
+
+    pub fn checkpoint(&self, checkpoint: Checkpoint) -> io::Result<()> {
+        self.call(|reply| Request::Checkpoint(checkpoint, reply))?
+    }
+
+    pub fn truncate(&self, total_pages: u64) -> io::Result<()> {
+        self.call(|reply| Request::Truncate(total_pages, reply))?
+    }
+
+    pub fn discard_pages(&self, range: Range<u64>) -> io::Result<()> {
+        self.call(|reply| Request::Discard(range, reply))?
+    }
+
+    pub fn stats(&self) -> IoStats {
+        self.call(Request::Stats).unwrap_or_default()
+    }
+
+    pub fn reset_stats(&self) {
+        self.send(Request::ResetStats).ok();
+    }
+}
+
+impl Drop for IoThread {
+    fn drop(&mut self) {
+        // the thread drains the queued writes and drops the storage once the channel is closed
+        self.requests.take();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
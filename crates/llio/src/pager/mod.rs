@@ -1,3 +1,7 @@
+mod io_thread;
+pub mod options;
 pub mod pager;
+mod pool;
 
+pub use options::*;
 pub use pager::*;
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+/// Pages a [`Pager`](super::Pager) keeps in memory unless told otherwise
+pub const BUFFER_POOL_PAGES: usize = 256;
+
+/// Settings of a [`Pager`](super::Pager)
+#[derive(Debug, Clone)]
+pub struct PagerOptions {
+    pool_size: usize,
+    background_flush: bool,
+    flush_interval: Duration,
+    dirty_ratio: f64,
+}
+
+impl PagerOptions {
+    pub fn builder() -> PagerOptionsBuilder {
+        PagerOptionsBuilder::new()
+    }
+
+    pub fn pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    pub fn background_flush(&self) -> bool {
+        self.background_flush
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        self.flush_interval
+    }
+
+    pub fn dirty_ratio(&self) -> f64 {
+        self.dirty_ratio
+    }
+
+    /// Number of changed pages that are written back right away, at least one
+    pub fn dirty_pages(&self) -> usize {
+        ((self.pool_size as f64 * self.dirty_ratio).ceil() as usize).max(1)
+    }
+}
+
+impl Default for PagerOptions {
+    fn default() -> Self {
+        Self {
+            pool_size: BUFFER_POOL_PAGES,
+            background_flush: true,
+            flush_interval: Duration::from_secs(1),
+            dirty_ratio: 0.25,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PagerOptionsBuilder {
+    options: PagerOptions,
+}
+
+impl PagerOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pages kept in memory besides the ones currently latched
+    pub fn pool_size(mut self, pages: usize) -> Self {
+        self.options.pool_size = pages;
+        self
+    }
+
+    /// Write changed pages back in the background, otherwise they are written on `sync`
+    /// or when the buffers of the storage fill up
+    pub fn background_flush(mut self, background_flush: bool) -> Self {
+        self.options.background_flush = background_flush;
+        self
+    }
+
+    /// Longest time a changed page waits before it is written back
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        self.options.flush_interval = interval;
+        self
+    }
+
+    /// Share of the buffer pool that may be changed before the pages are written back
+    pub fn dirty_ratio(mut self, ratio: f64) -> Self {
+        self.options.dirty_ratio = ratio;
+        self
+    }
+
+    pub fn build(self) -> PagerOptions {
+        self.options
+    }
+}
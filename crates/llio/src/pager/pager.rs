@@ -1,115 +1,780 @@
-use std::io::{self, Read, Write};
+use std::{
+    collections::HashSet,
+    io::{self, Read, Write},
+    mem,
+    ops::Range,
+    sync::{Mutex, MutexGuard},
+};
 
-use crate::io::direct::DirectFileIo;
+use crate::{
+    error::Error,
+    header::Checkpoint,
+    io::PageIo,
+    page::{Page, PageKind, PageView, PAGE_HEADER_SIZE},
+    stats::PagerStats,
+};
 
-/// Pager is an abstraction over hardware pages on the drive
-pub struct Pager {
-    io: DirectFileIo,
+use super::{
+    io_thread::IoThread,
+    options::PagerOptions,
+    pool::{BufferPool, LatchedPage},
+};
+
+/// Record length (u32) + head fragment capacity (u32) + first overflow page (u64)
+pub const SLOT_HEADER_SIZE: usize = 16;
+/// Next overflow page (u64) stored right after the page header
+pub const OVERFLOW_HEADER_SIZE: usize = 8;
+/// Next free list page (u64) stored right after the page header, the listed pages (u64 each) follow
+pub const FREE_LIST_HEADER_SIZE: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+struct SlotHeader {
+    len: u32,
+    capacity: u32,
+    // page 0 is always a data page, so it marks the end of the chain
+    next: u64,
+}
+
+impl SlotHeader {
+    fn read(page: PageView, offset: u32) -> Self {
+        let mut bytes = [0u8; SLOT_HEADER_SIZE];
+        page.read_at(&mut bytes, offset);
+
+        Self {
+            len: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            capacity: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            next: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; SLOT_HEADER_SIZE] {
+        let mut bytes = [0u8; SLOT_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&self.len.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.capacity.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.next.to_le_bytes());
+        bytes
+    }
+}
+
+struct Allocation {
     last_free_page: u64,
+    next_page: u64,
+    free_pages: Vec<u64>,
+    /// Released overflow pages a durable record may still point at, they are left as they are
+    /// and handed out again only after the next sync
+    released_pages: Vec<u64>,
+    checkpoint: Option<Checkpoint>,
+}
+
+impl Allocation {
+    fn page(&mut self) -> u64 {
+        self.free_pages.pop().unwrap_or_else(|| {
+            let idx = self.next_page;
+            self.next_page += 1;
+            idx
+        })
+    }
+
+    fn data_page(&mut self) -> u64 {
+        // data pages are only appended, so that records keep their insertion order
+        let idx = self.next_page;
+        self.next_page += 1;
+        self.last_free_page = idx;
+        idx
+    }
+}
+
+/// Pager is an abstraction over hardware pages on the drive.
+///
+/// Records live in slots on data pages. A record that does not fit into its slot
+/// keeps the first fragment in the slot and the rest in a chain of overflow pages.
+///
+/// A pager is meant to be shared between threads through an `Arc`. Pages are cached in a
+/// buffer pool and latched while they are read or changed: readers run concurrently, writers
+/// take turns. The storage is owned by a background thread that every load and write goes through.
+pub struct Pager {
+    io: IoThread,
+    pool: BufferPool,
+    page_size: usize,
+    read_only: bool,
+    allocation: Mutex<Allocation>,
+    stats: Mutex<PagerStats>,
 }
 
 impl Pager {
-    pub fn new(io: DirectFileIo) -> Self {
+    pub fn new(io: impl PageIo + 'static) -> Self {
+        Self::with_options(io, PagerOptions::default())
+    }
+
+    /// Pager that keeps at most `pages` pages in memory, besides the ones currently latched
+    pub fn with_pool_size(io: impl PageIo + 'static, pages: usize) -> Self {
+        Self::with_options(io, PagerOptions::builder().pool_size(pages).build())
+    }
+
+    pub fn with_options(io: impl PageIo + 'static, options: PagerOptions) -> Self {
+        let next_page = io.total_pages().max(1);
+
+        // pages appended since the last checkpoint are scanned, the marker knows about the rest
+        let checkpoint = io.last_checkpoint();
+        let recorded = checkpoint.filter(|checkpoint| checkpoint.last_data_page() < next_page);
+        let scan_from = recorded
+            .map(|checkpoint| checkpoint.total_pages().min(next_page))
+            .unwrap_or(0);
+
+        let inspect =
+            |idx: u64, f: &dyn Fn(PageView) -> bool| Self::inspect(&io, idx, f).unwrap_or(false);
+
+        // records are appended to the last data page, trailing overflow pages and holes are skipped
+        let last_free_page = (scan_from..next_page)
+            .rev()
+            .find(|&idx| inspect(idx, &|page| page.kind() == PageKind::Data && !page.empty()))
+            .or(recorded.map(|checkpoint| checkpoint.last_data_page()))
+            .unwrap_or(0);
+
+        // released overflow pages and the holes punched for them hold nothing, so they are free again.
+        // The checkpoint lists the ones it knew about, only the pages appended since are scanned
+        let mut free_pages = match (io.read_only(), recorded) {
+            (true, _) => Vec::new(),
+            (false, Some(checkpoint)) => Self::saved_free_pages(&io, checkpoint, next_page)
+                .into_iter()
+                .chain((scan_from..next_page).filter(|&idx| inspect(idx, &Self::is_free)))
+                .collect(),
+            (false, None) => (0..next_page)
+                .filter(|&idx| inspect(idx, &Self::is_free))
+                .collect(),
+        };
+        free_pages.retain(|&idx| idx != last_free_page);
+
         Self {
-            io,
-            last_free_page: 0,
+            page_size: io.page_size(),
+            read_only: io.read_only(),
+            io: IoThread::spawn(Box::new(io), &options),
+            pool: BufferPool::new(options.pool_size()),
+            allocation: Mutex::new(Allocation {
+                last_free_page,
+                next_page,
+                free_pages,
+                released_pages: Vec::new(),
+                checkpoint,
+            }),
+            stats: Mutex::default(),
         }
     }
 
-    pub fn read_at(&self, buf: &mut [u8], offset: (u64, u16)) -> io::Result<usize> {
-        let mut bytes_read = 0;
-        let mut page_idx = offset.0;
-        let mut page = self.io.load_page(page_idx)?;
-        bytes_read += page.read_at(&mut buf[bytes_read..], offset.1)?;
-        page_idx += 1;
-        while bytes_read < buf.len() {
-            let mut page = self.io.load_page(page_idx)?;
-            bytes_read += page.read(&mut buf[bytes_read..])?;
-            page_idx += 1;
+    /// Runs `f` on page `idx` of a storage that no pager owns yet, `None` if it cannot be read
+    fn inspect<T>(io: &dyn PageIo, idx: u64, f: impl FnOnce(PageView) -> T) -> Option<T> {
+        match io.view_page(idx) {
+            Some(page) => Some(f(page)),
+            None => io.load_page(idx).ok().map(|page| f(page.view())),
+        }
+    }
+
+    /// Pages listing the free ones are free themselves, they are only read on open
+    fn is_free(page: PageView) -> bool {
+        page.empty() || page.kind() == PageKind::FreeList
+    }
+
+    /// The free pages listed with `checkpoint` that are still free, any of them could have been
+    /// used again after the checkpoint was taken. A list page used again ends the list early,
+    /// the pages it listed stay unused until the space is reclaimed.
+    fn saved_free_pages(io: &dyn PageIo, checkpoint: Checkpoint, next_page: u64) -> Vec<u64> {
+        let mut free_pages = Vec::new();
+        let mut visited = HashSet::new();
+
+        let mut list = checkpoint.free_list();
+        while list != 0 && list < next_page && visited.insert(list) {
+            let listed = Self::inspect(io, list, |page| {
+                let len = page.occupied() as usize - PAGE_HEADER_SIZE;
+                let bytes = page.bytes_at(PAGE_HEADER_SIZE as u32, len);
+                (page.kind() == PageKind::FreeList && len >= FREE_LIST_HEADER_SIZE).then(|| {
+                    bytes
+                        .chunks_exact(mem::size_of::<u64>())
+                        .map(|idx| u64::from_le_bytes(idx.try_into().unwrap()))
+                        .collect::<Vec<_>>()
+                })
+            });
+            let Some(Some(listed)) = listed else {
+                break;
+            };
+
+            free_pages.push(list);
+            free_pages.extend(listed[1..].iter().filter(|&&idx| {
+                idx < next_page && Self::inspect(io, idx, Self::is_free).unwrap_or(false)
+            }));
+            list = listed[0];
+        }
+
+        free_pages
+    }
+
+    /// Lists the free pages in pages taken from among them, returns the first of the list
+    fn save_free_list(&self, allocation: &mut Allocation) -> io::Result<u64> {
+        let free_pages = &mut allocation.free_pages;
+        free_pages.sort_unstable();
+        free_pages.dedup();
+
+        // a list page is free itself, so it counts towards the pages to list
+        let per_page =
+            (self.page_size - PAGE_HEADER_SIZE - FREE_LIST_HEADER_SIZE) / mem::size_of::<u64>();
+        let count = free_pages.len().div_ceil(per_page + 1);
+        let (lists, listed) = free_pages.split_at(count);
+
+        let mut chunks = listed.chunks(per_page);
+        for (i, &idx) in lists.iter().enumerate() {
+            let next = lists.get(i + 1).copied().unwrap_or(0);
+            let mut page = Page::with_kind(self.page_size, PageKind::FreeList);
+            page.write_all(&next.to_le_bytes())?;
+            for idx in chunks.next().unwrap_or_default() {
+                page.write_all(&idx.to_le_bytes())?;
+            }
+            self.write_page(idx, page)?;
+        }
+
+        Ok(lists.first().copied().unwrap_or(0))
+    }
+
+    /// Snapshot of the record counters and those of the underlying storage
+    pub fn stats(&self) -> PagerStats {
+        PagerStats {
+            io: self.io.stats(),
+            ..self.stats.lock().unwrap().clone()
+        }
+    }
+
+    pub fn reset_stats(&self) {
+        *self.stats.lock().unwrap() = PagerStats::default();
+        self.io.reset_stats();
+    }
+
+    /// Takes the allocation lock every change to the records holds, writers are refused up front
+    /// as their pages would already be changed in the pool by the time the storage refuses them
+    fn allocation(&self) -> io::Result<MutexGuard<'_, Allocation>> {
+        if self.read_only {
+            return Err(Error::ReadOnly.into());
+        }
+        Ok(self.allocation.lock().unwrap())
+    }
+
+    /// Page `idx` from the buffer pool, loaded from the storage on a miss
+    fn page(&self, idx: u64) -> io::Result<LatchedPage> {
+        self.pool.page(idx, || self.io.load_page(idx))
+    }
+
+    /// Runs `f` on page `idx` while holding its read latch
+    fn with_page<T>(&self, idx: u64, f: impl FnOnce(PageView) -> io::Result<T>) -> io::Result<T> {
+        let page = self.page(idx)?;
+        let page = page.read().unwrap();
+        f(page.view())
+    }
+
+    /// Replaces the latched page `idx` with `page` and queues it for writing
+    fn store(&self, idx: u64, latched: &mut Page, page: Page) -> io::Result<()> {
+        *latched = page.clone();
+        self.io.flush_page(idx, page)
+    }
+
+    /// Writes a page that nothing points at yet, it is not loaded first
+    fn write_page(&self, idx: u64, page: Page) -> io::Result<()> {
+        let latched = self.pool.page(idx, || Ok(page.clone()))?;
+        let mut latched = latched.write().unwrap();
+        self.store(idx, &mut latched, page)
+    }
+
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Amount of record bytes a single overflow page holds
+    pub fn overflow_page_capacity(&self) -> usize {
+        self.page_size() - PAGE_HEADER_SIZE - OVERFLOW_HEADER_SIZE
+    }
+
+    /// Makes all the pages written so far durable
+    pub fn sync(&self) -> io::Result<()> {
+        let mut allocation = self.allocation.lock().unwrap();
+        self.sync_released(&mut allocation)
+    }
+
+    /// Syncs and hands the released pages out again, no durable record points at them anymore
+    fn sync_released(&self, allocation: &mut Allocation) -> io::Result<()> {
+        self.io.sync()?;
+        self.free_released(allocation)
+    }
+
+    fn free_released(&self, allocation: &mut Allocation) -> io::Result<()> {
+        for idx in mem::take(&mut allocation.released_pages) {
+            self.write_page(idx, Page::with_kind(self.page_size, PageKind::Overflow))?;
+            allocation.free_pages.push(idx);
+        }
+        Ok(())
+    }
+
+    /// Writes every changed page and the list of free pages, syncs and records a checkpoint that
+    /// the next pager opened on the storage starts from
+    pub fn checkpoint(&self) -> io::Result<Checkpoint> {
+        let mut allocation = self.allocation()?;
+        self.record_checkpoint(&mut allocation)
+    }
+
+    fn record_checkpoint(&self, allocation: &mut Allocation) -> io::Result<Checkpoint> {
+        let sequence = allocation
+            .checkpoint
+            .map(|checkpoint| checkpoint.sequence() + 1)
+            .unwrap_or(1);
+
+        // released pages are freed first, so that the saved list has them as well
+        if !allocation.released_pages.is_empty() {
+            self.sync_released(allocation)?;
+        }
+        let free_list = self.save_free_list(allocation)?;
+        let checkpoint = Checkpoint::new(
+            sequence,
+            allocation.next_page,
+            allocation.last_free_page,
+            free_list,
+        );
+
+        self.io.checkpoint(checkpoint)?;
+        allocation.checkpoint = Some(checkpoint);
+
+        Ok(checkpoint)
+    }
+
+    /// The latest checkpoint, recorded by this pager or found in the storage
+    pub fn last_checkpoint(&self) -> Option<Checkpoint> {
+        self.allocation.lock().unwrap().checkpoint
+    }
+
+    /// Gives the space of released overflow pages back to the storage, the ones at the end are cut off.
+    /// Syncs first, so that no durable record points at them anymore, and records a checkpoint
+    /// of the shortened storage at the end. Returns how many pages were freed.
+    ///
+    /// Every page is looked at, the ones freed after the checkpoint a pager was opened from are
+    /// only known to that pager and are found here once it was closed without another checkpoint.
+    pub fn reclaim_space(&self) -> io::Result<u64> {
+        let mut allocation = self.allocation()?;
+        self.sync_released(&mut allocation)?;
+
+        for idx in 0..allocation.next_page {
+            if idx != allocation.last_free_page
+                && self.with_page(idx, |page| Ok(Self::is_free(page)))?
+            {
+                allocation.free_pages.push(idx);
+            }
+        }
+
+        let Allocation {
+            next_page,
+            free_pages,
+            ..
+        } = &mut *allocation;
+        free_pages.sort_unstable();
+        free_pages.dedup();
+        let reclaimed = free_pages.len() as u64;
+
+        let total_pages = *next_page;
+        while free_pages.last() == Some(&(*next_page - 1)) {
+            free_pages.pop();
+            *next_page -= 1;
+        }
+        if *next_page < total_pages {
+            self.pool.discard(*next_page..total_pages);
+            self.io.truncate(*next_page)?;
+        }
+
+        // the rest stays on the free list, so that new chains still reuse them
+        let mut runs: Vec<Range<u64>> = Vec::new();
+        for &idx in free_pages.iter() {
+            match runs.last_mut() {
+                Some(run) if run.end == idx => run.end += 1,
+                _ => runs.push(idx..(idx + 1)),
+            }
+        }
+        for run in runs {
+            self.pool.discard(run.clone());
+            self.io.discard_pages(run)?;
         }
 
-        Ok(bytes_read)
+        self.record_checkpoint(&mut allocation)?;
+
+        Ok(reclaimed)
+    }
+
+    /// Loads the pages in `range` ahead of time, so that the following reads are served from memory
+    pub fn prefetch(&self, range: Range<u64>) -> io::Result<()> {
+        self.io.prefetch(range)
+    }
+
+    pub fn read_at(&self, buf: &mut [u8], offset: (u64, u32)) -> io::Result<usize> {
+        self.with_page(offset.0, |page| Ok(page.read_at(buf, offset.1)))
     }
 
     pub fn buffer(&self, offset: u64) -> io::Result<Box<[u8]>> {
-        let page = self.io.load_page(offset)?;
-        Ok(page
-            .buffer()
-            .into_iter()
-            .map(|&b| b)
-            .collect::<Vec<u8>>()
-            .into_boxed_slice())
-    }
-
-    pub fn write_at(&mut self, buf: &[u8], offset: (u64, u16)) -> io::Result<(usize, u64)> {
-        let mut bytes_written = 0;
-        let mut page_idx = offset.0;
-        let mut page = self.io.load_page(page_idx)?;
-        bytes_written += page.write_at(&buf[bytes_written..], offset.1)?;
-        self.io.flush_page(page_idx, page)?;
-
-        while bytes_written < buf.len() {
-            page_idx += 1;
-            let mut page = self.io.load_page(page_idx)?;
-            bytes_written += page.write_at(&buf[bytes_written..], 2)?;
-            self.io.flush_page(page_idx, page)?;
+        self.with_page(offset, |page| Ok(Box::from(page.buffer())))
+    }
+
+    fn broken_chain(page: u64) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("page {page} is not part of an overflow chain"),
+        )
+    }
+
+    /// Reads the slot header at `offset`, failing if the slot does not fit in the page
+    fn slot(page: PageView, offset: u32) -> io::Result<SlotHeader> {
+        let header = SlotHeader::read(page, offset);
+        let end = (offset as usize).checked_add(SLOT_HEADER_SIZE + header.capacity as usize);
+        let fits = end.is_some_and(|end| end <= page.buffer().len());
+        match offset as usize >= PAGE_HEADER_SIZE && fits {
+            true => Ok(header),
+            false => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no record slot at offset {offset}"),
+            )),
         }
+    }
+
+    /// Collects the overflow pages of a chain starting at `next`, `head` is the latched data page of its record
+    fn overflow_pages(&self, head: u64, mut next: u64) -> io::Result<Vec<u64>> {
+        let mut pages = Vec::new();
+        while next != 0 {
+            if next == head || pages.contains(&next) {
+                return Err(Self::broken_chain(next));
+            }
+            pages.push(next);
 
-        self.last_free_page = page_idx;
+            next = self.with_page(next, |page| {
+                if page.kind() != PageKind::Overflow {
+                    return Err(Self::broken_chain(next));
+                }
 
-        Ok((bytes_written, page_idx))
+                let mut bytes = [0u8; OVERFLOW_HEADER_SIZE];
+                page.read_at(&mut bytes, PAGE_HEADER_SIZE as u32);
+                Ok(u64::from_le_bytes(bytes))
+            })?;
+        }
+
+        Ok(pages)
     }
 
-    pub fn occupied(&self) -> io::Result<(u64, u16)> {
-        Ok((
-            self.last_free_page,
-            self.io.load_page(self.last_free_page)?.occupied(),
-        ))
+    /// Fills the `capacity` bytes of `head` at `offset` and then a fresh overflow chain with
+    /// the `len` bytes of a record, returns the first page of the chain
+    fn fill_record<E: From<io::Error>>(
+        &self,
+        allocation: &mut Allocation,
+        (head, offset, capacity): (&mut Page, u32, usize),
+        len: usize,
+        fill: impl FnOnce(&mut dyn Write) -> Result<(), E>,
+    ) -> Result<u64, E> {
+        let page_capacity = self.overflow_page_capacity();
+        let count = (len - capacity).div_ceil(page_capacity);
+        let pages = (0..count).map(|_| allocation.page()).collect::<Vec<_>>();
+
+        let mut chain = Vec::with_capacity(count);
+        for i in 0..count {
+            let next = pages.get(i + 1).copied().unwrap_or(0);
+            let mut page = Page::with_kind(self.page_size, PageKind::Overflow);
+            page.write_at(&next.to_le_bytes(), PAGE_HEADER_SIZE as u32)?;
+            chain.push(page);
+        }
+
+        let mut parts = vec![(head, offset, capacity)];
+        let mut remaining = len - capacity;
+        for page in chain.iter_mut() {
+            let room = remaining.min(page_capacity);
+            parts.push((page, (PAGE_HEADER_SIZE + OVERFLOW_HEADER_SIZE) as u32, room));
+            remaining -= room;
+        }
+        let mut writer = RecordWriter {
+            parts: parts.into_iter().rev().filter(|part| part.2 > 0).collect(),
+            written: 0,
+        };
+
+        if let Err(err) = fill(&mut writer).and_then(|_| Ok(writer.finish(len)?)) {
+            // nothing points at the chain yet, its pages can be handed out again
+            allocation.free_pages.extend(pages);
+            return Err(err);
+        }
+
+        self.stats.lock().unwrap().overflow_pages_written += count as u64;
+
+        for (&idx, page) in pages.iter().zip(chain) {
+            self.write_page(idx, page)?;
+        }
+        // writes reach the storage in any order, the chain is made durable before the slot
+        // that points at it is written
+        if count > 0 {
+            self.sync_released(allocation)?;
+        }
+
+        Ok(pages.first().copied().unwrap_or(0))
     }
 
-    pub fn erase_at(&mut self, size: usize, offset: (u64, u16)) -> io::Result<usize> {
-        let mut bytes_erased = 0;
-        let mut page_idx = offset.0;
-        let mut page = self.io.load_page(page_idx)?;
-        bytes_erased += page.erase_at(size, offset.1)?;
-        self.io.flush_page(page_idx, page)?;
+    /// Appends a record, returns the position of its slot
+    pub fn write_record(&self, buf: &[u8]) -> io::Result<(u64, u32)> {
+        self.write_record_with(buf.len(), |w| w.write_all(buf))
+    }
 
-        while bytes_erased < size {
-            page_idx += 1;
-            let mut page = self.io.load_page(page_idx)?;
-            bytes_erased += page.erase_at(size - bytes_erased, 2)?;
-            self.io.flush_page(page_idx, page)?;
+    /// Appends a record of `len` bytes that `fill` writes straight into its pages
+    pub fn write_record_with<E: From<io::Error>>(
+        &self,
+        len: usize,
+        fill: impl FnOnce(&mut dyn Write) -> Result<(), E>,
+    ) -> Result<(u64, u32), E> {
+        let mut allocation = self.allocation()?;
+
+        let mut page_idx = allocation.last_free_page;
+        let mut latched = self.page(page_idx)?;
+        let mut page = latched.read().unwrap().clone();
+
+        let fits = |page: &Page, len: usize| page.free() as usize >= SLOT_HEADER_SIZE + len;
+        let empty = Page::new(self.page_size);
+        if !fits(&page, len) && (!fits(&page, 0) || fits(&empty, len)) {
+            // a record that fits into an empty page is never split
+            page_idx = allocation.data_page();
+            latched = self.pool.page(page_idx, || Ok(empty.clone()))?;
+            page = empty;
         }
 
-        Ok(bytes_erased)
+        let capacity = len.min(page.free() as usize - SLOT_HEADER_SIZE);
+        let offset = page.occupied();
+        let fragment = offset + SLOT_HEADER_SIZE as u32;
+        // the chain is not reachable before the slot is written, so the data page is latched last
+        let next = self.fill_record(&mut allocation, (&mut page, fragment, capacity), len, fill)?;
+
+        let header = SlotHeader {
+            len: len as u32,
+            capacity: capacity as u32,
+            next,
+        };
+        page.replace_at(&header.to_bytes(), offset)?;
+        self.store(page_idx, &mut latched.write().unwrap(), page)?;
+        self.stats.lock().unwrap().records_written += 1;
+
+        Ok((page_idx, offset))
     }
 
-    pub fn replace_at(&mut self, buf: &[u8], offset: (u64, u16)) -> io::Result<usize> {
-        let mut bytes_written = 0;
-        let mut page_idx = offset.0;
-        let mut page = self.io.load_page(page_idx)?;
-        bytes_written += page.replace_at(&buf[bytes_written..], offset.1)?;
-        self.io.flush_page(page_idx, page)?;
+    /// Length of the record at `pos`, erased records are zero-sized
+    pub fn record_len(&self, pos: (u64, u32)) -> io::Result<u32> {
+        self.with_page(pos.0, |page| Ok(Self::slot(page, pos.1)?.len))
+    }
+
+    /// Runs `f` on the bytes of the record at `pos`. A record that fits in its slot is borrowed
+    /// from the page, which stays latched meanwhile, an overflowed one is read into a buffer first.
+    pub fn with_record<T>(&self, pos: (u64, u32), f: impl FnOnce(&[u8]) -> T) -> io::Result<T> {
+        let latched = self.page(pos.0)?;
+        let head = latched.read().unwrap();
+        let header = Self::slot(head.view(), pos.1)?;
+        if header.len > header.capacity {
+            drop(head);
+            return Ok(f(&self.read_record(pos)?));
+        }
+
+        let bytes = head
+            .view()
+            .bytes_at(pos.1 + SLOT_HEADER_SIZE as u32, header.len as usize);
+        self.stats.lock().unwrap().records_read += 1;
+
+        Ok(f(bytes))
+    }
+
+    pub fn read_record(&self, pos: (u64, u32)) -> io::Result<Box<[u8]>> {
+        // the data page stays latched until the whole chain is read, so that the record is not changed meanwhile
+        let latched = self.page(pos.0)?;
+        let head = latched.read().unwrap();
+
+        let header = Self::slot(head.view(), pos.1)?;
+        let mut buf = vec![0u8; header.len as usize].into_boxed_slice();
+        let mut bytes_read = head.view().read_at(
+            &mut buf[..(header.len.min(header.capacity) as usize)],
+            pos.1 + SLOT_HEADER_SIZE as u32,
+        );
 
-        while bytes_written < buf.len() {
+        if bytes_read < buf.len() {
+            // overflow pages of a chain are usually allocated one after another
+            let pages = (buf.len() - bytes_read).div_ceil(self.overflow_page_capacity()) as u64;
+            self.prefetch(header.next..(header.next + pages))?;
+        }
+
+        let mut next = header.next;
+        while bytes_read < buf.len() {
+            if next == 0 || next == pos.0 {
+                return Err(Self::broken_chain(next));
+            }
+
+            let remaining = &mut buf[bytes_read..];
+            let (read, following) = self.with_page(next, |page| {
+                if page.kind() != PageKind::Overflow {
+                    return Err(Self::broken_chain(next));
+                }
+
+                let mut bytes = [0u8; OVERFLOW_HEADER_SIZE];
+                page.read_at(&mut bytes, PAGE_HEADER_SIZE as u32);
+
+                // a released or torn page holds no data, reading on would never end the chain
+                let occupied = page.occupied() as usize;
+                let data_len = occupied
+                    .checked_sub(PAGE_HEADER_SIZE + OVERFLOW_HEADER_SIZE)
+                    .filter(|&len| len > 0 && occupied <= self.page_size)
+                    .ok_or_else(|| Self::broken_chain(next))?
+                    .min(remaining.len());
+                let read = page.read_at(
+                    &mut remaining[..data_len],
+                    (PAGE_HEADER_SIZE + OVERFLOW_HEADER_SIZE) as u32,
+                );
+                Ok((read, u64::from_le_bytes(bytes)))
+            })?;
+            bytes_read += read;
+            next = following;
+            self.stats.lock().unwrap().overflow_pages_read += 1;
+        }
+        self.stats.lock().unwrap().records_read += 1;
+
+        Ok(buf)
+    }
+
+    /// Overwrites the record at `pos`, the overflow chain grows or shrinks to fit `buf`
+    pub fn replace_record(&self, pos: (u64, u32), buf: &[u8]) -> io::Result<()> {
+        self.replace_record_with(pos, buf.len(), |w| w.write_all(buf))
+    }
+
+    /// Overwrites the record at `pos` with `len` bytes that `fill` writes straight into its pages
+    pub fn replace_record_with<E: From<io::Error>>(
+        &self,
+        pos: (u64, u32),
+        len: usize,
+        fill: impl FnOnce(&mut dyn Write) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut allocation = self.allocation()?;
+
+        // readers of the record wait until its chain is rewritten
+        let latched = self.page(pos.0)?;
+        let mut latched = latched.write().unwrap();
+        let mut page = latched.clone();
+        let mut header = Self::slot(page.view(), pos.1)?;
+
+        // the old chain is released only once the slot points at the new one,
+        // so a crash in between leaves either record whole
+        let chain = self.overflow_pages(pos.0, header.next)?;
+        let capacity = len.min(header.capacity as usize);
+        let fragment = pos.1 + SLOT_HEADER_SIZE as u32;
+        header.next =
+            self.fill_record(&mut allocation, (&mut page, fragment, capacity), len, fill)?;
+        header.len = len as u32;
+
+        page.erase_at(
+            header.capacity as usize - capacity,
+            fragment + capacity as u32,
+        )?;
+        page.replace_at(&header.to_bytes(), pos.1)?;
+        self.store(pos.0, &mut latched, page)?;
+
+        allocation.released_pages.extend(chain);
+        self.stats.lock().unwrap().records_written += 1;
+
+        Ok(())
+    }
+
+    /// Zeroes the record at `pos` and releases its overflow pages, the slot can be reused with `replace_record`
+    pub fn erase_record(&self, pos: (u64, u32)) -> io::Result<()> {
+        let mut allocation = self.allocation()?;
+
+        let latched = self.page(pos.0)?;
+        let mut latched = latched.write().unwrap();
+        let mut page = latched.clone();
+        let mut header = Self::slot(page.view(), pos.1)?;
+        let chain = self.overflow_pages(pos.0, header.next)?;
+
+        header.len = 0;
+        header.next = 0;
+        page.replace_at(&header.to_bytes(), pos.1)?;
+        page.erase_at(header.capacity as usize, pos.1 + SLOT_HEADER_SIZE as u32)?;
+        self.store(pos.0, &mut latched, page)?;
+
+        allocation.released_pages.extend(chain);
+        self.stats.lock().unwrap().records_erased += 1;
+
+        Ok(())
+    }
+
+    /// Position of the slot following `pos`, skipping overflow pages.
+    /// Past the last record, the position of the first slot of a page that does not exist yet is returned.
+    pub fn next_record(&self, pos: (u64, u32)) -> io::Result<(u64, u32)> {
+        let offset = self.with_page(pos.0, |page| {
+            let header = SlotHeader::read(page, pos.1);
+            let offset = pos.1 as usize + SLOT_HEADER_SIZE + header.capacity as usize;
+            Ok((offset + SLOT_HEADER_SIZE <= page.occupied() as usize).then_some(offset))
+        })?;
+        if let Some(offset) = offset {
+            return Ok((pos.0, offset as u32));
+        }
+
+        let next_page = self.allocation.lock().unwrap().next_page;
+        let mut page_idx = pos.0 + 1;
+        while page_idx < next_page {
+            let has_records = self.with_page(page_idx, |page| {
+                Ok(page.kind() == PageKind::Data && !page.empty())
+            })?;
+            if has_records {
+                break;
+            }
             page_idx += 1;
-            let mut page = self.io.load_page(page_idx)?;
-            bytes_written += page.replace_at(&buf[bytes_written..], 2)?;
-            self.io.flush_page(page_idx, page)?;
         }
 
-        self.last_free_page = page_idx;
+        Ok((page_idx, PAGE_HEADER_SIZE as u32))
+    }
+}
+
+/// Spreads the bytes of a record over its slot and the pages of its overflow chain
+struct RecordWriter<'a> {
+    /// Page, offset and room left of every part not filled yet, the last part first
+    parts: Vec<(&'a mut Page, u32, usize)>,
+    written: usize,
+}
+
+impl RecordWriter<'_> {
+    fn finish(&self, len: usize) -> io::Result<()> {
+        match self.written == len {
+            true => Ok(()),
+            false => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} bytes were written of a {len} byte record", self.written),
+            )),
+        }
+    }
+}
+
+impl Write for RecordWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // past the end of the record nothing is written, so `write_all` fails
+        let Some((page, offset, room)) = self.parts.last_mut() else {
+            return Ok(0);
+        };
 
-        Ok(bytes_written)
+        let len = buf.len().min(*room);
+        page.replace_at(&buf[..len], *offset)?;
+        *offset += len as u32;
+        *room -= len;
+        self.written += len;
+        if *room == 0 {
+            self.parts.pop();
+        }
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
 
 impl Write for Pager {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        Ok(self.write_at(buf, (self.last_free_page, 2))?.0)
+        self.write_record(buf)?;
+        Ok(buf.len())
     }
     fn flush(&mut self) -> std::io::Result<()> {
-        Ok(())
+        self.sync()
     }
 }
 
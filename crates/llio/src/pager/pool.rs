@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    io,
+    ops::Range,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use crate::page::Page;
+
+/// A page shared between threads, its lock is the latch readers and writers of the page take
+pub type LatchedPage = Arc<RwLock<Page>>;
+
+/// Pages kept in memory by a [`Pager`](super::Pager), every page behind its own latch
+pub struct BufferPool {
+    capacity: usize,
+    pages: Mutex<HashMap<u64, LatchedPage>>,
+    // a page is loaded by one thread at a time, so that a page loaded before a write never
+    // replaces the written one, misses on different pages are loaded side by side
+    loading: Mutex<HashMap<u64, Arc<Mutex<()>>>>,
+}
+
+impl BufferPool {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            pages: Mutex::default(),
+            loading: Mutex::default(),
+        }
+    }
+
+    /// Page `idx` from the pool, `load` is called when it is not there yet
+    pub fn page(
+        &self,
+        idx: u64,
+        load: impl FnOnce() -> io::Result<Page>,
+    ) -> io::Result<LatchedPage> {
+        if let Some(page) = self.cached(idx) {
+            return Ok(page);
+        }
+
+        let latch = Arc::clone(self.loading.lock().unwrap().entry(idx).or_default());
+        let loaded = {
+            let _loading = latch.lock().unwrap();
+            match self.cached(idx) {
+                Some(page) => Ok(page),
+                None => load().map(|page| self.insert(idx, page)),
+            }
+        };
+
+        // the last thread waiting for the page drops its latch
+        let mut loading = self.loading.lock().unwrap();
+        if Arc::strong_count(&latch) == 2 {
+            loading.remove(&idx);
+        }
+
+        loaded
+    }
+
+    fn insert(&self, idx: u64, page: Page) -> LatchedPage {
+        let page = Arc::new(RwLock::new(page));
+
+        let mut pages = self.pages.lock().unwrap();
+        Self::evict(&mut pages, self.capacity.saturating_sub(1));
+        pages.insert(idx, Arc::clone(&page));
+
+        page
+    }
+
+    fn cached(&self, idx: u64) -> Option<LatchedPage> {
+        let mut pages = self.pages.lock().unwrap();
+        Self::evict(&mut pages, self.capacity);
+        pages.get(&idx).cloned()
+    }
+
+    /// Drops pages nobody holds a latch on until at most `keep` are left,
+    /// every change to them is already on its way to the storage
+    fn evict(pages: &mut HashMap<u64, LatchedPage>, keep: usize) {
+        if pages.len() <= keep {
+            return;
+        }
+
+        let unused = pages
+            .iter()
+            .filter(|(_, page)| Arc::strong_count(page) == 1)
+            .map(|(&idx, _)| idx)
+            .take(pages.len() - keep)
+            .collect::<Vec<_>>();
+        for idx in unused {
+            pages.remove(&idx);
+        }
+    }
+
+    /// Forgets the pages in `range`, they are loaded from the storage again on the next access
+    pub fn discard(&self, range: Range<u64>) {
+        self.pages
+            .lock()
+            .unwrap()
+            .retain(|idx, _| !range.contains(idx));
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+/// Bucket `i` of a [`LatencyHistogram`] counts latencies below `2^i` microseconds, the last one everything above
+pub const LATENCY_BUCKETS: usize = 24;
+
+/// Latencies bucketed by powers of two of microseconds
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    buckets: [u64; LATENCY_BUCKETS],
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = (u64::BITS - micros.leading_zeros()) as usize;
+        self.buckets[bucket.min(LATENCY_BUCKETS - 1)] += 1;
+    }
+
+    pub fn buckets(&self) -> &[u64; LATENCY_BUCKETS] {
+        &self.buckets
+    }
+
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Upper bound of the latency that a `quantile` (0.0 to 1.0) of the samples stay below
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        let target = (self.count() as f64 * quantile.clamp(0.0, 1.0)).ceil() as u64;
+
+        let mut seen = 0;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target.max(1) {
+                return Duration::from_micros(1 << bucket);
+            }
+        }
+
+        Duration::ZERO
+    }
+
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += count;
+        }
+    }
+}
+
+/// Counters of a page storage, all of them start at zero when the file is opened or the stats are reset
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoStats {
+    /// Pages read from the storage, buffered pages are not counted
+    pub pages_read: u64,
+    pub pages_written: u64,
+    /// Bytes transferred from the storage, compressed pages count with their stored size
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// Batches of buffered pages written together
+    pub flush_batches: u64,
+    pub ring_submissions: u64,
+    pub syncs: u64,
+    /// Loads served from the flush or prefetch buffer
+    pub cache_hits: u64,
+    /// Loads that went to the storage
+    pub cache_misses: u64,
+    pub read_latency: LatencyHistogram,
+}
+
+impl IoStats {
+    /// Adds up the counters of several files
+    pub fn merge(&mut self, other: &IoStats) {
+        self.pages_read += other.pages_read;
+        self.pages_written += other.pages_written;
+        self.bytes_read += other.bytes_read;
+        self.bytes_written += other.bytes_written;
+        self.flush_batches += other.flush_batches;
+        self.ring_submissions += other.ring_submissions;
+        self.syncs += other.syncs;
+        self.cache_hits += other.cache_hits;
+        self.cache_misses += other.cache_misses;
+        self.read_latency.merge(&other.read_latency);
+    }
+}
+
+/// Record level counters of a [`Pager`](crate::pager::Pager), along with those of its storage
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PagerStats {
+    pub records_read: u64,
+    /// Records appended or replaced
+    pub records_written: u64,
+    pub records_erased: u64,
+    pub overflow_pages_read: u64,
+    pub overflow_pages_written: u64,
+    pub io: IoStats,
+}
@@ -1,21 +1,48 @@
 pub mod trail;
 
-#[derive(Debug, Clone)]
+/// File id (u32) + page number (u64) + slot offset (u32)
+pub const RECORD_ID_SIZE: usize = 16;
+
+/// Location of a record: the file it lives in, as registered in the database catalog,
+/// and the position of its slot in that file.
+///
+/// Record ids are ordered by file, then by position, so they can be used as index keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RecordId {
-    path: String,
-    offset: u64,
+    file_id: u32,
+    page: u64,
+    slot: u32,
 }
 
 impl RecordId {
-    pub fn new(path: String, offset: u64) -> Self {
-        Self { path, offset }
+    pub fn new(file_id: u32, page: u64, slot: u32) -> Self {
+        Self {
+            file_id,
+            page,
+            slot,
+        }
+    }
+
+    /// A record id of the slot at `pos`, as returned by the [`Pager`](crate::pager::Pager)
+    pub fn from_position(file_id: u32, pos: (u64, u32)) -> Self {
+        Self::new(file_id, pos.0, pos.1)
+    }
+
+    pub fn file_id(&self) -> u32 {
+        self.file_id
+    }
+
+    pub fn page(&self) -> u64 {
+        self.page
     }
 
-    pub fn path(&self) -> &str {
-        &self.path
+    /// Offset of the record slot in its page
+    pub fn slot(&self) -> u32 {
+        self.slot
     }
 
-    pub fn offset(&self) -> u64 {
-        self.offset
+    /// Position of the record slot, as taken by the [`Pager`](crate::pager::Pager)
+    pub fn position(&self) -> (u64, u32) {
+        (self.page, self.slot)
     }
 }
@@ -1,53 +1,35 @@
-use std::{error::Error, mem, ptr};
+use std::error::Error;
 
-use trail::{deserialize::Deserialize, serialize::Serialize};
+use trail::{
+    deserialize::{take, Deserialize},
+    serialize::Serialize,
+};
 
-use super::RecordId;
+use super::{RecordId, RECORD_ID_SIZE};
 
 impl Serialize for RecordId {
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
-        let size = self.size() as usize;
-        let mut buf = vec![0u8; size].into_boxed_slice();
-        unsafe {
-            ptr::copy_nonoverlapping(
-                (size as u32).serialize()?.as_ptr(),
-                (&mut buf[..mem::size_of::<u32>()]).as_mut_ptr(),
-                mem::size_of::<u32>(),
-            );
-        };
-
-        unsafe {
-            ptr::copy_nonoverlapping(
-                self.path.serialize()?.as_ptr(),
-                (&mut buf[mem::size_of::<u32>()..]).as_mut_ptr(),
-                self.path.size() as usize,
-            );
-        };
-
-        unsafe {
-            ptr::copy_nonoverlapping(
-                self.offset().serialize()?.as_ptr(),
-                (&mut buf[(mem::size_of::<u32>() + self.path.len())..]).as_mut_ptr(),
-                self.offset.size() as usize,
-            );
-        }
+        let mut buf = vec![0u8; RECORD_ID_SIZE].into_boxed_slice();
+        buf[0..4].copy_from_slice(&self.file_id.to_le_bytes());
+        buf[4..12].copy_from_slice(&self.page.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.slot.to_le_bytes());
 
         Ok(buf)
     }
 
     fn size(&self) -> u32 {
-        mem::size_of::<u32>() as u32 + self.path.size() + self.offset.size()
+        RECORD_ID_SIZE as u32
     }
 }
 
 impl Deserialize for RecordId {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let record_len = u32::deserialize(&from[..mem::size_of::<u32>()])? as usize;
-        let path = String::deserialize(
-            &from[mem::size_of::<u32>()..(record_len - mem::size_of::<u64>())],
-        )?;
-        let offset = u64::deserialize(&from[(mem::size_of::<u32>() + path.len())..record_len])?;
-
-        Ok(Self { path, offset })
+    fn deserialize(from: &[u8]) -> trail::error::Result<Self> {
+        let bytes = take(from, 0, RECORD_ID_SIZE)?;
+
+        Ok(Self {
+            file_id: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            page: u64::from_le_bytes(bytes[4..12].try_into().unwrap()),
+            slot: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+        })
     }
 }
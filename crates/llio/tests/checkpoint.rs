@@ -0,0 +1,184 @@
+use std::{
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use llio::{
+    header::FileKind,
+    io::{direct::DirectFileIo, memory::MemoryFileIo, PageIo},
+    page::DEFAULT_PAGE_SIZE,
+    pager::{Pager, PagerOptions},
+};
+
+mod common;
+
+use common::test_file;
+
+fn open_pager(path: &Path, options: PagerOptions) -> Pager {
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    Pager::with_options(io, options)
+}
+
+#[test]
+pub fn changed_pages_are_written_back_in_the_background() {
+    let path = test_file("checkpoint_interval.db");
+    let options = PagerOptions::builder()
+        .flush_interval(Duration::from_millis(20))
+        .dirty_ratio(1.0)
+        .build();
+    let pager = open_pager(&path, options);
+
+    pager.write_record(&[1u8; 100]).unwrap();
+    let started = Instant::now();
+    while pager.stats().io.pages_written == 0 {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+pub fn dirty_pages_are_written_back_once_enough_piled_up() {
+    let path = test_file("checkpoint_ratio.db");
+    let options = PagerOptions::builder()
+        .pool_size(8)
+        .dirty_ratio(0.5)
+        .flush_interval(Duration::from_secs(3600))
+        .build();
+    let pager = open_pager(&path, options);
+
+    // a record spanning six pages changes more than half of the pool
+    pager.write_record(&[2u8; 5 * DEFAULT_PAGE_SIZE]).unwrap();
+    assert!(pager.stats().io.pages_written >= 4);
+
+    let path = test_file("checkpoint_manual.db");
+    let pager = open_pager(
+        &path,
+        PagerOptions::builder().background_flush(false).build(),
+    );
+    pager.write_record(&[2u8; 100]).unwrap();
+    assert_eq!(pager.stats().io.pages_written, 0);
+    pager.sync().unwrap();
+    assert_eq!(pager.stats().io.pages_written, 1);
+
+    // an overflow chain is made durable before the slot that points at it
+    pager.write_record(&[2u8; 5 * DEFAULT_PAGE_SIZE]).unwrap();
+    assert_eq!(pager.stats().io.pages_written, 6);
+    pager.sync().unwrap();
+    assert_eq!(pager.stats().io.pages_written, 7);
+}
+
+#[test]
+pub fn a_page_changed_repeatedly_counts_as_dirty_once() {
+    let path = test_file("checkpoint_distinct.db");
+    let options = PagerOptions::builder()
+        .pool_size(8)
+        .dirty_ratio(0.5)
+        .flush_interval(Duration::from_secs(3600))
+        .build();
+    let pager = open_pager(&path, options);
+
+    // all of the records land on the same data page
+    for byte in 0..10 {
+        pager.write_record(&[byte; 100]).unwrap();
+    }
+    assert_eq!(pager.stats().io.pages_written, 0);
+}
+
+#[test]
+pub fn checkpoints_are_recorded_in_the_header() {
+    let path = test_file("checkpoint_header.db");
+    let pager = open_pager(&path, PagerOptions::default());
+    assert_eq!(pager.last_checkpoint(), None);
+
+    pager.write_record(&[3u8; 100]).unwrap();
+    assert_eq!(pager.checkpoint().unwrap().sequence(), 1);
+    pager.write_record(&[4u8; 2 * DEFAULT_PAGE_SIZE]).unwrap();
+    let checkpoint = pager.checkpoint().unwrap();
+    assert_eq!(checkpoint.sequence(), 2);
+    drop(pager);
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(io.header().checkpoint(), Some(checkpoint));
+    assert_eq!(checkpoint.total_pages(), io.total_pages());
+
+    let pager = Pager::new(io);
+    assert_eq!(pager.last_checkpoint(), Some(checkpoint));
+    assert_eq!(pager.checkpoint().unwrap().sequence(), 3);
+}
+
+#[test]
+pub fn recovery_starts_from_the_last_checkpoint() {
+    let disk = MemoryFileIo::new();
+    let pager = Pager::new(disk.clone());
+
+    for byte in 0..3 {
+        pager.write_record(&[byte; 3000]).unwrap();
+    }
+    let checkpoint = pager.checkpoint().unwrap();
+    assert_eq!(checkpoint.last_data_page(), 2);
+
+    // records appended after the checkpoint are found by scanning the pages past it
+    pager.write_record(&[3u8; 3000]).unwrap();
+    let last = pager.write_record(&[4u8; 3000]).unwrap();
+    pager.sync().unwrap();
+    drop(pager);
+
+    let pager = Pager::new(disk.clone());
+    assert_eq!(pager.write_record(&[5u8; 10]).unwrap().0, last.0);
+    pager.checkpoint().unwrap();
+    drop(pager);
+
+    // without new pages, the marker alone tells where records go
+    let pager = Pager::new(disk);
+    assert_eq!(pager.write_record(&[6u8; 10]).unwrap().0, last.0);
+}
+
+#[test]
+pub fn free_pages_are_listed_with_the_checkpoint() {
+    let path = test_file("checkpoint_free_list.db");
+    let pager = open_pager(&path, PagerOptions::default());
+    let chained = pager.write_record(&[1u8; 4 * DEFAULT_PAGE_SIZE]).unwrap();
+    for _ in 0..200 {
+        pager.write_record(&[2u8; 2000]).unwrap();
+    }
+    pager.erase_record(chained).unwrap();
+    let total_pages = pager.checkpoint().unwrap().total_pages();
+    drop(pager);
+
+    // only the listed pages are read on open, not every page of the file
+    let pager = open_pager(&path, PagerOptions::default());
+    assert!(pager.stats().io.pages_read < 10);
+
+    // the chain of the erased record is handed out again
+    pager.write_record(&[3u8; 3 * DEFAULT_PAGE_SIZE]).unwrap();
+    assert!(pager.checkpoint().unwrap().total_pages() <= total_pages + 1);
+}
+
+#[test]
+pub fn listed_pages_used_after_the_checkpoint_are_not_handed_out_again() {
+    let disk = MemoryFileIo::new();
+    let pager = Pager::new(disk.clone());
+    let chained = pager.write_record(&[1u8; 4 * DEFAULT_PAGE_SIZE]).unwrap();
+    pager.erase_record(chained).unwrap();
+    pager.checkpoint().unwrap();
+    drop(pager);
+
+    // the listed pages go to a record written after the checkpoint, no other checkpoint is taken
+    let pager = Pager::new(disk.clone());
+    let kept = vec![2u8; 4 * DEFAULT_PAGE_SIZE];
+    let pos = pager.write_record(&kept).unwrap();
+    pager.sync().unwrap();
+    drop(pager);
+
+    let pager = Pager::new(disk.clone());
+    pager.write_record(&[3u8; 4 * DEFAULT_PAGE_SIZE]).unwrap();
+    assert_eq!(pager.read_record(pos).unwrap(), kept.into());
+
+    // pages freed without a checkpoint after them are missing from the list, reclaiming finds them
+    pager.erase_record(pos).unwrap();
+    pager.sync().unwrap();
+    drop(pager);
+    let pager = Pager::new(disk);
+    assert_eq!(pager.reclaim_space().unwrap(), 4);
+}
@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use llio::{
+    error::Result,
+    header::FileKind,
+    io::{direct::DirectFileIo, options::FileOptions},
+    page::{Page, DEFAULT_PAGE_SIZE},
+};
+
+/// A path for a test file in the target directory, the file of an earlier run is removed
+pub fn test_file(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+pub fn open(path: &Path, options: FileOptions) -> Result<DirectFileIo> {
+    DirectFileIo::with_options(path.to_str().unwrap(), FileKind::Collection, options)
+}
+
+/// A page of the default size that starts with `bytes`
+pub fn page_with(bytes: &[u8]) -> Page {
+    let mut page = Page::new(DEFAULT_PAGE_SIZE);
+    page.write_all(bytes).unwrap();
+    page
+}
+
+/// `len` bytes that differ for every `seed`
+pub fn record(len: usize, seed: u8) -> Vec<u8> {
+    (0..len)
+        .map(|i| (i as u8).wrapping_mul(31) ^ seed)
+        .collect()
+}
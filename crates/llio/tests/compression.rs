@@ -0,0 +1,110 @@
+use std::{fs, io::Write};
+
+use llio::{
+    compression::{compress, decompress},
+    header::{FileKind, FEATURE_COMPRESSION},
+    io::{direct::DirectFileIo, options::FileOptions, PageIo},
+    page::{Page, DEFAULT_PAGE_SIZE},
+};
+
+mod common;
+
+use common::{open, test_file};
+
+/// A page that looks like a batch of serialized documents
+fn document_page(idx: u64) -> Page {
+    let mut page = Page::new(DEFAULT_PAGE_SIZE);
+    for id in 0..40 {
+        let document =
+            format!("\u{3}name\0\u{5}user-{idx}-{id}\0\u{4}email\0user{id}@example.com\0");
+        page.write_all(document.as_bytes()).unwrap();
+    }
+    page
+}
+
+#[test]
+pub fn codec_round_trips() {
+    let mut state = 7u64;
+    let random = (0..5000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect::<Vec<_>>();
+    let repetitive = b"abcabcabcd".repeat(500);
+    let mixed = [&random[..300], &repetitive[..], &random[300..900]].concat();
+
+    assert!(compress(&random).is_none());
+    assert!(compress(&[]).is_none());
+
+    for input in [&repetitive, &mixed, &vec![0u8; 65536]] {
+        let compressed = compress(input).unwrap();
+        assert!(compressed.len() < input.len());
+        assert_eq!(&decompress(&compressed, input.len()).unwrap(), input);
+        // the decoder never produces more or less than asked for
+        assert!(decompress(&compressed, input.len() - 1).is_none());
+    }
+
+    // literals past the end of the input and matches before the start of the output
+    assert!(decompress(&[0x50, b'a', b'b'], 5).is_none());
+    assert!(decompress(&[0x10, b'a', 2, 0], 5).is_none());
+}
+
+#[test]
+pub fn compressed_pages_take_less_space() {
+    let path = test_file("compression_pages.db");
+    let options = FileOptions::builder().compression(true).build();
+
+    let mut io = open(&path, options).unwrap();
+    assert!(io.header().has_feature(FEATURE_COMPRESSION));
+    for idx in 0..64 {
+        io.flush_page(idx, document_page(idx)).unwrap();
+    }
+    io.sync().unwrap();
+    drop(io);
+
+    let size = fs::metadata(&path).unwrap().len();
+    assert!(size < 16 * DEFAULT_PAGE_SIZE as u64, "{size} bytes");
+
+    // the options of an existing file are ignored
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(io.total_pages(), 64);
+    for idx in 0..64 {
+        assert_eq!(
+            io.load_page(idx).unwrap().buffer(),
+            document_page(idx).buffer()
+        );
+    }
+    assert!(io.load_page(100).unwrap().empty());
+}
+
+#[test]
+pub fn rewritten_pages_reuse_space() {
+    let path = test_file("compression_rewrites.db");
+    let options = FileOptions::builder().compression(true).build();
+
+    let mut io = open(&path, options).unwrap();
+    for round in 0..50 {
+        for idx in 0..8 {
+            io.flush_page(idx, document_page(idx + round)).unwrap();
+        }
+        io.sync().unwrap();
+    }
+    // dropping a compressed file commits the page map as well
+    io.flush_page(3, document_page(1000)).unwrap();
+    drop(io);
+
+    assert!(fs::metadata(&path).unwrap().len() < 16 * DEFAULT_PAGE_SIZE as u64);
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(
+        io.load_page(3).unwrap().buffer(),
+        document_page(1000).buffer()
+    );
+    assert_eq!(
+        io.load_page(7).unwrap().buffer(),
+        document_page(56).buffer()
+    );
+}
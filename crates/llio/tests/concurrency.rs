@@ -0,0 +1,111 @@
+use std::{sync::Arc, thread};
+
+use llio::{io::memory::MemoryFileIo, page::DEFAULT_PAGE_SIZE, pager::Pager};
+
+mod common;
+
+use common::record;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+pub fn pagers_are_shared_between_threads() {
+    assert_send_sync::<Pager>();
+
+    // a small pool, so that readers also load pages from the storage thread
+    let pager = Arc::new(Pager::with_pool_size(MemoryFileIo::new(), 4));
+    let positions = (0..200u8)
+        .map(|seed| {
+            let len = if seed % 10 == 0 {
+                3 * DEFAULT_PAGE_SIZE
+            } else {
+                300
+            };
+            (pager.write_record(&record(len, seed)).unwrap(), len, seed)
+        })
+        .collect::<Vec<_>>();
+    let positions = Arc::new(positions);
+
+    let readers = (0..4)
+        .map(|_| {
+            let pager = Arc::clone(&pager);
+            let positions = Arc::clone(&positions);
+            thread::spawn(move || {
+                for &(pos, len, seed) in positions.iter() {
+                    assert_eq!(&pager.read_record(pos).unwrap()[..], &record(len, seed)[..]);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
+
+#[test]
+pub fn readers_never_see_half_replaced_records() {
+    let pager = Arc::new(Pager::with_pool_size(MemoryFileIo::new(), 8));
+    let short = record(DEFAULT_PAGE_SIZE, 1);
+    let long = record(4 * DEFAULT_PAGE_SIZE, 2);
+    let pos = pager.write_record(&short).unwrap();
+
+    let writer = {
+        let pager = Arc::clone(&pager);
+        let (short, long) = (short.clone(), long.clone());
+        thread::spawn(move || {
+            for i in 0..200 {
+                let buf = if i % 2 == 0 { &long } else { &short };
+                pager.replace_record(pos, buf).unwrap();
+            }
+        })
+    };
+
+    let readers = (0..3)
+        .map(|_| {
+            let pager = Arc::clone(&pager);
+            let (short, long) = (short.clone(), long.clone());
+            thread::spawn(move || {
+                for _ in 0..200 {
+                    let buf = pager.read_record(pos).unwrap();
+                    assert!(buf[..] == short[..] || buf[..] == long[..]);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    writer.join().unwrap();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
+
+#[test]
+pub fn writers_take_turns() {
+    let disk = MemoryFileIo::new();
+    let pager = Arc::new(Pager::new(disk.clone()));
+
+    let writers = (0..4u8)
+        .map(|writer| {
+            let pager = Arc::clone(&pager);
+            thread::spawn(move || {
+                (0..50u8)
+                    .map(|i| {
+                        let seed = writer * 50 + i;
+                        (pager.write_record(&record(700, seed)).unwrap(), seed)
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    let positions = writers
+        .into_iter()
+        .flat_map(|writer| writer.join().unwrap())
+        .collect::<Vec<_>>();
+    pager.sync().unwrap();
+    drop(pager);
+
+    let pager = Pager::new(disk);
+    for (pos, seed) in positions {
+        assert_eq!(&pager.read_record(pos).unwrap()[..], &record(700, seed)[..]);
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use llio::{
+    error::Error,
+    header::{FileKind, FORMAT_VERSION},
+    io::{direct::DirectFileIo, options::FileOptions, PageIo},
+    page::{Page, DEFAULT_PAGE_SIZE},
+};
+
+mod common;
+
+use common::{open, page_with, test_file};
+
+#[test]
+pub fn pages_are_written_to_disk() {
+    let path = test_file("direct_pages_are_written.db");
+
+    let mut io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    io.flush_page(1, page_with(b"persisted")).unwrap();
+    io.sync().unwrap();
+    drop(io);
+
+    // the header page comes first
+    assert_eq!(
+        fs::metadata(&path).unwrap().len(),
+        3 * DEFAULT_PAGE_SIZE as u64
+    );
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(io.total_pages(), 2);
+    assert!(io.load_page(0).unwrap().empty());
+    assert_eq!(&io.load_page(1).unwrap().buffer()[8..17], b"persisted");
+    // pages past the end of the file were never written
+    assert!(io.load_page(5).unwrap().empty());
+}
+
+#[test]
+pub fn open_failure_is_reported() {
+    let path = test_file("missing_directory/file.db");
+
+    let result = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection);
+    assert!(matches!(result, Err(Error::Open { .. })));
+}
+
+#[test]
+pub fn short_read_is_reported() {
+    let path = test_file("direct_short_read.db");
+    drop(DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap());
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&vec![1u8; DEFAULT_PAGE_SIZE + 512]).unwrap();
+    drop(file);
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert!(io.load_page(0).is_ok());
+    assert!(matches!(
+        io.load_page(1),
+        Err(Error::ShortRead { page: 1, read: 512 })
+    ));
+}
+
+#[test]
+pub fn header_is_validated_on_open() {
+    let path = test_file("direct_header.db");
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::BTree).unwrap();
+    assert_eq!(io.header().kind(), FileKind::BTree);
+    assert_eq!(io.header().format_version(), FORMAT_VERSION);
+    assert_eq!(io.header().page_size(), DEFAULT_PAGE_SIZE as u32);
+    drop(io);
+
+    let reopened = DirectFileIo::new(path.to_str().unwrap(), FileKind::BTree).unwrap();
+    assert!(reopened.header().created_at() > 0);
+    assert_eq!(reopened.total_pages(), 0);
+    drop(reopened);
+
+    assert!(matches!(
+        DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection),
+        Err(Error::FileKindMismatch {
+            expected: FileKind::Collection,
+            found: 2
+        })
+    ));
+
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[8..10].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    fs::write(&path, &bytes).unwrap();
+    assert!(matches!(
+        DirectFileIo::new(path.to_str().unwrap(), FileKind::BTree),
+        Err(Error::UnsupportedVersion(version)) if version == FORMAT_VERSION + 1
+    ));
+
+    bytes[0] = b'X';
+    fs::write(&path, &bytes).unwrap();
+    assert!(matches!(
+        DirectFileIo::new(path.to_str().unwrap(), FileKind::BTree),
+        Err(Error::InvalidMagic)
+    ));
+}
+
+#[test]
+pub fn page_size_is_recorded_in_the_header() {
+    let path = test_file("direct_page_size.db");
+    let options = FileOptions::builder().page_size(16384).build();
+
+    let mut io = open(&path, options).unwrap();
+    let mut page = Page::new(io.page_size());
+    page.write_all(&[7u8; 10_000]).unwrap();
+    io.flush_page(0, page).unwrap();
+    io.sync().unwrap();
+    drop(io);
+
+    assert_eq!(fs::metadata(&path).unwrap().len(), 2 * 16384);
+
+    // the page size of an existing file wins over the default one
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(io.page_size(), 16384);
+    assert_eq!(io.total_pages(), 1);
+    assert_eq!(
+        &io.load_page(0).unwrap().buffer()[8..10_008],
+        &[7u8; 10_000]
+    );
+
+    for page_size in [0, 2048, 12288, 131072] {
+        let options = FileOptions::builder().page_size(page_size).build();
+        assert!(matches!(
+            open(&path, options),
+            Err(Error::InvalidPageSize(size)) if size == page_size
+        ));
+    }
+}
+
+fn write_numbered_pages(path: &Path, count: u64) {
+    let mut io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    for idx in 0..count {
+        io.flush_page(idx, page_with(&idx.to_le_bytes())).unwrap();
+    }
+    io.sync().unwrap();
+}
+
+fn page_number(page: &Page) -> u64 {
+    u64::from_le_bytes(page.buffer()[8..16].try_into().unwrap())
+}
+
+#[test]
+pub fn pages_are_loaded_in_batches() {
+    let path = test_file("direct_batched_reads.db");
+    write_numbered_pages(&path, 80);
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pages = io.load_pages(0..84).unwrap();
+    assert_eq!(pages.len(), 84);
+
+    for (idx, page) in pages.iter().enumerate().take(80) {
+        assert_eq!(page_number(page), idx as u64);
+    }
+    assert!(pages[80..].iter().all(|page| page.empty()));
+}
+
+#[test]
+pub fn prefetched_pages_are_invalidated_by_writes() {
+    let path = test_file("direct_prefetch.db");
+    write_numbered_pages(&path, 8);
+
+    let mut io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    io.prefetch(0..8).unwrap();
+    assert_eq!(page_number(&io.load_page(3).unwrap()), 3);
+
+    io.flush_page(3, page_with(&42u64.to_le_bytes())).unwrap();
+
+    assert_eq!(page_number(&io.load_page(3).unwrap()), 42);
+    assert_eq!(page_number(&io.load_page(4).unwrap()), 4);
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+use llio::{
+    encryption::{Cipher, EncryptionKey},
+    error::Error,
+    header::{FileKind, FEATURE_COMPRESSION, FEATURE_ENCRYPTION},
+    io::{direct::DirectFileIo, options::FileOptions, PageIo},
+    page::{Page, DEFAULT_PAGE_SIZE},
+};
+
+mod common;
+
+use common::{open, page_with, test_file};
+
+const SECRET: &[u8] = b"customer-card-4111111111111111";
+
+fn key(byte: u8) -> EncryptionKey {
+    EncryptionKey::new([byte; 32])
+}
+
+fn secret_page() -> Page {
+    page_with(&SECRET.repeat(20))
+}
+
+#[test]
+pub fn pages_are_bound_to_their_index() {
+    let cipher = Cipher::new(&key(1));
+
+    let encrypted = cipher.encrypt(3, b"page contents");
+    assert_eq!(cipher.decrypt(3, &encrypted).unwrap(), b"page contents");
+    // the same page encrypts differently every time
+    assert_ne!(cipher.encrypt(3, b"page contents"), encrypted);
+
+    assert!(cipher.decrypt(4, &encrypted).is_none());
+    assert!(Cipher::new(&key(2)).decrypt(3, &encrypted).is_none());
+
+    let mut tampered = encrypted.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(cipher.decrypt(3, &tampered).is_none());
+}
+
+#[test]
+pub fn encrypted_files_need_the_right_key() {
+    let path = test_file("encryption_keys.db");
+    let options = FileOptions::builder().encryption_key(key(1)).build();
+
+    let mut io = open(&path, options.clone()).unwrap();
+    assert!(io.header().has_feature(FEATURE_ENCRYPTION));
+    io.flush_page(0, secret_page()).unwrap();
+    io.sync().unwrap();
+    drop(io);
+
+    let raw = fs::read(&path).unwrap();
+    assert!(!raw.windows(SECRET.len()).any(|window| window == SECRET));
+
+    assert!(matches!(
+        DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection),
+        Err(Error::KeyRequired)
+    ));
+    assert!(matches!(
+        open(&path, FileOptions::builder().encryption_key(key(2)).build()),
+        Err(Error::WrongKey)
+    ));
+
+    let io = open(&path, options).unwrap();
+    assert_eq!(io.load_page(0).unwrap().buffer(), secret_page().buffer());
+}
+
+#[test]
+pub fn tampered_pages_are_rejected() {
+    let path = test_file("encryption_tampering.db");
+    let options = FileOptions::builder()
+        .encryption_key(key(1))
+        .compression(true)
+        .build();
+
+    let mut io = open(&path, options.clone()).unwrap();
+    assert!(io.header().has_feature(FEATURE_COMPRESSION));
+    io.flush_page(0, secret_page()).unwrap();
+    drop(io);
+
+    // the first extent starts right after the header page
+    let mut raw = fs::read(&path).unwrap();
+    raw[DEFAULT_PAGE_SIZE + 40] ^= 1;
+    fs::write(&path, &raw).unwrap();
+
+    let io = open(&path, options).unwrap();
+    assert!(matches!(io.load_page(0), Err(Error::Decrypt { page: 0 })));
+}
@@ -0,0 +1,94 @@
+use llio::io::{
+    faulty::{FaultConfig, FaultyFileIo},
+    memory::MemoryFileIo,
+    PageIo,
+};
+
+mod common;
+
+use common::page_with;
+
+#[test]
+pub fn unsynced_writes_are_dropped_on_crash() {
+    let disk = MemoryFileIo::new();
+    let config = FaultConfig::builder()
+        .crash_after_writes(2)
+        .drop_unsynced(true)
+        .build();
+    let mut io = FaultyFileIo::new(disk.clone(), config);
+
+    io.flush_page(0, page_with(b"synced")).unwrap();
+    io.sync().unwrap();
+    io.flush_page(1, page_with(b"unsynced")).unwrap();
+
+    assert!(io.flush_page(2, page_with(b"crash")).is_err());
+    assert!(io.is_crashed());
+    assert!(io.load_page(0).is_err());
+    drop(io);
+
+    assert_eq!(&disk.load_page(0).unwrap().buffer()[8..14], b"synced");
+    assert!(disk.load_page(1).unwrap().empty());
+    assert!(disk.load_page(2).unwrap().empty());
+}
+
+#[test]
+pub fn unsynced_writes_can_survive_a_crash() {
+    let disk = MemoryFileIo::new();
+    let config = FaultConfig::builder()
+        .crash_after_writes(1)
+        .drop_unsynced(false)
+        .build();
+    let mut io = FaultyFileIo::new(disk.clone(), config);
+
+    io.flush_page(0, page_with(b"unsynced")).unwrap();
+    assert!(io.flush_page(1, page_with(b"crash")).is_err());
+    drop(io);
+
+    assert_eq!(&disk.load_page(0).unwrap().buffer()[8..16], b"unsynced");
+    assert!(disk.load_page(1).unwrap().empty());
+}
+
+#[test]
+pub fn torn_write_persists_page_prefix() {
+    let disk = MemoryFileIo::new();
+    let config = FaultConfig::builder()
+        .crash_after_writes(1)
+        .torn_write(10)
+        .drop_unsynced(true)
+        .build();
+    let mut io = FaultyFileIo::new(disk.clone(), config);
+
+    io.flush_page(0, page_with(b"old page")).unwrap();
+    io.sync().unwrap();
+    assert!(io.flush_page(0, page_with(b"new page")).is_err());
+    drop(io);
+
+    // page header and the first two bytes are new, the rest is old
+    let page = disk.load_page(0).unwrap();
+    assert_eq!(&page.buffer()[8..16], b"ned page");
+}
+
+#[test]
+pub fn read_errors_are_injected() {
+    let config = FaultConfig::builder().read_error_every(3).build();
+    let io = FaultyFileIo::new(MemoryFileIo::new(), config);
+
+    assert!(io.load_page(0).is_ok());
+    assert!(io.load_page(0).is_ok());
+    assert!(io.load_page(0).is_err());
+    assert!(io.load_page(0).is_ok());
+}
+
+#[test]
+pub fn clean_shutdown_syncs_writes() {
+    let disk = MemoryFileIo::new();
+    let mut io = FaultyFileIo::new(disk.clone(), FaultConfig::default());
+
+    io.flush_page(3, page_with(b"page")).unwrap();
+    assert_eq!(io.total_pages(), 4);
+    assert_eq!(disk.total_pages(), 0);
+    drop(io);
+
+    assert_eq!(disk.total_pages(), 4);
+    assert_eq!(&disk.load_page(3).unwrap().buffer()[8..12], b"page");
+}
@@ -0,0 +1,75 @@
+use llio::{
+    error::Error,
+    io::{
+        lock::{FileLock, LockMode},
+        options::FileOptions,
+        PageIo,
+    },
+    page::{Page, DEFAULT_PAGE_SIZE},
+};
+
+mod common;
+
+use common::{open, page_with, test_file};
+
+fn locked(lock_mode: LockMode) -> FileOptions {
+    FileOptions::builder().lock_mode(lock_mode).build()
+}
+
+#[test]
+pub fn writers_exclude_each_other() {
+    let path = test_file("lock_writers.db");
+
+    let io = open(&path, locked(LockMode::Exclusive)).unwrap();
+    assert!(matches!(
+        open(&path, locked(LockMode::Exclusive)),
+        Err(Error::Locked { .. })
+    ));
+    assert!(matches!(
+        open(&path, locked(LockMode::Shared)),
+        Err(Error::Locked { .. })
+    ));
+    drop(io);
+
+    // the lock is gone with the file
+    assert!(open(&path, locked(LockMode::Exclusive)).is_ok());
+}
+
+#[test]
+pub fn readers_share_the_file() {
+    let path = test_file("lock_readers.db");
+    let mut io = open(&path, locked(LockMode::Exclusive)).unwrap();
+    io.flush_page(0, page_with(b"shared")).unwrap();
+    io.sync().unwrap();
+    drop(io);
+
+    let mut first = open(&path, locked(LockMode::Shared)).unwrap();
+    let second = open(&path, locked(LockMode::Shared)).unwrap();
+    assert_eq!(&second.load_page(0).unwrap().buffer()[8..14], b"shared");
+    assert!(matches!(
+        open(&path, locked(LockMode::Exclusive)),
+        Err(Error::Locked { .. })
+    ));
+
+    assert!(matches!(
+        first.flush_page(0, Page::new(DEFAULT_PAGE_SIZE)),
+        Err(Error::ReadOnly)
+    ));
+    assert!(first.sync().is_ok());
+}
+
+#[test]
+pub fn lock_files_are_released_on_drop() {
+    let path = test_file("lock_file");
+
+    let lock = FileLock::acquire(path.to_str().unwrap(), LockMode::Exclusive).unwrap();
+    let err = FileLock::acquire(path.to_str().unwrap(), LockMode::Shared).unwrap_err();
+    assert!(err.to_string().starts_with("database is in use"));
+    drop(lock);
+
+    let first = FileLock::acquire(path.to_str().unwrap(), LockMode::Shared).unwrap();
+    let second = FileLock::acquire(path.to_str().unwrap(), LockMode::Shared).unwrap();
+    assert_eq!(second.mode(), LockMode::Shared);
+    drop((first, second));
+    assert!(FileLock::acquire(path.to_str().unwrap(), LockMode::Exclusive).is_ok());
+}
@@ -0,0 +1,126 @@
+use std::{fs, path::Path};
+
+use llio::{
+    error::Error,
+    header::FileKind,
+    io::{
+        direct::DirectFileIo,
+        lock::LockMode,
+        mmap::{AccessPattern, MmapFileIo},
+        options::FileOptions,
+        PageIo,
+    },
+    page::{Page, MAX_PAGE_SIZE, MIN_PAGE_SIZE},
+    pager::Pager,
+};
+
+mod common;
+
+use common::{open, test_file};
+
+fn nth_record(idx: usize) -> Vec<u8> {
+    // every fourth record needs an overflow chain
+    let len = if idx.is_multiple_of(4) { 9000 } else { 100 };
+    (0..len).map(|byte| (byte + idx) as u8).collect()
+}
+
+fn write_records(path: &Path, count: usize) -> Vec<(u64, u32)> {
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pager = Pager::new(io);
+    let positions = (0..count)
+        .map(|idx| pager.write_record(&nth_record(idx)).unwrap())
+        .collect();
+    pager.sync().unwrap();
+    positions
+}
+
+#[test]
+pub fn records_are_read_from_the_mapping() {
+    let path = test_file("mmap_records.db");
+    let positions = write_records(&path, 40);
+
+    let io = MmapFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    io.advise(AccessPattern::Sequential).unwrap();
+    let pager = Pager::new(io);
+
+    for (idx, pos) in positions.iter().enumerate() {
+        assert_eq!(&pager.read_record(*pos).unwrap()[..], &nth_record(idx)[..]);
+    }
+
+    // records are visited in insertion order, just like with direct I/O
+    let mut pos = positions[0];
+    for expected in &positions[1..] {
+        pos = pager.next_record(pos).unwrap();
+        assert_eq!(pos, *expected);
+    }
+}
+
+#[test]
+pub fn pages_are_borrowed_from_the_mapping() {
+    let path = test_file("mmap_views.db");
+    write_records(&path, 8);
+
+    let direct = DirectFileIo::with_options(
+        path.to_str().unwrap(),
+        FileKind::Collection,
+        FileOptions::builder().lock_mode(LockMode::Shared).build(),
+    )
+    .unwrap();
+    let mut io = MmapFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(io.total_pages(), direct.total_pages());
+    io.prefetch(0..io.total_pages()).unwrap();
+
+    for idx in 0..io.total_pages() {
+        let view = io.view_page(idx).unwrap();
+        assert_eq!(view.buffer(), direct.load_page(idx).unwrap().buffer());
+        assert_eq!(view.buffer(), io.load_page(idx).unwrap().buffer());
+    }
+    assert!(io.view_page(io.total_pages()).is_none());
+    assert!(io.load_page(io.total_pages()).unwrap().empty());
+
+    assert!(matches!(
+        io.flush_page(0, Page::new(io.page_size())),
+        Err(Error::ReadOnly)
+    ));
+}
+
+#[test]
+pub fn mapped_files_are_not_written_to() {
+    let path = test_file("mmap_lock.db");
+    write_records(&path, 1);
+
+    let io = MmapFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert!(matches!(
+        DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection),
+        Err(Error::Locked { .. })
+    ));
+    drop(io);
+
+    let compressed = test_file("mmap_compressed.db");
+    let options = FileOptions::builder().compression(true).build();
+    drop(
+        DirectFileIo::with_options(compressed.to_str().unwrap(), FileKind::Collection, options)
+            .unwrap(),
+    );
+    assert!(matches!(
+        MmapFileIo::new(compressed.to_str().unwrap(), FileKind::Collection),
+        Err(Error::UnsupportedFeatures(_))
+    ));
+}
+
+#[test]
+pub fn truncated_headers_are_rejected() {
+    let path = test_file("mmap_truncated.db");
+    let options = FileOptions::builder().page_size(MAX_PAGE_SIZE).build();
+    drop(open(&path, options).unwrap());
+
+    // the header is intact, but the file ends before its page does
+    let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(MIN_PAGE_SIZE as u64).unwrap();
+    drop(file);
+
+    assert!(matches!(
+        MmapFileIo::new(path.to_str().unwrap(), FileKind::Collection),
+        Err(Error::TruncatedFile { len: MIN_PAGE_SIZE })
+    ));
+}
@@ -0,0 +1,275 @@
+use std::io;
+
+use llio::{
+    io::{
+        faulty::{FaultConfig, FaultyFileIo},
+        memory::MemoryFileIo,
+        PageIo,
+    },
+    page::{Page, PageKind, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, PAGE_HEADER_SIZE},
+    pager::{Pager, SLOT_HEADER_SIZE},
+    util::record_id::RecordId,
+};
+
+mod common;
+
+use common::record;
+
+#[test]
+pub fn large_records_are_stored_in_overflow_chains() {
+    let disk = MemoryFileIo::new();
+    let pager = Pager::new(disk.clone());
+
+    let small = record(100, 1);
+    let large = record(3 * DEFAULT_PAGE_SIZE + 17, 2);
+
+    let first = pager.write_record(&small).unwrap();
+    let second = pager.write_record(&large).unwrap();
+    let third = pager.write_record(&small).unwrap();
+    pager.sync().unwrap();
+
+    assert_eq!(first, (0, PAGE_HEADER_SIZE as u32));
+    // the head fragment shares the page with the first record
+    assert_eq!(second.0, 0);
+    assert_eq!(disk.load_page(1).unwrap().kind(), PageKind::Overflow);
+
+    let pager = Pager::new(disk.clone());
+    assert_eq!(&pager.read_record(first).unwrap()[..], &small[..]);
+    assert_eq!(&pager.read_record(second).unwrap()[..], &large[..]);
+    assert_eq!(&pager.read_record(third).unwrap()[..], &small[..]);
+
+    // overflow pages are skipped when walking the records
+    assert_eq!(pager.next_record(first).unwrap(), second);
+    assert_eq!(pager.next_record(second).unwrap(), third);
+}
+
+#[test]
+pub fn neighbours_survive_growing_records() {
+    let pager = Pager::new(MemoryFileIo::new());
+
+    let first = pager.write_record(&record(64, 1)).unwrap();
+    let second = pager.write_record(&record(64, 2)).unwrap();
+
+    let grown = record(2 * DEFAULT_PAGE_SIZE, 3);
+    pager.replace_record(first, &grown).unwrap();
+    assert_eq!(&pager.read_record(first).unwrap()[..], &grown[..]);
+    assert_eq!(&pager.read_record(second).unwrap()[..], &record(64, 2)[..]);
+
+    let shrunk = record(10, 4);
+    pager.replace_record(first, &shrunk).unwrap();
+    assert_eq!(&pager.read_record(first).unwrap()[..], &shrunk[..]);
+    assert_eq!(&pager.read_record(second).unwrap()[..], &record(64, 2)[..]);
+}
+
+#[test]
+pub fn erased_records_release_their_chain() {
+    let disk = MemoryFileIo::new();
+    let pager = Pager::new(disk.clone());
+
+    let large = pager
+        .write_record(&record(2 * DEFAULT_PAGE_SIZE, 1))
+        .unwrap();
+    pager.erase_record(large).unwrap();
+    assert_eq!(pager.record_len(large).unwrap(), 0);
+    assert!(pager.read_record(large).unwrap().is_empty());
+
+    // released overflow pages are reused by the next chain
+    pager.sync().unwrap();
+    let total_pages = disk.total_pages();
+    let other = pager
+        .write_record(&record(2 * DEFAULT_PAGE_SIZE, 2))
+        .unwrap();
+    pager.sync().unwrap();
+    // the head fragment goes to a new data page, the chain fits into the released pages
+    assert_eq!(disk.total_pages(), total_pages + 1);
+    assert_eq!(
+        &pager.read_record(other).unwrap()[..],
+        &record(2 * DEFAULT_PAGE_SIZE, 2)[..]
+    );
+}
+
+#[test]
+pub fn torn_overflow_pages_break_the_chain() {
+    let mut disk = MemoryFileIo::new();
+    let pager = Pager::new(disk.clone());
+
+    let large = pager
+        .write_record(&record(3 * DEFAULT_PAGE_SIZE, 1))
+        .unwrap();
+    pager.sync().unwrap();
+
+    // the second page of the chain is torn back to an empty overflow page
+    disk.flush_page(2, Page::with_kind(DEFAULT_PAGE_SIZE, PageKind::Overflow))
+        .unwrap();
+    let pager = Pager::new(disk);
+    let err = pager.read_record(large).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+pub fn replaced_records_survive_crashes() {
+    let old = record(3 * DEFAULT_PAGE_SIZE, 1);
+    let new = record(3 * DEFAULT_PAGE_SIZE, 2);
+
+    for writes in 0..5 {
+        let disk = MemoryFileIo::new();
+        let pager = Pager::new(disk.clone());
+        let pos = pager.write_record(&old).unwrap();
+        pager.sync().unwrap();
+        drop(pager);
+
+        let config = FaultConfig::builder()
+            .crash_after_writes(writes)
+            .drop_unsynced(false)
+            .build();
+        let pager = Pager::new(FaultyFileIo::new(disk.clone(), config));
+        let _ = pager.replace_record(pos, &new);
+        drop(pager);
+
+        // the record is either the old or the new one, never a mix of both
+        let record = Pager::new(disk).read_record(pos).unwrap();
+        assert!(
+            record[..] == old[..] || record[..] == new[..],
+            "{writes} writes"
+        );
+    }
+}
+
+#[test]
+pub fn replaced_records_survive_writes_lost_out_of_order() {
+    let old = record(3 * DEFAULT_PAGE_SIZE, 1);
+    let records = [
+        record(3 * DEFAULT_PAGE_SIZE, 2),
+        record(2 * DEFAULT_PAGE_SIZE, 3),
+    ];
+
+    for every in 2..5 {
+        for writes in 0..16 {
+            let disk = MemoryFileIo::new();
+            let pager = Pager::new(disk.clone());
+            let pos = pager.write_record(&old).unwrap();
+            pager.sync().unwrap();
+            drop(pager);
+
+            // the record is replaced twice and the storage crashes before the next sync
+            let config = FaultConfig::builder()
+                .crash_after_writes(writes)
+                .drop_unsynced(false)
+                .lose_unsynced_every(every)
+                .build();
+            let pager = Pager::new(FaultyFileIo::new(disk.clone(), config));
+            for replacement in &records {
+                let _ = pager.replace_record(pos, replacement);
+            }
+            drop(pager);
+
+            let record = Pager::new(disk).read_record(pos).unwrap();
+            assert!(
+                record[..] == old[..] || records.iter().any(|new| record[..] == new[..]),
+                "every {every}, {writes} writes"
+            );
+        }
+    }
+}
+
+#[test]
+pub fn records_are_filled_in_place() {
+    let disk = MemoryFileIo::new();
+    let pager = Pager::new(disk.clone());
+    let large = record(3 * DEFAULT_PAGE_SIZE + 5, 1);
+
+    // written in small pieces, across the slot and every page of the chain
+    let pos = pager
+        .write_record_with(large.len(), |w| {
+            large.chunks(1000).try_for_each(|chunk| w.write_all(chunk))
+        })
+        .unwrap();
+    assert_eq!(&pager.read_record(pos).unwrap()[..], &large[..]);
+
+    let small = record(10, 2);
+    pager
+        .replace_record_with(pos, small.len(), |w| w.write_all(&small))
+        .unwrap();
+    assert_eq!(&pager.read_record(pos).unwrap()[..], &small[..]);
+
+    // a record has to be filled exactly, nothing changes otherwise
+    let short = pager.replace_record_with(pos, large.len(), |w| w.write_all(&small));
+    assert_eq!(short.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    let long = pager.replace_record_with(pos, small.len(), |w| w.write_all(&large));
+    assert_eq!(long.unwrap_err().kind(), io::ErrorKind::WriteZero);
+    assert_eq!(&pager.read_record(pos).unwrap()[..], &small[..]);
+
+    pager.sync().unwrap();
+    let pager = Pager::new(disk);
+    assert_eq!(&pager.read_record(pos).unwrap()[..], &small[..]);
+}
+
+#[test]
+pub fn records_fill_large_pages() {
+    let disk = MemoryFileIo::with_page_size(MAX_PAGE_SIZE);
+    let pager = Pager::new(disk.clone());
+
+    // two records fill the page up to the last byte
+    let half = MAX_PAGE_SIZE / 2 - PAGE_HEADER_SIZE / 2 - SLOT_HEADER_SIZE;
+    let first = pager.write_record(&record(half, 1)).unwrap();
+    let second = pager.write_record(&record(half, 2)).unwrap();
+    pager.sync().unwrap();
+    assert_eq!(first.0, second.0);
+    assert!(disk.load_page(0).unwrap().is_full());
+
+    let large = pager.write_record(&record(3 * MAX_PAGE_SIZE, 3)).unwrap();
+    assert_eq!(large, (1, PAGE_HEADER_SIZE as u32));
+    pager.sync().unwrap();
+
+    let pager = Pager::new(disk);
+    assert_eq!(
+        &pager.read_record(second).unwrap()[..],
+        &record(half, 2)[..]
+    );
+    assert_eq!(
+        &pager.read_record(large).unwrap()[..],
+        &record(3 * MAX_PAGE_SIZE, 3)[..]
+    );
+}
+
+#[test]
+pub fn slots_outside_of_the_page_are_rejected() {
+    let pager = Pager::new(MemoryFileIo::new());
+    let pos = pager.write_record(&record(100, 7)).unwrap();
+    pager.sync().unwrap();
+
+    let page_size = DEFAULT_PAGE_SIZE as u32;
+    for slot in [0, page_size - 4, page_size, u32::MAX] {
+        let pos = RecordId::new(1, pos.0, slot).position();
+        let kind = |result: io::Result<()>| result.unwrap_err().kind();
+        assert_eq!(kind(pager.erase_record(pos)), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            kind(pager.replace_record(pos, &[1u8; 8])),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            kind(pager.read_record(pos).map(drop)),
+            io::ErrorKind::InvalidInput
+        );
+    }
+    assert_eq!(pager.read_record(pos).unwrap(), record(100, 7).into());
+}
+
+#[test]
+pub fn records_are_borrowed_from_their_page_unless_overflowed() {
+    let pager = Pager::new(MemoryFileIo::new());
+    let small = record(100, 8);
+    let large = record(2 * DEFAULT_PAGE_SIZE, 9);
+    let first = pager.write_record(&small).unwrap();
+    let second = pager.write_record(&large).unwrap();
+    pager.reset_stats();
+
+    assert!(pager.with_record(first, |bytes| bytes == small).unwrap());
+    assert!(pager.with_record(second, |bytes| bytes == large).unwrap());
+    let stats = pager.stats();
+    assert_eq!(stats.records_read, 2);
+    assert_eq!(stats.overflow_pages_read, 2);
+
+    pager.erase_record(first).unwrap();
+    assert!(pager.with_record(first, <[u8]>::is_empty).unwrap());
+}
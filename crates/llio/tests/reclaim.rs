@@ -0,0 +1,179 @@
+use std::{fs, io::Write, os::unix::fs::MetadataExt, path::Path};
+
+use llio::{
+    header::FileKind,
+    io::{direct::DirectFileIo, options::FileOptions, PageIo},
+    page::{Page, DEFAULT_PAGE_SIZE},
+    pager::Pager,
+};
+
+mod common;
+
+use common::{open, page_with, test_file};
+
+/// Bytes actually allocated to the file
+fn disk_usage(path: &Path) -> u64 {
+    fs::metadata(path).unwrap().blocks() * 512
+}
+
+fn filled_page(byte: u8) -> Page {
+    page_with(&vec![byte; DEFAULT_PAGE_SIZE - 8])
+}
+
+#[test]
+pub fn pages_are_truncated_and_punched_out() {
+    let path = test_file("reclaim_direct.db");
+    let mut io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    for idx in 0..8 {
+        io.flush_page(idx, filled_page(idx as u8 + 1)).unwrap();
+    }
+    io.sync().unwrap();
+    let usage = disk_usage(&path);
+
+    io.discard_pages(2..4).unwrap();
+    io.sync().unwrap();
+    assert_eq!(disk_usage(&path), usage - 2 * DEFAULT_PAGE_SIZE as u64);
+    assert!(io.load_page(2).unwrap().empty());
+    assert_eq!(io.load_page(4).unwrap().buffer()[8], 5);
+
+    io.truncate(5).unwrap();
+    assert_eq!(io.total_pages(), 5);
+    assert_eq!(
+        fs::metadata(&path).unwrap().len(),
+        6 * DEFAULT_PAGE_SIZE as u64
+    );
+    drop(io);
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    assert_eq!(io.total_pages(), 5);
+    assert_eq!(io.load_page(4).unwrap().buffer()[8], 5);
+}
+
+#[test]
+pub fn compressed_files_shrink_after_truncation() {
+    let path = test_file("reclaim_compressed.db");
+    let options = FileOptions::builder().compression(true).build();
+    let mut io = DirectFileIo::with_options(
+        path.to_str().unwrap(),
+        FileKind::Collection,
+        options.clone(),
+    )
+    .unwrap();
+    io.flush_page(0, filled_page(1)).unwrap();
+    io.sync().unwrap();
+    let len = fs::metadata(&path).unwrap().len();
+
+    // incompressible pages
+    for idx in 1..16 {
+        let mut page = Page::new(DEFAULT_PAGE_SIZE);
+        let noise = (0..DEFAULT_PAGE_SIZE - 8)
+            .map(|i| ((i as u64 * 2654435761 + idx) >> 7) as u8)
+            .collect::<Vec<_>>();
+        page.write_all(&noise).unwrap();
+        io.flush_page(idx, page).unwrap();
+    }
+    io.sync().unwrap();
+    assert!(fs::metadata(&path).unwrap().len() > len + 8 * DEFAULT_PAGE_SIZE as u64);
+
+    io.truncate(1).unwrap();
+    io.sync().unwrap();
+    // the page map of the previous commit is only released by the next one
+    io.sync().unwrap();
+    assert!(fs::metadata(&path).unwrap().len() <= len + DEFAULT_PAGE_SIZE as u64);
+    drop(io);
+
+    let io = open(&path, options).unwrap();
+    assert_eq!(io.total_pages(), 1);
+    assert_eq!(io.load_page(0).unwrap().buffer()[8], 1);
+}
+
+#[test]
+pub fn erased_records_give_their_space_back() {
+    let path = test_file("reclaim_pager.db");
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pager = Pager::new(io);
+
+    let first = pager.write_record(&[1u8; 100]).unwrap();
+    let middle = pager.write_record(&[2u8; 6 * DEFAULT_PAGE_SIZE]).unwrap();
+    let last = pager.write_record(&[3u8; 100]).unwrap();
+    let tail = pager.write_record(&[4u8; 4 * DEFAULT_PAGE_SIZE]).unwrap();
+    pager.sync().unwrap();
+    let len = fs::metadata(&path).unwrap().len();
+    let usage = disk_usage(&path);
+
+    pager.erase_record(middle).unwrap();
+    pager.erase_record(tail).unwrap();
+    assert!(pager.reclaim_space().unwrap() >= 9);
+
+    // the trailing chain is cut off, the one in the middle is punched out
+    assert!(fs::metadata(&path).unwrap().len() <= len - 3 * DEFAULT_PAGE_SIZE as u64);
+    assert!(disk_usage(&path) <= usage - 9 * DEFAULT_PAGE_SIZE as u64);
+    drop(pager);
+
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pager = Pager::new(io);
+    assert_eq!(&pager.read_record(first).unwrap()[..], &[1u8; 100]);
+    assert_eq!(&pager.read_record(last).unwrap()[..], &[3u8; 100]);
+    assert_eq!(pager.record_len(middle).unwrap(), 0);
+    assert_eq!(pager.next_record(first).unwrap(), middle);
+    assert_eq!(pager.next_record(middle).unwrap(), last);
+
+    let appended = pager.write_record(&[5u8; 100]).unwrap();
+    assert_eq!(pager.next_record(tail).unwrap(), appended);
+}
+
+#[test]
+pub fn files_grow_in_preallocated_chunks() {
+    let path = test_file("reclaim_preallocated.db");
+    let options = FileOptions::builder().preallocation(16).build();
+    let mut io = open(&path, options).unwrap();
+    io.flush_page(0, filled_page(1)).unwrap();
+    io.sync().unwrap();
+
+    // the reserved space does not show up in the file length
+    assert_eq!(
+        fs::metadata(&path).unwrap().len(),
+        2 * DEFAULT_PAGE_SIZE as u64
+    );
+    assert!(disk_usage(&path) >= 17 * DEFAULT_PAGE_SIZE as u64);
+    assert_eq!(io.total_pages(), 1);
+}
+
+#[test]
+pub fn pages_released_before_reopening_are_reclaimed() {
+    let path = test_file("reclaim_reopened.db");
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pager = Pager::new(io);
+
+    let first = pager.write_record(&[1u8; 100]).unwrap();
+    let middle = pager.write_record(&[2u8; 6 * DEFAULT_PAGE_SIZE]).unwrap();
+    let last = pager.write_record(&[3u8; 100]).unwrap();
+    let tail = pager.write_record(&[4u8; 4 * DEFAULT_PAGE_SIZE]).unwrap();
+    pager.erase_record(middle).unwrap();
+    pager.erase_record(tail).unwrap();
+    pager.sync().unwrap();
+    drop(pager);
+    let len = fs::metadata(&path).unwrap().len();
+
+    // the released pages are found again on open
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pager = Pager::new(io);
+    assert!(pager.reclaim_space().unwrap() >= 9);
+    assert!(fs::metadata(&path).unwrap().len() <= len - 3 * DEFAULT_PAGE_SIZE as u64);
+    drop(pager);
+
+    // punched out pages are reused by the new chain, only its data page is appended
+    let io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+    let pager = Pager::new(io);
+    let len = fs::metadata(&path).unwrap().len();
+    let replaced = pager.write_record(&[5u8; 4 * DEFAULT_PAGE_SIZE]).unwrap();
+    pager.sync().unwrap();
+    assert!(fs::metadata(&path).unwrap().len() <= len + DEFAULT_PAGE_SIZE as u64);
+
+    assert_eq!(&pager.read_record(first).unwrap()[..], &[1u8; 100]);
+    assert_eq!(&pager.read_record(last).unwrap()[..], &[3u8; 100]);
+    assert_eq!(
+        &pager.read_record(replaced).unwrap()[..],
+        &[5u8; 4 * DEFAULT_PAGE_SIZE]
+    );
+}
@@ -1,34 +1,54 @@
-use llio::util::record_id::RecordId;
+use std::collections::{BTreeSet, HashSet};
+
+use llio::util::record_id::{RecordId, RECORD_ID_SIZE};
 use trail::{deserialize::Deserialize, serialize::Serialize};
 
 #[test]
 pub fn record_id_serialization_works() {
-    let record_id = RecordId::new("/serialization/test".to_string(), 1024);
+    let record_id = RecordId::new(7, 1024, 40);
 
     let buffer = record_id.serialize();
     assert!(buffer.is_ok());
 
     let buffer = buffer.unwrap();
+    assert_eq!(buffer.len(), RECORD_ID_SIZE);
     assert_eq!(
         &buffer[..],
-        [
-            31, 0, 0, 0, 47, 115, 101, 114, 105, 97, 108, 105, 122, 97, 116, 105, 111, 110, 47,
-            116, 101, 115, 116, 0, 4, 0, 0, 0, 0, 0, 0
-        ]
+        [7, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 40, 0, 0, 0]
     );
 }
 
 #[test]
 pub fn record_id_deserialization_works() {
-    let buffer = [
-        31, 0, 0, 0, 47, 115, 101, 114, 105, 97, 108, 105, 122, 97, 116, 105, 111, 110, 47, 116,
-        101, 115, 116, 0, 4, 0, 0, 0, 0, 0, 0,
-    ];
+    let buffer = [7, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 40, 0, 0, 0];
 
     let record_id = RecordId::deserialize(&buffer);
-    // assert!(record_id.is_ok());
+    assert!(record_id.is_ok());
 
     let record_id = record_id.unwrap();
-    assert_eq!(record_id.path(), "/serialization/test");
-    assert_eq!(record_id.offset(), 1024);
+    assert_eq!(record_id.file_id(), 7);
+    assert_eq!(record_id.page(), 1024);
+    assert_eq!(record_id.slot(), 40);
+    assert_eq!(record_id.position(), (1024, 40));
+
+    assert!(RecordId::deserialize(&buffer[..10]).is_err());
+}
+
+#[test]
+pub fn record_ids_are_ordered_by_file_then_position() {
+    let ids = [
+        RecordId::new(2, 0, 8),
+        RecordId::new(1, 5, 8),
+        RecordId::new(1, 0, 500),
+        RecordId::new(1, 0, 8),
+    ];
+
+    let sorted = ids.iter().copied().collect::<BTreeSet<_>>();
+    assert_eq!(
+        sorted.into_iter().collect::<Vec<_>>(),
+        [ids[3], ids[2], ids[1], ids[0]]
+    );
+
+    let hashed = ids.iter().chain(&ids).copied().collect::<HashSet<_>>();
+    assert_eq!(hashed.len(), ids.len());
 }
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use llio::{
+    header::FileKind,
+    io::{direct::DirectFileIo, memory::MemoryFileIo, options::FileOptions, PageIo},
+    page::{Page, DEFAULT_PAGE_SIZE},
+    pager::Pager,
+    stats::{IoStats, LatencyHistogram},
+};
+
+mod common;
+
+use common::{open, page_with, test_file};
+
+#[test]
+pub fn page_transfers_are_counted() {
+    let path = test_file("stats_direct.db");
+    let mut io = DirectFileIo::new(path.to_str().unwrap(), FileKind::Collection).unwrap();
+
+    for idx in 0..4 {
+        io.flush_page(idx, page_with(b"counted")).unwrap();
+    }
+    // still buffered
+    io.load_page(2).unwrap();
+    io.sync().unwrap();
+    io.load_page(2).unwrap();
+    io.prefetch(0..4).unwrap();
+
+    let stats = io.stats();
+    assert_eq!(stats.pages_written, 4);
+    assert_eq!(stats.bytes_written, 4 * DEFAULT_PAGE_SIZE as u64);
+    assert_eq!(stats.flush_batches, 1);
+    assert_eq!(stats.syncs, 1);
+    assert_eq!(stats.cache_hits, 1);
+    assert_eq!(stats.cache_misses, 1);
+    assert_eq!(stats.pages_read, 5);
+    assert_eq!(stats.bytes_read, 5 * DEFAULT_PAGE_SIZE as u64);
+    assert_eq!(stats.ring_submissions, 2);
+    assert_eq!(stats.read_latency.count(), 5);
+
+    io.reset_stats();
+    assert_eq!(io.stats(), IoStats::default());
+
+    // compressed pages count with the size they take on disk
+    let path = test_file("stats_compressed.db");
+    let options = FileOptions::builder().compression(true).build();
+    let mut io = open(&path, options).unwrap();
+    io.flush_page(0, Page::new(DEFAULT_PAGE_SIZE)).unwrap();
+    io.flush_page(0, page_with(&[1u8; 100])).unwrap();
+    io.sync().unwrap();
+    io.prefetch(0..1).unwrap();
+
+    let stats = io.stats();
+    assert_eq!(stats.pages_written, 1);
+    assert_eq!(stats.pages_read, 1);
+    assert!(stats.bytes_read < DEFAULT_PAGE_SIZE as u64);
+}
+
+#[test]
+pub fn records_are_counted_by_the_pager() {
+    let pager = Pager::new(MemoryFileIo::new());
+    let small = pager.write_record(&[1u8; 100]).unwrap();
+    let large = pager.write_record(&[2u8; 3 * DEFAULT_PAGE_SIZE]).unwrap();
+    pager.read_record(small).unwrap();
+    pager.read_record(large).unwrap();
+    pager.erase_record(large).unwrap();
+
+    let stats = pager.stats();
+    assert_eq!(stats.records_written, 2);
+    assert_eq!(stats.records_read, 2);
+    assert_eq!(stats.records_erased, 1);
+    assert!(stats.overflow_pages_written >= 2);
+    assert_eq!(stats.overflow_pages_read, stats.overflow_pages_written);
+    // the in-memory storage keeps no counters
+    assert_eq!(stats.io, IoStats::default());
+
+    pager.reset_stats();
+    assert_eq!(pager.stats().records_written, 0);
+}
+
+#[test]
+pub fn latencies_are_bucketed() {
+    let mut histogram = LatencyHistogram::default();
+    assert_eq!(histogram.quantile(0.5), Duration::ZERO);
+
+    for micros in [0, 3, 3, 3, 100, 5000] {
+        histogram.record(Duration::from_micros(micros));
+    }
+    histogram.record(Duration::from_secs(3600));
+
+    assert_eq!(histogram.count(), 7);
+    assert_eq!(histogram.buckets()[0], 1);
+    assert_eq!(histogram.buckets()[2], 3);
+    assert_eq!(histogram.quantile(0.5), Duration::from_micros(4));
+    assert_eq!(histogram.quantile(0.8), Duration::from_micros(8192));
+    assert_eq!(histogram.quantile(1.0), Duration::from_micros(1 << 23));
+
+    let mut merged = histogram.clone();
+    merged.merge(&histogram);
+    assert_eq!(merged.count(), 14);
+}
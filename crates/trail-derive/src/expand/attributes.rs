@@ -0,0 +1,110 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, ExprPath, Fields, Generics, Ident, LitStr, Result, Token, TypeParamBound, Variant,
+};
+
+/// Where the value of a missing or skipped member comes from
+pub enum DefaultValue {
+    Trait,
+    Function(ExprPath),
+}
+
+impl DefaultValue {
+    /// An expression of a function building the default value
+    pub fn function(&self) -> TokenStream {
+        match self {
+            DefaultValue::Trait => quote!(::std::default::Default::default),
+            DefaultValue::Function(path) => quote!(#path),
+        }
+    }
+}
+
+/// `#[trail(...)]` on a struct member or an enum variant
+#[derive(Default)]
+pub struct Attributes {
+    pub rename: Option<String>,
+    pub default: Option<DefaultValue>,
+    pub skip: bool,
+}
+
+impl Attributes {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut attributes = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("trail")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    attributes.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("default") {
+                    attributes.default = Some(match meta.input.peek(Token![=]) {
+                        true => DefaultValue::Function(meta.value()?.parse::<LitStr>()?.parse()?),
+                        false => DefaultValue::Trait,
+                    });
+                } else if meta.path.is_ident("skip") {
+                    attributes.skip = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `default` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(attributes)
+    }
+
+    /// The name a member or variant is stored under
+    pub fn key(&self, ident: &Ident) -> String {
+        self.rename.clone().unwrap_or_else(|| ident.to_string())
+    }
+
+    /// The default of a skipped member, or of a missing one
+    pub fn default_value(&self) -> Option<DefaultValue> {
+        match (&self.default, self.skip) {
+            (Some(DefaultValue::Trait), _) | (None, true) => Some(DefaultValue::Trait),
+            (Some(DefaultValue::Function(path)), _) => Some(DefaultValue::Function(path.clone())),
+            (None, false) => None,
+        }
+    }
+}
+
+/// Enum variants are only renamed
+pub fn variant_attributes(variant: &Variant) -> Result<Attributes> {
+    let attributes = Attributes::parse(&variant.attrs)?;
+    if attributes.default.is_some() || attributes.skip {
+        return Err(syn::Error::new_spanned(
+            &variant.ident,
+            "variants only take `rename`",
+        ));
+    }
+    Ok(attributes)
+}
+
+/// Members of tuples are stored by position, they take no attributes
+pub fn check_unnamed(fields: &Fields) -> Result<()> {
+    match fields {
+        Fields::Unnamed(fields) => fields.unnamed.iter().try_for_each(|field| {
+            match field
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("trail"))
+            {
+                Some(attr) => Err(syn::Error::new_spanned(
+                    attr,
+                    "members of tuples take no attributes",
+                )),
+                None => Ok(()),
+            }
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Adds `bound` to every type parameter
+pub fn bound(generics: &Generics, bound: TypeParamBound) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+    generics
+}
@@ -0,0 +1,129 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Fields, Result};
+
+use super::attributes::{bound, check_unnamed, variant_attributes, Attributes};
+
+/// `FromField` building a value from its stored form, and `Deserialize` reading it
+pub fn deserialize(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let generics = bound(&input.generics, parse_quote!(::trail::field::FromField));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let value = from_field(&data.fields, quote!(Self), quote!(field))?;
+            quote!(::std::result::Result::Ok(#value))
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let key = variant_attributes(variant)?.key(ident);
+
+                    Ok(match &variant.fields {
+                        Fields::Unit => quote! {
+                            (#key, ::std::option::Option::None) => {
+                                ::std::result::Result::Ok(Self::#ident)
+                            }
+                        },
+                        fields => {
+                            let value = from_field(fields, quote!(Self::#ident), quote!(content))?;
+                            quote! {
+                                (#key, ::std::option::Option::Some(content)) => {
+                                    ::std::result::Result::Ok(#value)
+                                }
+                            }
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            quote! {
+                let (tag, content) = ::trail::field::convert::variant(field)?;
+                match (tag.as_str(), content) {
+                    #(#arms)*
+                    _ => ::std::result::Result::Err(::trail::field::convert::unknown_variant(&tag)),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "unions cannot be deserialized",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::trail::field::FromField for #name #ty_generics #where_clause {
+            fn from_field(
+                field: ::trail::field::Field,
+            ) -> ::std::result::Result<Self, ::std::boxed::Box<dyn ::std::error::Error>> {
+                #body
+            }
+        }
+
+        impl #impl_generics ::trail::deserialize::Deserialize for #name #ty_generics #where_clause {
+            fn deserialize(from: &[u8]) -> ::trail::error::Result<Self> {
+                let field = <::trail::field::Field as ::trail::deserialize::Deserialize>::deserialize(from)?;
+                ::trail::field::FromField::from_field(field)
+                    .map_err(|err| ::trail::error::Error::invalid(0, err.to_string()))
+            }
+        }
+    })
+}
+
+/// An expression building `path` with `fields` out of the `field` expression
+fn from_field(fields: &Fields, path: TokenStream, field: TokenStream) -> Result<TokenStream> {
+    check_unnamed(fields)?;
+
+    Ok(match fields {
+        Fields::Named(_) => {
+            let mut members = Vec::new();
+            for field in fields.iter() {
+                let ident = field.ident.as_ref().unwrap();
+                let attributes = Attributes::parse(&field.attrs)?;
+                let key = attributes.key(ident);
+
+                let value = match (attributes.skip, attributes.default_value()) {
+                    (true, Some(default)) => {
+                        let default = default.function();
+                        quote!(#default())
+                    }
+                    (false, Some(default)) => {
+                        let default = default.function();
+                        quote!(::trail::field::convert::member_or(&mut members, #key, #default)?)
+                    }
+                    (_, None) => quote!(::trail::field::convert::member(&mut members, #key)?),
+                };
+                members.push(quote!(#ident: #value));
+            }
+
+            quote! {{
+                let mut members = ::trail::field::convert::members(#field)?;
+                #path { #(#members),* }
+            }}
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            quote!(#path(::trail::field::FromField::from_field(#field)?))
+        }
+        Fields::Unnamed(fields) => {
+            let len = fields.unnamed.len();
+            let elements = (0..len)
+                .map(|idx| format_ident!("__element{}", idx))
+                .collect::<Vec<_>>();
+
+            quote! {{
+                let [#(#elements),*] = ::trail::field::convert::elements::<#len>(#field)?;
+                #path(#(::trail::field::FromField::from_field(#elements)?),*)
+            }}
+        }
+        Fields::Unit => quote! {{
+            ::trail::field::convert::unit(#field)?;
+            #path
+        }},
+    })
+}
@@ -0,0 +1,6 @@
+pub mod attributes;
+pub mod deserialize;
+pub mod serialize;
+
+pub use deserialize::deserialize;
+pub use serialize::serialize;
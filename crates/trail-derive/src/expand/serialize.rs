@@ -0,0 +1,164 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Fields, Index, Result};
+
+use super::attributes::{bound, check_unnamed, variant_attributes, Attributes};
+
+/// `ToField` building the stored form, and `Serialize` writing it
+pub fn serialize(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let generics = bound(&input.generics, parse_quote!(::trail::field::ToField));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // `Serialize` also needs its type to be `Debug`
+    let debug_generics = bound(&generics, parse_quote!(::std::fmt::Debug));
+    let (debug_impl_generics, _, debug_where_clause) = debug_generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let access = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| match &field.ident {
+                    Some(ident) => quote!(&self.#ident),
+                    None => {
+                        let idx = Index::from(idx);
+                        quote!(&self.#idx)
+                    }
+                });
+            to_field(&data.fields, access.collect())?
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let key = variant_attributes(variant)?.key(ident);
+                    let (patterns, access) = bindings(&variant.fields)?;
+
+                    Ok(match &variant.fields {
+                        Fields::Unit => quote! {
+                            Self::#ident => ::trail::field::Field::from(#key),
+                        },
+                        Fields::Named(_) => {
+                            let content = to_field(&variant.fields, access)?;
+                            quote! {
+                                Self::#ident { #(#patterns),* } => {
+                                    ::trail::field::convert::tagged(#key, #content)
+                                }
+                            }
+                        }
+                        Fields::Unnamed(_) => {
+                            let content = to_field(&variant.fields, access)?;
+                            quote! {
+                                Self::#ident(#(#patterns),*) => {
+                                    ::trail::field::convert::tagged(#key, #content)
+                                }
+                            }
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            match arms.is_empty() {
+                true => quote!(match *self {}),
+                false => quote!(match self { #(#arms)* }),
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(name, "unions cannot be serialized")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::trail::field::ToField for #name #ty_generics #where_clause {
+            fn to_field(&self) -> ::trail::field::Field {
+                #body
+            }
+        }
+
+        impl #debug_impl_generics ::trail::serialize::Serialize for #name #ty_generics #debug_where_clause {
+            fn serialize(
+                &self,
+            ) -> ::std::result::Result<
+                ::std::boxed::Box<[u8]>,
+                ::std::boxed::Box<dyn ::std::error::Error>,
+            > {
+                ::trail::serialize::Serialize::serialize(&::trail::field::ToField::to_field(self))
+            }
+
+            fn size(&self) -> u32 {
+                ::trail::serialize::Serialize::size(&::trail::field::ToField::to_field(self))
+            }
+
+            fn serialize_into(
+                &self,
+                w: &mut dyn ::std::io::Write,
+            ) -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+                ::trail::serialize::Serialize::serialize_into(
+                    &::trail::field::ToField::to_field(self),
+                    w,
+                )
+            }
+        }
+    })
+}
+
+/// The patterns binding a variant's members in a match arm, and how each is read
+fn bindings(fields: &Fields) -> Result<(Vec<TokenStream>, Vec<TokenStream>)> {
+    let mut patterns = Vec::new();
+    let mut access = Vec::new();
+
+    for (idx, field) in fields.iter().enumerate() {
+        let binding = format_ident!("__field{}", idx);
+        match &field.ident {
+            Some(ident) if Attributes::parse(&field.attrs)?.skip => {
+                patterns.push(quote!(#ident: _))
+            }
+            Some(ident) => patterns.push(quote!(#ident: #binding)),
+            None => patterns.push(quote!(#binding)),
+        }
+        access.push(quote!(#binding));
+    }
+
+    Ok((patterns, access))
+}
+
+/// An expression of the field storing `fields`, each read through its `access` expression
+fn to_field(fields: &Fields, access: Vec<TokenStream>) -> Result<TokenStream> {
+    check_unnamed(fields)?;
+
+    Ok(match fields {
+        Fields::Named(_) => {
+            let mut inserts = Vec::new();
+            for (field, access) in fields.iter().zip(access) {
+                let attributes = Attributes::parse(&field.attrs)?;
+                if attributes.skip {
+                    continue;
+                }
+
+                let key = attributes.key(field.ident.as_ref().unwrap());
+                inserts.push(quote! {
+                    members.insert(
+                        ::std::string::String::from(#key),
+                        ::trail::field::ToField::to_field(#access),
+                    );
+                });
+            }
+
+            quote! {{
+                let mut members = ::std::collections::HashMap::new();
+                #(#inserts)*
+                ::trail::field::Field::map(members)
+            }}
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            quote!(::trail::field::ToField::to_field(#(#access)*))
+        }
+        Fields::Unnamed(_) => quote! {
+            ::trail::field::Field::array(::std::vec![
+                #(::trail::field::ToField::to_field(#access)),*
+            ])
+        },
+        Fields::Unit => quote!(::trail::field::Field::null()),
+    })
+}
@@ -0,0 +1,30 @@
+//! `#[derive(Serialize, Deserialize)]` for trail, enabled by its `derive` feature
+//!
+//! Structs are stored as a map of their members, tuple structs as an array and newtypes as
+//! the value they wrap. Unit enum variants are stored as their name, the other variants as a
+//! map of their name to their data. Members and variants take `#[trail(...)]` attributes:
+//!
+//! - `rename = "name"` stores the member or variant under another name
+//! - `default` or `default = "path"` fills a missing member from `Default` or a function
+//! - `skip` never stores the member and always builds it with its default
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod expand;
+
+#[proc_macro_derive(Serialize, attributes(trail))]
+pub fn derive_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::serialize(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(Deserialize, attributes(trail))]
+pub fn derive_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::deserialize(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use trail::{
+    deserialize::Deserialize,
+    field::{Decimal, Field, FromField, Timestamp, ToField},
+    serialize::Serialize,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Invoice {
+    number: u64,
+    #[trail(rename = "customer_name")]
+    customer: String,
+    lines: Vec<Line>,
+    paid_at: Option<Timestamp>,
+    #[trail(default)]
+    tags: Vec<String>,
+    #[trail(default = "default_currency")]
+    currency: String,
+    #[trail(skip)]
+    cached_total: Option<Decimal>,
+    status: Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Line {
+    item: Sku,
+    amount: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Sku(String);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Point(i32, i32);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Marker;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Status {
+    Draft,
+    #[trail(rename = "sent")]
+    Sent(Timestamp),
+    Refunded {
+        amount: Decimal,
+        #[trail(skip)]
+        note: String,
+    },
+    Moved(Point, Marker),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<Box<Page<T>>>,
+}
+
+fn default_currency() -> String {
+    "EUR".to_string()
+}
+
+fn invoice() -> Invoice {
+    Invoice {
+        number: 42,
+        customer: "whyneet".to_string(),
+        lines: vec![Line {
+            item: Sku("A-1".to_string()),
+            amount: "19.99".parse().unwrap(),
+        }],
+        paid_at: None,
+        tags: vec!["q3".to_string()],
+        currency: "USD".to_string(),
+        cached_total: None,
+        status: Status::Refunded {
+            amount: "5".parse().unwrap(),
+            note: String::new(),
+        },
+    }
+}
+
+#[test]
+fn structs_are_stored_as_maps() {
+    let field = invoice().to_field();
+    let members = field.value_as_map().unwrap();
+
+    assert_eq!(members["number"], Field::uint64(42));
+    assert_eq!(members["customer_name"], Field::from("whyneet"));
+    assert!(!members.contains_key("customer"));
+    assert!(!members.contains_key("cached_total"));
+    assert!(members["paid_at"].is_null());
+
+    let line = members["lines"].value_as_array().unwrap()[0]
+        .value_as_map()
+        .unwrap();
+    assert_eq!(line["item"], Field::from("A-1"));
+    assert_eq!(
+        line["amount"],
+        Field::from("19.99".parse::<Decimal>().unwrap())
+    );
+
+    let status = members["status"].value_as_map().unwrap();
+    let refund = status["Refunded"].value_as_map().unwrap();
+    assert_eq!(refund.len(), 1);
+    assert_eq!(refund["amount"], Field::int32(5));
+}
+
+#[test]
+fn derived_types_round_trip() {
+    let mut invoice = invoice();
+    let buffer = invoice.serialize().unwrap();
+    assert_eq!(buffer.len(), invoice.size() as usize);
+    assert_eq!(Invoice::deserialize(&buffer).unwrap(), invoice);
+
+    for status in [
+        Status::Draft,
+        Status::Sent(Timestamp::from_micros(7)),
+        Status::Moved(Point(-1, 2), Marker),
+    ] {
+        invoice.status = status;
+        let buffer = invoice.serialize().unwrap();
+        assert_eq!(Invoice::deserialize(&buffer).unwrap(), invoice);
+    }
+
+    assert_eq!(Status::Draft.to_field(), Field::from("Draft"));
+    let sent = Status::Sent(Timestamp::from_micros(7)).to_field();
+    assert!(sent.value_as_map().unwrap().contains_key("sent"));
+    assert_eq!(
+        Point(3, 4).to_field(),
+        Field::array(vec![Field::int32(3), Field::int32(4)])
+    );
+    assert!(Marker.to_field().is_null());
+
+    let page = Page {
+        items: vec![1u32, 2],
+        next: Some(Box::new(Page {
+            items: vec![3],
+            next: None,
+        })),
+    };
+    assert_eq!(
+        Page::<u32>::deserialize(&page.serialize().unwrap()).unwrap(),
+        page
+    );
+}
+
+#[test]
+fn missing_members_take_their_defaults() {
+    let mut members = invoice().to_field().value_as_map().unwrap().clone();
+    members.remove("tags");
+    members.remove("currency");
+    members.remove("paid_at");
+    members.insert(
+        "cached_total".to_string(),
+        Field::from("1".parse::<Decimal>().unwrap()),
+    );
+
+    let invoice = Invoice::from_field(Field::map(members.clone())).unwrap();
+    assert_eq!(invoice.tags, Vec::<String>::new());
+    assert_eq!(invoice.currency, "EUR");
+    assert_eq!(invoice.paid_at, None);
+    assert_eq!(invoice.cached_total, None);
+
+    members.remove("number");
+    let err = Invoice::from_field(Field::map(members)).unwrap_err();
+    assert_eq!(err.to_string(), "missing field `number`");
+}
+
+#[test]
+fn mismatched_fields_are_reported() {
+    let mut members = invoice().to_field().value_as_map().unwrap().clone();
+    members.insert("number".to_string(), Field::from("42"));
+    let err = Invoice::from_field(Field::map(members)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`number`: expected a UInt64 field, found a String field"
+    );
+
+    assert!(Invoice::from_field(Field::int32(1)).is_err());
+    assert!(Point::from_field(Field::array(vec![Field::int32(1)])).is_err());
+    assert!(Marker::from_field(Field::int32(1)).is_err());
+
+    let err = Status::from_field(Field::from("Cancelled")).unwrap_err();
+    assert_eq!(err.to_string(), "unknown variant `Cancelled`");
+    // a variant with data needs it
+    assert!(Status::from_field(Field::from("sent")).is_err());
+
+    let two_tags = HashMap::from([
+        ("Draft".to_string(), Field::null()),
+        ("sent".to_string(), Field::null()),
+    ]);
+    assert!(Status::from_field(Field::map(two_tags)).is_err());
+}
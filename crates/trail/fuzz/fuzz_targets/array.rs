@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use trail::{deserialize::Deserialize, field::Field};
+
+// array bytes cut short or nested too deep are errors, not panics
+fuzz_target!(|data: &[u8]| {
+    let _ = Vec::<Field>::deserialize(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use trail::{deserialize::Deserialize, field::Field};
+
+// reading any input returns a field or an error, it never panics
+fuzz_target!(|data: &[u8]| {
+    let _ = Field::deserialize(data);
+});
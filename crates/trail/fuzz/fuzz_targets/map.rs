@@ -0,0 +1,11 @@
+#![no_main]
+
+use std::collections::HashMap;
+
+use libfuzzer_sys::fuzz_target;
+use trail::{deserialize::Deserialize, field::Field};
+
+// bytes that are not a map are rejected without panicking
+fuzz_target!(|data: &[u8]| {
+    let _ = HashMap::<String, Field>::deserialize(data);
+});
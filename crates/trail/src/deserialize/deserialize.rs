@@ -1,5 +1,16 @@
-use std::error::Error;
+use crate::error::{Error, Result};
 
 pub trait Deserialize: Sized {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>>;
+    fn deserialize(from: &[u8]) -> Result<Self>;
+}
+
+/// The `len` bytes at `offset`, or an error if the input ends before them
+pub fn take(from: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| from.get(offset..end))
+        .ok_or(Error::UnexpectedEof {
+            offset,
+            needed: len,
+        })
 }
@@ -1,91 +1,89 @@
-use std::{collections::HashMap, io::Error as IoError, rc::Rc};
+use std::{collections::HashMap, rc::Rc};
 
-use crate::{field::Field, serialize::Serialize};
+use crate::{
+    error::{Error, Result, MAX_DEPTH},
+    field::{ArrayRef, Field, MapRef},
+};
 
 use super::macro_impl::deserializable_number;
 
 deserializable_number!(for u128, u64, u32, u16, u8, i128, i64, i32, i16, i8, f64, f32);
 
 impl Deserialize for String {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-        Ok(String::from_utf8_lossy(&from[..]).to_string())
+    fn deserialize(from: &[u8]) -> Result<Self> {
+        String::from_utf8(from.to_vec()).map_err(|err| Error::InvalidUtf8 {
+            offset: err.utf8_error().valid_up_to(),
+        })
     }
 }
 
 impl Deserialize for bool {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-        Ok(from[0] != 0)
+    fn deserialize(from: &[u8]) -> Result<Self> {
+        match from {
+            [byte] => Ok(*byte != 0),
+            _ => Err(Error::InvalidLength {
+                offset: 0,
+                expected: 1,
+                found: from.len(),
+            }),
+        }
     }
 }
 
 impl Deserialize for HashMap<String, Field> {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let mut map = HashMap::new();
-
-        let mut byte_offset = 0;
-
-        while byte_offset < from.len() {
-            let field_name_length = 'block: {
-                for idx in byte_offset..from.len() {
-                    if from[idx + 1] == 0 && from[idx] != b'\\' {
-                        break 'block Some(idx + 1);
-                    }
-                }
-
-                None
-            }
-            .ok_or(IoError::new(
-                std::io::ErrorKind::InvalidData,
-                "invalid ccb input",
-            ))?;
+    fn deserialize(from: &[u8]) -> Result<Self> {
+        read_map(from, 0)
+    }
+}
 
-            let field_name = String::deserialize(&from[byte_offset..field_name_length])?;
-            byte_offset = field_name_length + 1;
-            let field = Field::deserialize(&from[byte_offset..])?;
+/// The members of a map nested `depth` levels deep, in either encoding
+pub(crate) fn read_map(from: &[u8], depth: usize) -> Result<HashMap<String, Field>> {
+    if depth > MAX_DEPTH {
+        return Err(Error::NestingTooDeep { offset: 0 });
+    }
 
-            byte_offset += field.size() as usize;
+    let mut map = HashMap::new();
 
-            map.insert(field_name, field);
+    let mut members = MapRef::new(from).iter();
+    while let Some((offset, name, field)) = members.next_member()? {
+        // every value is decoded so a malformed one is reported, but the first member of a
+        // name wins, just like in `MapRef::get`
+        let field = field.decode(depth).map_err(|err| err.at(offset))?;
+        if !map.contains_key(name) {
+            map.insert(name.to_string(), field);
         }
-
-        Ok(map)
     }
+
+    Ok(map)
 }
 
 impl Deserialize for Vec<Field> {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let mut vec = Vec::new();
-
-        let mut offset = 0;
-
-        while from.len() - offset > 0 {
-            let field = Field::deserialize(&from[(offset as usize)..])?;
-            let size = field.size();
-
-            vec.push(field);
-
-            offset += size as usize;
-        }
-
-        Ok(vec)
+    fn deserialize(from: &[u8]) -> Result<Self> {
+        read_array(from, 0)
     }
 }
 
-impl Deserialize for Vec<Rc<Field>> {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let mut vec = Vec::new();
-
-        let mut offset = 0;
+/// The elements of an array nested `depth` levels deep
+pub(crate) fn read_array(from: &[u8], depth: usize) -> Result<Vec<Field>> {
+    if depth > MAX_DEPTH {
+        return Err(Error::NestingTooDeep { offset: 0 });
+    }
 
-        while offset < from.len() {
-            let field = Field::deserialize(&from[(offset as usize)..])?;
-            let size = field.size();
+    let mut vec = Vec::new();
 
-            vec.push(Rc::new(field));
+    let mut elements = ArrayRef::new(from).iter();
+    while let Some((offset, field)) = elements.next_element()? {
+        vec.push(field.decode(depth).map_err(|err| err.at(offset))?);
+    }
 
-            offset += size as usize;
-        }
+    Ok(vec)
+}
 
-        Ok(vec)
+impl Deserialize for Vec<Rc<Field>> {
+    fn deserialize(from: &[u8]) -> Result<Self> {
+        Ok(Vec::<Field>::deserialize(from)?
+            .into_iter()
+            .map(Rc::new)
+            .collect())
     }
 }
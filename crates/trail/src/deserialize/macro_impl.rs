@@ -1,10 +1,15 @@
 macro_rules! deserializable_number {
     (for $($t:ty),+) => {
       use super::Deserialize;
-      use std::{mem, convert::TryInto, error::Error};
+      use std::mem;
         $(impl Deserialize for $t {
-            fn deserialize(from: &[u8]) -> Result<Self, Box<dyn Error>> {
-              let array: [u8; mem::size_of::<Self>()] = (&from[..]).try_into()?;
+            fn deserialize(from: &[u8]) -> Result<Self> {
+              let array: [u8; mem::size_of::<Self>()] =
+                  from.try_into().map_err(|_| Error::InvalidLength {
+                      offset: 0,
+                      expected: mem::size_of::<Self>(),
+                      found: from.len(),
+                  })?;
                 Ok(Self::from_le_bytes(array))
             }
         })*
@@ -3,3 +3,6 @@ pub mod implementation;
 pub mod macro_impl;
 
 pub use deserialize::*;
+
+#[cfg(feature = "derive")]
+pub use trail_derive::Deserialize;
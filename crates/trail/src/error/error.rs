@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// How deep maps and arrays may nest, deeper input is rejected before it can overflow the stack
+pub const MAX_DEPTH: usize = 128;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors produced while reading values back from bytes
+///
+/// Offsets count from the start of the buffer given to the outermost `deserialize` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input ends before the `needed` bytes starting at `offset`
+    UnexpectedEof { offset: usize, needed: usize },
+    /// A field starts with a type tag this build does not know about
+    UnknownFieldType { offset: usize, tag: u8 },
+    /// A string is not valid UTF-8
+    InvalidUtf8 { offset: usize },
+    /// A length prefix points past the end of the input
+    LengthOverflow { offset: usize, length: u64 },
+    /// A value does not have the size its type needs
+    InvalidLength {
+        offset: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The bytes are not a valid value of their type
+    InvalidData { offset: usize, reason: String },
+    /// Maps and arrays are nested deeper than [`MAX_DEPTH`]
+    NestingTooDeep { offset: usize },
+}
+
+impl Error {
+    pub fn invalid(offset: usize, reason: impl Into<String>) -> Self {
+        Self::InvalidData {
+            offset,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        match *self {
+            Self::UnexpectedEof { offset, .. }
+            | Self::UnknownFieldType { offset, .. }
+            | Self::InvalidUtf8 { offset }
+            | Self::LengthOverflow { offset, .. }
+            | Self::InvalidLength { offset, .. }
+            | Self::InvalidData { offset, .. }
+            | Self::NestingTooDeep { offset } => offset,
+        }
+    }
+
+    /// The same error in a value that starts `base` bytes into a larger input
+    pub fn at(mut self, base: usize) -> Self {
+        match &mut self {
+            Self::UnexpectedEof { offset, .. }
+            | Self::UnknownFieldType { offset, .. }
+            | Self::InvalidUtf8 { offset }
+            | Self::LengthOverflow { offset, .. }
+            | Self::InvalidLength { offset, .. }
+            | Self::InvalidData { offset, .. }
+            | Self::NestingTooDeep { offset } => *offset = offset.saturating_add(base),
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof { offset, needed } => {
+                write!(
+                    f,
+                    "unexpected end of input: {needed} bytes needed at offset {offset}"
+                )
+            }
+            Self::UnknownFieldType { offset, tag } => {
+                write!(f, "unknown field type {tag} at offset {offset}")
+            }
+            Self::InvalidUtf8 { offset } => write!(f, "invalid UTF-8 at offset {offset}"),
+            Self::LengthOverflow { offset, length } => {
+                write!(f, "length {length} at offset {offset} overflows the input")
+            }
+            Self::InvalidLength {
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "invalid length at offset {offset}: expected {expected} bytes, found {found}"
+            ),
+            Self::InvalidData { offset, reason } => {
+                write!(f, "invalid data at offset {offset}: {reason}")
+            }
+            Self::NestingTooDeep { offset } => {
+                write!(
+                    f,
+                    "nesting deeper than {MAX_DEPTH} levels at offset {offset}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
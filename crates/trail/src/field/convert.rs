@@ -0,0 +1,220 @@
+use std::{collections::HashMap, error::Error};
+
+use super::{
+    decimal::Decimal,
+    field::{Field, FieldType, FieldValue, TypeMismatch},
+    timestamp::Timestamp,
+    uuid::Uuid,
+};
+
+/// Converts a value into a [`Field`], structs and enums get it from `#[derive(Serialize)]`
+pub trait ToField {
+    fn to_field(&self) -> Field;
+}
+
+/// Builds a value back from a [`Field`], structs and enums get it from `#[derive(Deserialize)]`
+pub trait FromField: Sized {
+    fn from_field(field: Field) -> Result<Self, Box<dyn Error>>;
+
+    /// The value of a struct member missing from its map, `None` when the member is required
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+fn mismatch(expected: FieldType, found: &FieldValue) -> Box<dyn Error> {
+    Box::new(TypeMismatch {
+        expected,
+        found: found.field_type(),
+    })
+}
+
+/// `ToField` and `FromField` for the types a field stores by value
+macro_rules! field_by_value {
+    ($($t:ty),+) => {
+        $(
+            impl ToField for $t {
+                fn to_field(&self) -> Field {
+                    Field::from(self.clone())
+                }
+            }
+
+            impl FromField for $t {
+                fn from_field(field: Field) -> Result<Self, Box<dyn Error>> {
+                    Ok(field.get::<$t>()?)
+                }
+            }
+        )+
+    };
+}
+
+field_by_value!(String, i8, u8, i32, u32, i64, u64, f32, f64, bool, Timestamp, Uuid, Decimal);
+
+impl ToField for str {
+    fn to_field(&self) -> Field {
+        Field::from(self)
+    }
+}
+
+impl ToField for Field {
+    fn to_field(&self) -> Field {
+        self.clone()
+    }
+}
+
+impl FromField for Field {
+    fn from_field(field: Field) -> Result<Self, Box<dyn Error>> {
+        Ok(field)
+    }
+}
+
+impl<T: ToField + ?Sized> ToField for &T {
+    fn to_field(&self) -> Field {
+        (**self).to_field()
+    }
+}
+
+impl<T: ToField + ?Sized> ToField for Box<T> {
+    fn to_field(&self) -> Field {
+        (**self).to_field()
+    }
+}
+
+impl<T: FromField> FromField for Box<T> {
+    fn from_field(field: Field) -> Result<Self, Box<dyn Error>> {
+        T::from_field(field).map(Box::new)
+    }
+}
+
+/// `None` is stored as a null field
+impl<T: ToField> ToField for Option<T> {
+    fn to_field(&self) -> Field {
+        self.as_ref().map_or_else(Field::null, ToField::to_field)
+    }
+}
+
+impl<T: FromField> FromField for Option<T> {
+    fn from_field(field: Field) -> Result<Self, Box<dyn Error>> {
+        match field.is_null() {
+            true => Ok(None),
+            false => T::from_field(field).map(Some),
+        }
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// Vectors are stored as arrays, `Vec<u8>` included, a [`Field::binary`] member takes raw bytes
+impl<T: ToField> ToField for Vec<T> {
+    fn to_field(&self) -> Field {
+        Field::array(self.iter().map(ToField::to_field).collect())
+    }
+}
+
+impl<T: FromField> FromField for Vec<T> {
+    fn from_field(field: Field) -> Result<Self, Box<dyn Error>> {
+        match field.into_value() {
+            FieldValue::Array(elements) => elements.into_iter().map(T::from_field).collect(),
+            value => Err(mismatch(FieldType::Array, &value)),
+        }
+    }
+}
+
+impl<T: ToField> ToField for HashMap<String, T> {
+    fn to_field(&self) -> Field {
+        Field::map(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.to_field()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: FromField> FromField for HashMap<String, T> {
+    fn from_field(field: Field) -> Result<Self, Box<dyn Error>> {
+        match field.into_value() {
+            FieldValue::Map(map) => map
+                .into_iter()
+                .map(|(key, field)| Ok((key, T::from_field(field)?)))
+                .collect(),
+            value => Err(mismatch(FieldType::Map, &value)),
+        }
+    }
+}
+
+/*
+
+helpers for the code generated by trail-derive
+
+*/
+
+/// The members of a struct, stored as a map
+pub fn members(field: Field) -> Result<HashMap<String, Field>, Box<dyn Error>> {
+    HashMap::<String, Field>::from_field(field)
+}
+
+/// Takes a required member out of the map, or the one of an `Option` type
+pub fn member<T: FromField>(
+    members: &mut HashMap<String, Field>,
+    key: &str,
+) -> Result<T, Box<dyn Error>> {
+    match members.remove(key) {
+        Some(field) => T::from_field(field).map_err(|err| format!("`{key}`: {err}").into()),
+        None => T::missing().ok_or_else(|| format!("missing field `{key}`").into()),
+    }
+}
+
+/// Takes a member out of the map, falling back to `default` when it is missing
+pub fn member_or<T: FromField>(
+    members: &mut HashMap<String, Field>,
+    key: &str,
+    default: impl FnOnce() -> T,
+) -> Result<T, Box<dyn Error>> {
+    match members.contains_key(key) {
+        true => member(members, key),
+        false => Ok(default()),
+    }
+}
+
+/// The elements of a tuple, stored as an array of exactly `N` fields
+pub fn elements<const N: usize>(field: Field) -> Result<[Field; N], Box<dyn Error>> {
+    let elements = Vec::<Field>::from_field(field)?;
+    let len = elements.len();
+    elements
+        .try_into()
+        .map_err(|_| format!("expected {N} elements, found {len}").into())
+}
+
+/// A unit struct, stored as a null field
+pub fn unit(field: Field) -> Result<(), Box<dyn Error>> {
+    match field.into_value() {
+        FieldValue::Null => Ok(()),
+        value => Err(mismatch(FieldType::Null, &value)),
+    }
+}
+
+/// An enum variant with data, stored as a map of its name to the data
+pub fn tagged(tag: &str, content: Field) -> Field {
+    Field::map(HashMap::from([(tag.to_string(), content)]))
+}
+
+/// The name of an enum variant and its data, unit variants are stored as just their name
+pub fn variant(field: Field) -> Result<(String, Option<Field>), Box<dyn Error>> {
+    match field.into_value() {
+        FieldValue::String(tag) => Ok((tag, None)),
+        FieldValue::Map(map) => {
+            let mut entries = map.into_iter();
+            match (entries.next(), entries.next()) {
+                (Some((tag, content)), None) => Ok((tag, Some(content))),
+                _ => Err("a variant is a map of exactly one entry".into()),
+            }
+        }
+        value => Err(mismatch(FieldType::Map, &value)),
+    }
+}
+
+pub fn unknown_variant(tag: &str) -> Box<dyn Error> {
+    format!("unknown variant `{tag}`").into()
+}
@@ -0,0 +1,230 @@
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::{deserialize::Deserialize, error, serialize::Serialize};
+
+/// The most digits a decimal may have after the point, every `i128` coefficient fits in it
+pub const MAX_SCALE: u8 = 38;
+pub const DECIMAL_SIZE: usize = 17;
+
+/// An exact decimal number, `coefficient * 10^-scale`
+///
+/// Decimals compare by value, `1.5` equals `1.50` although the two print differently.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    coefficient: i128,
+    scale: u8,
+}
+
+fn power_of_ten(exponent: u8) -> i128 {
+    10i128.pow(exponent as u32)
+}
+
+impl Decimal {
+    /// Panics when the scale is over [`MAX_SCALE`]
+    pub fn new(coefficient: i128, scale: u8) -> Self {
+        assert!(
+            scale <= MAX_SCALE,
+            "decimal scale {scale} is over {MAX_SCALE}"
+        );
+        Self { coefficient, scale }
+    }
+
+    pub fn coefficient(&self) -> i128 {
+        self.coefficient
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// The same value with the trailing zeros after the point removed
+    pub fn normalize(&self) -> Self {
+        let mut decimal = *self;
+        while decimal.scale > 0 && decimal.coefficient % 10 == 0 {
+            decimal.coefficient /= 10;
+            decimal.scale -= 1;
+        }
+        decimal
+    }
+
+    /// The same value with more digits after the point, `None` if the coefficient overflows
+    fn rescale(&self, scale: u8) -> Option<Self> {
+        let coefficient = self
+            .coefficient
+            .checked_mul(power_of_ten(scale - self.scale))?;
+        Some(Self { coefficient, scale })
+    }
+
+    /// Exact sum at the larger of the two scales, `None` on overflow
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (self.rescale(scale)?, other.rescale(scale)?);
+        Some(Self::new(a.coefficient.checked_add(b.coefficient)?, scale))
+    }
+
+    /// Exact difference at the larger of the two scales, `None` on overflow
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (self.rescale(scale)?, other.rescale(scale)?);
+        Some(Self::new(a.coefficient.checked_sub(b.coefficient)?, scale))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // integral parts first, then the fractions at a common scale, neither can overflow
+        let (a, b) = (power_of_ten(self.scale), power_of_ten(other.scale));
+        let integral = (self.coefficient / a).cmp(&(other.coefficient / b));
+
+        let scale = self.scale.max(other.scale);
+        let fraction = |decimal: &Self, unit: i128| {
+            (decimal.coefficient % unit) * power_of_ten(scale - decimal.scale)
+        };
+        integral.then_with(|| fraction(self, a).cmp(&fraction(other, b)))
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let decimal = self.normalize();
+        decimal.coefficient.hash(state);
+        decimal.scale.hash(state);
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coefficient < 0 {
+            f.write_str("-")?;
+        }
+
+        let digits = self.coefficient.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return f.write_str(&digits);
+        }
+
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (integral, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{integral}.{fraction}")
+    }
+}
+
+/// A string is not a decimal number like `-12.50`, or it does not fit in a [`Decimal`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseDecimalError;
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid decimal")
+    }
+}
+
+impl Error for ParseDecimalError {}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    /// Keeps the scale as written, `"1.50"` has a scale of 2
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integral, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if integral.is_empty() && fraction.is_empty() || fraction.len() > MAX_SCALE as usize {
+            return Err(ParseDecimalError);
+        }
+
+        let mut coefficient: i128 = 0;
+        for c in integral.chars().chain(fraction.chars()) {
+            let digit = c.to_digit(10).ok_or(ParseDecimalError)? as i128;
+            coefficient = coefficient
+                .checked_mul(10)
+                .and_then(|c| match negative {
+                    true => c.checked_sub(digit),
+                    false => c.checked_add(digit),
+                })
+                .ok_or(ParseDecimalError)?;
+        }
+
+        Ok(Self::new(coefficient, fraction.len() as u8))
+    }
+}
+
+/// `From` for integers, at a scale of 0
+macro_rules! decimal_from_integer {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for Decimal {
+                fn from(value: $t) -> Self {
+                    Self::new(value as i128, 0)
+                }
+            }
+        )+
+    };
+}
+
+decimal_from_integer!(i8, u8, i16, u16, i32, u32, i64, u64, i128);
+
+/*
+
+binary decimal repr:
+
+coefficient (i128)
+scale (u8)
+
+*/
+
+impl Serialize for Decimal {
+    fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
+        let mut buffer = [0u8; DECIMAL_SIZE];
+        buffer[..16].copy_from_slice(&self.coefficient.to_le_bytes());
+        buffer[16] = self.scale;
+        Ok(Box::new(buffer))
+    }
+
+    fn size(&self) -> u32 {
+        DECIMAL_SIZE as u32
+    }
+}
+
+impl Deserialize for Decimal {
+    fn deserialize(from: &[u8]) -> error::Result<Self> {
+        let [coefficient @ .., scale]: [u8; DECIMAL_SIZE] =
+            from.try_into().map_err(|_| error::Error::InvalidLength {
+                offset: 0,
+                expected: DECIMAL_SIZE,
+                found: from.len(),
+            })?;
+        if scale > MAX_SCALE {
+            return Err(error::Error::invalid(
+                16,
+                format!("decimal scale {scale} is over {MAX_SCALE}"),
+            ));
+        }
+
+        Ok(Self::new(i128::from_le_bytes(coefficient), scale))
+    }
+}
@@ -1,9 +1,21 @@
-use std::{collections::HashMap, ffi::c_void, mem, ptr};
-
-use crate::{deserialize::Deserialize, serialize::Serialize};
+use std::{cmp::Ordering, collections::HashMap, error::Error, fmt, io::Write, mem, vec};
+
+use crate::{
+    deserialize::{
+        implementation::{read_array, read_map},
+        Deserialize,
+    },
+    error,
+    serialize::{
+        implementation::{serialize_map, MAP_HEADER},
+        serialize_buffered, Serialize,
+    },
+};
+
+use super::{decimal::Decimal, field_ref::FieldRef, timestamp::Timestamp, uuid::Uuid};
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FieldType {
     String = 0,
     Byte = 1,
@@ -15,6 +27,13 @@ pub enum FieldType {
     Float32 = 7,
     Float64 = 8,
     Map = 9,
+    Array = 10,
+    Bool = 11,
+    Null = 12,
+    Binary = 13,
+    Timestamp = 14,
+    Uuid = 15,
+    Decimal = 16,
 }
 
 impl Serialize for FieldType {
@@ -28,8 +47,14 @@ impl Serialize for FieldType {
 }
 
 impl Deserialize for FieldType {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(match from[0] {
+    /// Reads the tag in the first byte
+    fn deserialize(from: &[u8]) -> error::Result<Self> {
+        let tag = *from.first().ok_or(error::Error::UnexpectedEof {
+            offset: 0,
+            needed: 1,
+        })?;
+
+        Ok(match tag {
             0 => FieldType::String,
             1 => FieldType::Byte,
             2 => FieldType::UByte,
@@ -40,279 +65,643 @@ impl Deserialize for FieldType {
             7 => FieldType::Float32,
             8 => FieldType::Float64,
             9 => FieldType::Map,
-            _ => unreachable!(),
+            10 => FieldType::Array,
+            11 => FieldType::Bool,
+            12 => FieldType::Null,
+            13 => FieldType::Binary,
+            14 => FieldType::Timestamp,
+            15 => FieldType::Uuid,
+            16 => FieldType::Decimal,
+            _ => return Err(error::Error::UnknownFieldType { offset: 0, tag }),
         })
     }
 }
 
-impl PartialEq for FieldType {
+/// Value of a [`Field`], one variant per [`FieldType`]
+#[derive(Debug, Clone)]
+pub enum FieldValue {
+    String(String),
+    Byte(i8),
+    UByte(u8),
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Float32(f32),
+    Float64(f64),
+    Map(HashMap<String, Field>),
+    /// Elements may be of different types, including other arrays and maps
+    Array(Vec<Field>),
+    Bool(bool),
+    /// An explicitly absent value, it has no bytes
+    Null,
+    Binary(Vec<u8>),
+    Timestamp(Timestamp),
+    Uuid(Uuid),
+    Decimal(Decimal),
+}
+
+impl FieldValue {
+    pub fn field_type(&self) -> FieldType {
+        match self {
+            FieldValue::String(_) => FieldType::String,
+            FieldValue::Byte(_) => FieldType::Byte,
+            FieldValue::UByte(_) => FieldType::UByte,
+            FieldValue::Int32(_) => FieldType::Int32,
+            FieldValue::UInt32(_) => FieldType::UInt32,
+            FieldValue::Int64(_) => FieldType::Int64,
+            FieldValue::UInt64(_) => FieldType::UInt64,
+            FieldValue::Float32(_) => FieldType::Float32,
+            FieldValue::Float64(_) => FieldType::Float64,
+            FieldValue::Map(_) => FieldType::Map,
+            FieldValue::Array(_) => FieldType::Array,
+            FieldValue::Bool(_) => FieldType::Bool,
+            FieldValue::Null => FieldType::Null,
+            FieldValue::Binary(_) => FieldType::Binary,
+            FieldValue::Timestamp(_) => FieldType::Timestamp,
+            FieldValue::Uuid(_) => FieldType::Uuid,
+            FieldValue::Decimal(_) => FieldType::Decimal,
+        }
+    }
+
+    /// Decimals and integers as an exact number, to compare them with each other
+    fn as_decimal(&self) -> Option<Decimal> {
+        match *self {
+            FieldValue::Byte(value) => Some(value.into()),
+            FieldValue::UByte(value) => Some(value.into()),
+            FieldValue::Int32(value) => Some(value.into()),
+            FieldValue::UInt32(value) => Some(value.into()),
+            FieldValue::Int64(value) => Some(value.into()),
+            FieldValue::UInt64(value) => Some(value.into()),
+            FieldValue::Decimal(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for FieldValue {
+    /// Integers and decimals are equal by value, whatever their types
     fn eq(&self, other: &Self) -> bool {
-        self.serialize().unwrap() == other.serialize().unwrap()
+        match (self, other) {
+            (FieldValue::Map(a), FieldValue::Map(b)) => a == b,
+            (FieldValue::Array(a), FieldValue::Array(b)) => a == b,
+            _ => self.partial_cmp(other) == Some(Ordering::Equal),
+        }
+    }
+}
+
+impl PartialOrd for FieldValue {
+    /// Only values of the same type are ordered, arrays and binaries element by element and
+    /// maps not at all, nulls are equal to each other. Integers and decimals of any type are
+    /// ordered against each other, by value.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (FieldValue::String(a), FieldValue::String(b)) => a.partial_cmp(b),
+            (FieldValue::Float32(a), FieldValue::Float32(b)) => a.partial_cmp(b),
+            (FieldValue::Float64(a), FieldValue::Float64(b)) => a.partial_cmp(b),
+            (FieldValue::Array(a), FieldValue::Array(b)) => a.partial_cmp(b),
+            (FieldValue::Bool(a), FieldValue::Bool(b)) => a.partial_cmp(b),
+            (FieldValue::Null, FieldValue::Null) => Some(Ordering::Equal),
+            (FieldValue::Binary(a), FieldValue::Binary(b)) => a.partial_cmp(b),
+            (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a.partial_cmp(b),
+            (FieldValue::Uuid(a), FieldValue::Uuid(b)) => a.partial_cmp(b),
+            _ => Some(self.as_decimal()?.cmp(&other.as_decimal()?)),
+        }
+    }
+}
+
+impl Serialize for FieldValue {
+    fn serialize(&self) -> Result<Box<[u8]>, Box<dyn std::error::Error>> {
+        serialize_buffered(self)
+    }
+
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            FieldValue::String(value) => value.serialize_into(w),
+            FieldValue::Byte(value) => value.serialize_into(w),
+            FieldValue::UByte(value) => value.serialize_into(w),
+            FieldValue::Int32(value) => value.serialize_into(w),
+            FieldValue::UInt32(value) => value.serialize_into(w),
+            FieldValue::Int64(value) => value.serialize_into(w),
+            FieldValue::UInt64(value) => value.serialize_into(w),
+            FieldValue::Float32(value) => value.serialize_into(w),
+            FieldValue::Float64(value) => value.serialize_into(w),
+            FieldValue::Map(value) => value.serialize_into(w),
+            FieldValue::Array(value) => value.serialize_into(w),
+            FieldValue::Bool(value) => value.serialize_into(w),
+            FieldValue::Null => Ok(()),
+            FieldValue::Binary(value) => Ok(w.write_all(value)?),
+            FieldValue::Timestamp(value) => value.serialize_into(w),
+            FieldValue::Uuid(value) => value.serialize_into(w),
+            FieldValue::Decimal(value) => value.serialize_into(w),
+        }
+    }
+
+    fn size(&self) -> u32 {
+        match self {
+            FieldValue::String(value) => value.size(),
+            FieldValue::Byte(value) => value.size(),
+            FieldValue::UByte(value) => value.size(),
+            FieldValue::Int32(value) => value.size(),
+            FieldValue::UInt32(value) => value.size(),
+            FieldValue::Int64(value) => value.size(),
+            FieldValue::UInt64(value) => value.size(),
+            FieldValue::Float32(value) => value.size(),
+            FieldValue::Float64(value) => value.size(),
+            FieldValue::Map(value) => value.size(),
+            FieldValue::Array(value) => value.size(),
+            FieldValue::Bool(value) => value.size(),
+            FieldValue::Null => 0,
+            FieldValue::Binary(value) => value.len() as u32,
+            FieldValue::Timestamp(value) => value.size(),
+            FieldValue::Uuid(value) => value.size(),
+            FieldValue::Decimal(value) => value.size(),
+        }
     }
 }
 
-#[derive(Debug)]
+/// A field was read as a type it does not hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeMismatch {
+    pub expected: FieldType,
+    pub found: FieldType,
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected a {:?} field, found a {:?} field",
+            self.expected, self.found
+        )
+    }
+}
+
+impl Error for TypeMismatch {}
+
+#[derive(Debug, Clone)]
 pub struct Field {
-    field_type: FieldType,
-    value: Box<dyn Serialize>,
+    value: FieldValue,
 }
 
 impl Serialize for Field {
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn std::error::Error>> {
-        // just the binary for value
-        let value_buffer = self.value.serialize()?;
-        let mut full_buffer = vec![0u8; self.size() as usize].into_boxed_slice();
-
-        unsafe {
-            ptr::copy_nonoverlapping(
-                (self.field_type as u8).to_le_bytes().as_ptr(),
-                full_buffer.as_mut_ptr(),
-                1,
-            );
-
-            let value_buffer_len = (value_buffer.len() as u32).to_le_bytes();
-
-            ptr::copy_nonoverlapping(
-                value_buffer_len.as_ptr(),
-                full_buffer.as_mut_ptr().add(1),
-                value_buffer_len.len(),
-            );
-
-            ptr::copy_nonoverlapping(
-                value_buffer.as_ptr(),
-                full_buffer
-                    .as_mut_ptr()
-                    .add(mem::size_of::<u8>() + mem::size_of::<u32>()),
-                value_buffer.len(),
-            );
-        };
+        serialize_buffered(self)
+    }
 
-        Ok(full_buffer)
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn std::error::Error>> {
+        // the sizes of nested fields are computed once, not again at every level they are in
+        let mut sizes = Vec::new();
+        self.value_sizes(&mut sizes);
+        self.serialize_sized(w, &mut sizes.into_iter())
     }
 
     fn size(&self) -> u32 {
-        self.field_type.size() + mem::size_of::<u32>() as u32 + self.value.size()
+        self.field_type().size() + mem::size_of::<u32>() as u32 + self.value.size()
     }
 }
 
-impl Deserialize for Field {
-    fn deserialize(from: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
-        let field_type = FieldType::deserialize(&[from[0]])?;
-        let field_length = u32::deserialize(&from[1..5])?;
-
-        let field_start: usize = 5;
-        let field_end = field_start + field_length as usize;
-
-        let value: Box<dyn Serialize> = match field_type {
-            FieldType::String => Box::new(String::deserialize(&from[field_start..field_end])?),
-            FieldType::Byte => Box::new(i8::deserialize(&from[field_start..field_end])?),
-            FieldType::UByte => Box::new(u8::deserialize(&from[field_start..field_end])?),
-            FieldType::Int32 => Box::new(i32::deserialize(&from[field_start..field_end])?),
-            FieldType::UInt32 => Box::new(u32::deserialize(&from[field_start..field_end])?),
-            FieldType::Int64 => Box::new(i64::deserialize(&from[field_start..field_end])?),
-            FieldType::UInt64 => Box::new(u64::deserialize(&from[field_start..field_end])?),
-            FieldType::Float32 => Box::new(f32::deserialize(&from[field_start..field_end])?),
-            FieldType::Float64 => Box::new(f64::deserialize(&from[field_start..field_end])?),
-            FieldType::Map => Box::new(HashMap::<String, Field>::deserialize(
-                &from[field_start..field_end],
-            )?),
+impl Field {
+    /// Collects the value size of this field and of every field nested in it, in the order
+    /// they are written, and returns the first one
+    fn value_sizes(&self, sizes: &mut Vec<u32>) -> u32 {
+        let header = self.field_type().size() + mem::size_of::<u32>() as u32;
+        let idx = sizes.len();
+        sizes.push(0);
+
+        let size = match &self.value {
+            FieldValue::Map(members) => {
+                members
+                    .iter()
+                    .fold(MAP_HEADER.len() as u32, |acc, (key, field)| {
+                        let name = mem::size_of::<u32>() as u32 + key.len() as u32;
+                        acc + name + header + field.value_sizes(sizes)
+                    })
+            }
+            FieldValue::Array(elements) => elements
+                .iter()
+                .map(|field| header + field.value_sizes(sizes))
+                .sum(),
+            value => value.size(),
         };
+        sizes[idx] = size;
+
+        size
+    }
+
+    /// Writes the field with the sizes collected by [`Field::value_sizes`]
+    fn serialize_sized(
+        &self,
+        w: &mut dyn Write,
+        sizes: &mut vec::IntoIter<u32>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        w.write_all(&[self.field_type() as u8])?;
+        w.write_all(&sizes.next().unwrap_or_default().to_le_bytes())?;
+
+        match &self.value {
+            FieldValue::Map(members) => serialize_map(
+                w,
+                members.iter().map(|(key, field)| (key.as_str(), field)),
+                |w, field| field.serialize_sized(w, sizes),
+            ),
+            FieldValue::Array(elements) => elements
+                .iter()
+                .try_for_each(|field| field.serialize_sized(w, sizes)),
+            value => value.serialize_into(w),
+        }
+    }
+}
 
-        Ok(Self { field_type, value })
+impl Deserialize for Field {
+    /// Reads the field at the start of `from`, the bytes after it are ignored
+    fn deserialize(from: &[u8]) -> error::Result<Self> {
+        Self::read(from, 0).map(|(field, _)| field)
+    }
+}
+
+impl Field {
+    /// Reads the field at the start of `from` and the number of bytes it takes, `depth` counts
+    /// the maps and arrays it is nested in
+    pub(crate) fn read(from: &[u8], depth: usize) -> error::Result<(Self, usize)> {
+        let (field, length) = FieldRef::read(from)?;
+
+        Ok((field.decode(depth)?, length))
+    }
+
+    /// Decodes a value of `field_type` from its bytes
+    pub(crate) fn read_value(
+        field_type: FieldType,
+        from: &[u8],
+        depth: usize,
+    ) -> error::Result<FieldValue> {
+        Ok(match field_type {
+            FieldType::String => FieldValue::String(String::deserialize(from)?),
+            FieldType::Byte => FieldValue::Byte(i8::deserialize(from)?),
+            FieldType::UByte => FieldValue::UByte(u8::deserialize(from)?),
+            FieldType::Int32 => FieldValue::Int32(i32::deserialize(from)?),
+            FieldType::UInt32 => FieldValue::UInt32(u32::deserialize(from)?),
+            FieldType::Int64 => FieldValue::Int64(i64::deserialize(from)?),
+            FieldType::UInt64 => FieldValue::UInt64(u64::deserialize(from)?),
+            FieldType::Float32 => FieldValue::Float32(f32::deserialize(from)?),
+            FieldType::Float64 => FieldValue::Float64(f64::deserialize(from)?),
+            FieldType::Map => FieldValue::Map(read_map(from, depth + 1)?),
+            FieldType::Array => FieldValue::Array(read_array(from, depth + 1)?),
+            FieldType::Bool => FieldValue::Bool(bool::deserialize(from)?),
+            FieldType::Null if from.is_empty() => FieldValue::Null,
+            FieldType::Null => {
+                return Err(error::Error::InvalidLength {
+                    offset: 0,
+                    expected: 0,
+                    found: from.len(),
+                })
+            }
+            FieldType::Binary => FieldValue::Binary(from.to_vec()),
+            FieldType::Timestamp => FieldValue::Timestamp(Timestamp::deserialize(from)?),
+            FieldType::Uuid => FieldValue::Uuid(Uuid::deserialize(from)?),
+            FieldType::Decimal => FieldValue::Decimal(Decimal::deserialize(from)?),
+        })
     }
 }
 
 impl Field {
+    pub fn new(value: FieldValue) -> Self {
+        Self { value }
+    }
+
     pub fn string(value: String) -> Self {
-        Self {
-            field_type: FieldType::String,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::String(value))
     }
 
     pub fn byte(value: i8) -> Self {
-        Self {
-            field_type: FieldType::Byte,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::Byte(value))
     }
 
     pub fn ubyte(value: u8) -> Self {
-        Self {
-            field_type: FieldType::UByte,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::UByte(value))
     }
 
     pub fn int32(value: i32) -> Self {
-        Self {
-            field_type: FieldType::Int32,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::Int32(value))
     }
 
     pub fn uint32(value: u32) -> Self {
-        Self {
-            field_type: FieldType::UInt32,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::UInt32(value))
     }
 
     pub fn int64(value: i64) -> Self {
-        Self {
-            field_type: FieldType::Int64,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::Int64(value))
     }
 
     pub fn uint64(value: u64) -> Self {
-        Self {
-            field_type: FieldType::UInt64,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::UInt64(value))
     }
 
     pub fn float32(value: f32) -> Self {
-        Self {
-            field_type: FieldType::Float32,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::Float32(value))
     }
 
     pub fn float64(value: f64) -> Self {
-        Self {
-            field_type: FieldType::Float64,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::Float64(value))
     }
 
-    pub fn map_str<'a: 'static>(value: HashMap<&'a str, Field>) -> Self {
-        Self {
-            field_type: FieldType::Map,
-            value: Box::new(value),
-        }
+    pub fn map_str(value: HashMap<&str, Field>) -> Self {
+        Self::map(
+            value
+                .into_iter()
+                .map(|(key, field)| (key.to_string(), field))
+                .collect(),
+        )
     }
 
     pub fn map(value: HashMap<String, Field>) -> Self {
-        Self {
-            field_type: FieldType::Map,
-            value: Box::new(value),
-        }
+        Self::new(FieldValue::Map(value))
     }
 
-    pub fn value(&self) -> &Box<dyn Serialize> {
-        &self.value
+    pub fn array(value: Vec<Field>) -> Self {
+        Self::new(FieldValue::Array(value))
     }
 
-    pub fn value_as_string(&self) -> &str {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn bool(value: bool) -> Self {
+        Self::new(FieldValue::Bool(value))
+    }
 
-        unsafe { (ptr as *const String).as_ref().unwrap() }
+    pub fn null() -> Self {
+        Self::new(FieldValue::Null)
     }
 
-    pub fn value_as_byte(&self) -> &i8 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn binary(value: Vec<u8>) -> Self {
+        Self::new(FieldValue::Binary(value))
+    }
 
-        unsafe { (ptr as *const i8).as_ref().unwrap() }
+    pub fn timestamp(value: Timestamp) -> Self {
+        Self::new(FieldValue::Timestamp(value))
     }
 
-    pub fn value_as_ubyte(&self) -> &u8 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn uuid(value: Uuid) -> Self {
+        Self::new(FieldValue::Uuid(value))
+    }
 
-        unsafe { (ptr as *const u8).as_ref().unwrap() }
+    pub fn decimal(value: Decimal) -> Self {
+        Self::new(FieldValue::Decimal(value))
     }
 
-    pub fn value_as_int32(&self) -> &i32 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn field_type(&self) -> FieldType {
+        self.value.field_type()
+    }
 
-        unsafe { (ptr as *const i32).as_ref().unwrap() }
+    pub fn value(&self) -> &FieldValue {
+        &self.value
+    }
+
+    pub fn into_value(self) -> FieldValue {
+        self.value
     }
 
-    pub fn value_as_uint32(&self) -> &u32 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    /// Converts the value into `T`, failing when the field holds another type
+    pub fn get<'a, T>(&'a self) -> Result<T, TypeMismatch>
+    where
+        T: TryFrom<&'a Field, Error = TypeMismatch>,
+    {
+        T::try_from(self)
+    }
 
-        unsafe { (ptr as *const u32).as_ref().unwrap() }
+    pub fn value_as_string(&self) -> Option<&str> {
+        match &self.value {
+            FieldValue::String(value) => Some(value),
+            _ => None,
+        }
     }
 
-    pub fn value_as_int64(&self) -> &i64 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn value_as_byte(&self) -> Option<&i8> {
+        match &self.value {
+            FieldValue::Byte(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        unsafe { (ptr as *const i64).as_ref().unwrap() }
+    pub fn value_as_ubyte(&self) -> Option<&u8> {
+        match &self.value {
+            FieldValue::UByte(value) => Some(value),
+            _ => None,
+        }
     }
 
-    pub fn value_as_uint64(&self) -> &u64 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn value_as_int32(&self) -> Option<&i32> {
+        match &self.value {
+            FieldValue::Int32(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        unsafe { (ptr as *const u64).as_ref().unwrap() }
+    pub fn value_as_uint32(&self) -> Option<&u32> {
+        match &self.value {
+            FieldValue::UInt32(value) => Some(value),
+            _ => None,
+        }
     }
 
-    pub fn value_as_float32(&self) -> &f32 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn value_as_int64(&self) -> Option<&i64> {
+        match &self.value {
+            FieldValue::Int64(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        unsafe { (ptr as *const f32).as_ref().unwrap() }
+    pub fn value_as_uint64(&self) -> Option<&u64> {
+        match &self.value {
+            FieldValue::UInt64(value) => Some(value),
+            _ => None,
+        }
     }
 
-    pub fn value_as_float64(&self) -> &f64 {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn value_as_float32(&self) -> Option<&f32> {
+        match &self.value {
+            FieldValue::Float32(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        unsafe { (ptr as *const f64).as_ref().unwrap() }
+    pub fn value_as_float64(&self) -> Option<&f64> {
+        match &self.value {
+            FieldValue::Float64(value) => Some(value),
+            _ => None,
+        }
     }
 
-    pub fn value_as_str_map(&self) -> &HashMap<&str, Field> {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn value_as_map(&self) -> Option<&HashMap<String, Field>> {
+        match &self.value {
+            FieldValue::Map(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        unsafe { (ptr as *const HashMap<&str, Field>).as_ref().unwrap() }
+    pub fn value_as_array(&self) -> Option<&Vec<Field>> {
+        match &self.value {
+            FieldValue::Array(value) => Some(value),
+            _ => None,
+        }
     }
 
-    pub fn value_as_map(&self) -> &HashMap<String, Field> {
-        let ptr = self.value.as_ref() as *const _ as *const c_void;
+    pub fn value_as_bool(&self) -> Option<&bool> {
+        match &self.value {
+            FieldValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
 
-        unsafe { (ptr as *const HashMap<String, Field>).as_ref().unwrap() }
+    pub fn is_null(&self) -> bool {
+        matches!(self.value, FieldValue::Null)
     }
-}
 
-impl PartialEq for Field {
-    fn eq(&self, other: &Self) -> bool {
-        if self.field_type != other.field_type {
-            return false;
+    pub fn value_as_binary(&self) -> Option<&[u8]> {
+        match &self.value {
+            FieldValue::Binary(value) => Some(value),
+            _ => None,
         }
+    }
 
-        match self.field_type {
-            FieldType::String => self.value_as_string() == other.value_as_string(),
-            FieldType::Byte => self.value_as_byte() == other.value_as_byte(),
-            FieldType::UByte => self.value_as_ubyte() == other.value_as_ubyte(),
-            FieldType::Int32 => self.value_as_int32() == other.value_as_int32(),
-            FieldType::UInt32 => self.value_as_uint32() == other.value_as_uint32(),
-            FieldType::Int64 => self.value_as_int64() == other.value_as_int64(),
-            FieldType::UInt64 => self.value_as_uint64() == other.value_as_uint64(),
-            FieldType::Float32 => self.value_as_float32() == other.value_as_float32(),
-            FieldType::Float64 => self.value_as_float64() == other.value_as_float64(),
-            FieldType::Map => self.value_as_str_map() == other.value_as_str_map(),
+    pub fn value_as_timestamp(&self) -> Option<&Timestamp> {
+        match &self.value {
+            FieldValue::Timestamp(value) => Some(value),
+            _ => None,
         }
     }
-}
 
-impl PartialOrd for Field {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.field_type != other.field_type {
-            return None;
+    pub fn value_as_uuid(&self) -> Option<&Uuid> {
+        match &self.value {
+            FieldValue::Uuid(value) => Some(value),
+            _ => None,
         }
+    }
 
-        match self.field_type {
-            FieldType::String => self.value_as_string().partial_cmp(other.value_as_string()),
-            FieldType::Byte => self.value_as_byte().partial_cmp(other.value_as_byte()),
-            FieldType::UByte => self.value_as_ubyte().partial_cmp(other.value_as_ubyte()),
-            FieldType::Int32 => self.value_as_int32().partial_cmp(other.value_as_int32()),
-            FieldType::UInt32 => self.value_as_uint32().partial_cmp(other.value_as_uint32()),
-            FieldType::Int64 => self.value_as_int64().partial_cmp(other.value_as_int64()),
-            FieldType::UInt64 => self.value_as_uint64().partial_cmp(other.value_as_uint64()),
-            FieldType::Float32 => self
-                .value_as_float32()
-                .partial_cmp(other.value_as_float32()),
-            FieldType::Float64 => self
-                .value_as_float64()
-                .partial_cmp(other.value_as_float64()),
+    pub fn value_as_decimal(&self) -> Option<&Decimal> {
+        match &self.value {
+            FieldValue::Decimal(value) => Some(value),
             _ => None,
         }
     }
 }
+
+impl PartialEq for Field {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for Field {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl From<FieldValue> for Field {
+    fn from(value: FieldValue) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Field {
+    fn from(value: &str) -> Self {
+        Self::string(value.to_string())
+    }
+}
+
+impl From<HashMap<String, Field>> for Field {
+    fn from(value: HashMap<String, Field>) -> Self {
+        Self::map(value)
+    }
+}
+
+impl From<Vec<Field>> for Field {
+    fn from(value: Vec<Field>) -> Self {
+        Self::array(value)
+    }
+}
+
+impl<'a> TryFrom<&'a Field> for &'a str {
+    type Error = TypeMismatch;
+
+    fn try_from(field: &'a Field) -> Result<Self, Self::Error> {
+        field.value_as_string().ok_or(TypeMismatch {
+            expected: FieldType::String,
+            found: field.field_type(),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a Field> for &'a HashMap<String, Field> {
+    type Error = TypeMismatch;
+
+    fn try_from(field: &'a Field) -> Result<Self, Self::Error> {
+        field.value_as_map().ok_or(TypeMismatch {
+            expected: FieldType::Map,
+            found: field.field_type(),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a Field> for &'a [u8] {
+    type Error = TypeMismatch;
+
+    fn try_from(field: &'a Field) -> Result<Self, Self::Error> {
+        field.value_as_binary().ok_or(TypeMismatch {
+            expected: FieldType::Binary,
+            found: field.field_type(),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a Field> for &'a Vec<Field> {
+    type Error = TypeMismatch;
+
+    fn try_from(field: &'a Field) -> Result<Self, Self::Error> {
+        field.value_as_array().ok_or(TypeMismatch {
+            expected: FieldType::Array,
+            found: field.field_type(),
+        })
+    }
+}
+
+/// `From<T>` and `TryFrom<&Field>` for the types stored by value
+macro_rules! field_conversions {
+    ($($t:ty => $variant:ident),+) => {
+        $(
+            impl From<$t> for Field {
+                fn from(value: $t) -> Self {
+                    Self::new(FieldValue::$variant(value))
+                }
+            }
+
+            impl TryFrom<&Field> for $t {
+                type Error = TypeMismatch;
+
+                fn try_from(field: &Field) -> Result<Self, Self::Error> {
+                    match &field.value {
+                        FieldValue::$variant(value) => Ok(value.clone()),
+                        _ => Err(TypeMismatch {
+                            expected: FieldType::$variant,
+                            found: field.field_type(),
+                        }),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+field_conversions!(
+    String => String,
+    i8 => Byte,
+    u8 => UByte,
+    i32 => Int32,
+    u32 => UInt32,
+    i64 => Int64,
+    u64 => UInt64,
+    f32 => Float32,
+    f64 => Float64,
+    bool => Bool,
+    Vec<u8> => Binary,
+    Timestamp => Timestamp,
+    Uuid => Uuid,
+    Decimal => Decimal
+);
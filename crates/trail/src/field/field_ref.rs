@@ -0,0 +1,339 @@
+use std::{collections::HashMap, str};
+
+use crate::{
+    deserialize::{
+        implementation::{read_array, read_map},
+        take, Deserialize,
+    },
+    error::{Error, Result},
+    serialize::implementation::{MAP_HEADER, MAP_VERSION},
+};
+
+use super::field::{Field, FieldType};
+
+/// The type and length in front of every field value
+const FIELD_HEADER_SIZE: usize = 5;
+
+/// A field read in place from its bytes, its value is decoded only when asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRef<'a> {
+    field_type: FieldType,
+    value: &'a [u8],
+}
+
+impl<'a> FieldRef<'a> {
+    /// The field at the start of `from`, the bytes after it are ignored
+    pub fn new(from: &'a [u8]) -> Result<Self> {
+        Self::read(from).map(|(field, _)| field)
+    }
+
+    /// The field at the start of `from` and the number of bytes it takes
+    pub(crate) fn read(from: &'a [u8]) -> Result<(Self, usize)> {
+        let field_type = FieldType::deserialize(take(from, 0, 1)?)?;
+        let length = u32::deserialize(take(from, 1, 4)?).map_err(|err| err.at(1))?;
+        let value =
+            take(from, FIELD_HEADER_SIZE, length as usize).map_err(|_| Error::LengthOverflow {
+                offset: 1,
+                length: length as u64,
+            })?;
+
+        Ok((Self { field_type, value }, FIELD_HEADER_SIZE + value.len()))
+    }
+
+    pub fn field_type(&self) -> FieldType {
+        self.field_type
+    }
+
+    /// The encoded value, without the type and length in front of it
+    pub fn value_bytes(&self) -> &'a [u8] {
+        self.value
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.field_type == FieldType::Null
+    }
+
+    /// `None` if the field is not a string, or not valid UTF-8
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.field_type {
+            FieldType::String => str::from_utf8(self.value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_binary(&self) -> Option<&'a [u8]> {
+        match self.field_type {
+            FieldType::Binary => Some(self.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<MapRef<'a>> {
+        match self.field_type {
+            FieldType::Map => Some(MapRef::new(self.value)),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<ArrayRef<'a>> {
+        match self.field_type {
+            FieldType::Array => Some(ArrayRef::new(self.value)),
+            _ => None,
+        }
+    }
+
+    /// Decodes the field into an owned one
+    pub fn to_field(&self) -> Result<Field> {
+        self.decode(0)
+    }
+
+    /// Decodes a field nested in `depth` maps and arrays
+    pub(crate) fn decode(&self, depth: usize) -> Result<Field> {
+        Field::read_value(self.field_type, self.value, depth)
+            .map(Field::new)
+            .map_err(|err| err.at(FIELD_HEADER_SIZE))
+    }
+}
+
+/// The members of a map read in place, in either map encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapRef<'a> {
+    from: &'a [u8],
+}
+
+impl<'a> MapRef<'a> {
+    /// A view of the bytes written by `HashMap<String, Field>::serialize`
+    pub fn new(from: &'a [u8]) -> Self {
+        Self { from }
+    }
+
+    /// The members in the order they are stored
+    pub fn iter(&self) -> Members<'a> {
+        Members {
+            from: self.from,
+            offset: 0,
+            legacy: None,
+            done: false,
+        }
+    }
+
+    /// The first member named `name`, the members before it are read but not decoded.
+    /// Later members of the same name are ignored, also when the map is decoded as a whole.
+    pub fn get(&self, name: &str) -> Result<Option<FieldRef<'a>>> {
+        let mut members = self.iter();
+        while let Some((_, key, field)) = members.next_member()? {
+            if key == name {
+                return Ok(Some(field));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Decodes the members into owned fields, of members sharing a name the first one is kept
+    pub fn to_map(&self) -> Result<HashMap<String, Field>> {
+        read_map(self.from, 0)
+    }
+}
+
+impl<'a> IntoIterator for MapRef<'a> {
+    type Item = Result<(&'a str, FieldRef<'a>)>;
+    type IntoIter = Members<'a>;
+
+    fn into_iter(self) -> Members<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the members of a [`MapRef`], it stops after the first error
+#[derive(Debug, Clone)]
+pub struct Members<'a> {
+    from: &'a [u8],
+    offset: usize,
+    /// Whether the map has null-terminated names, known once its header is read
+    legacy: Option<bool>,
+    done: bool,
+}
+
+impl<'a> Members<'a> {
+    /// The next member and the offset of its field in the map
+    pub(crate) fn next_member(&mut self) -> Result<Option<(usize, &'a str, FieldRef<'a>)>> {
+        let legacy = match self.legacy {
+            Some(legacy) => legacy,
+            None => {
+                let legacy = self.read_header()?;
+                self.legacy = Some(legacy);
+                legacy
+            }
+        };
+
+        if self.offset >= self.from.len() {
+            return Ok(None);
+        }
+
+        let (name, field_offset) = match legacy {
+            true => self.legacy_name()?,
+            false => self.name()?,
+        };
+        let (field, length) =
+            FieldRef::read(&self.from[field_offset..]).map_err(|err| err.at(field_offset))?;
+        self.offset = field_offset + length;
+
+        Ok(Some((field_offset, name, field)))
+    }
+
+    /// Skips the version header, `true` for a map written before maps had one
+    fn read_header(&mut self) -> Result<bool> {
+        match self.from {
+            [marker, version, ..] if *marker == MAP_HEADER[0] => match *version {
+                MAP_VERSION => {
+                    self.offset = MAP_HEADER.len();
+                    Ok(false)
+                }
+                version => Err(Error::invalid(1, format!("unknown map version {version}"))),
+            },
+            [marker] if *marker == MAP_HEADER[0] => Err(Error::UnexpectedEof {
+                offset: 1,
+                needed: 1,
+            }),
+            _ => Ok(true),
+        }
+    }
+
+    /// A length-prefixed name and the offset of the field after it
+    fn name(&self) -> Result<(&'a str, usize)> {
+        let length = u32::deserialize(take(self.from, self.offset, 4)?)?;
+        let start = self.offset + 4;
+        let name = take(self.from, start, length as usize).map_err(|_| Error::LengthOverflow {
+            offset: self.offset,
+            length: length as u64,
+        })?;
+        let name = str::from_utf8(name).map_err(|err| Error::InvalidUtf8 {
+            offset: start + err.valid_up_to(),
+        })?;
+
+        Ok((name, start + name.len()))
+    }
+
+    /// A null-terminated name and the offset of the field after it
+    fn legacy_name(&self) -> Result<(&'a str, usize)> {
+        // the name runs up to the first null terminator that is not escaped
+        let rest = &self.from[self.offset..];
+        let length = rest
+            .windows(2)
+            .position(|pair| pair[1] == 0 && pair[0] != b'\\')
+            .map(|idx| idx + 1)
+            .ok_or_else(|| Error::invalid(self.offset, "unterminated field name"))?;
+        let name = str::from_utf8(&rest[..length]).map_err(|err| Error::InvalidUtf8 {
+            offset: self.offset + err.valid_up_to(),
+        })?;
+
+        Ok((name, self.offset + length + 1))
+    }
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = Result<(&'a str, FieldRef<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_member() {
+            Ok(member) => member.map(|(_, name, field)| Ok((name, field))),
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// The elements of an array read in place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrayRef<'a> {
+    from: &'a [u8],
+}
+
+impl<'a> ArrayRef<'a> {
+    /// A view of the bytes written by `Vec<Field>::serialize`
+    pub fn new(from: &'a [u8]) -> Self {
+        Self { from }
+    }
+
+    pub fn iter(&self) -> Elements<'a> {
+        Elements {
+            from: self.from,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// The element at `index`, the elements before it are read but not decoded
+    pub fn get(&self, index: usize) -> Result<Option<FieldRef<'a>>> {
+        let mut elements = self.iter();
+        for _ in 0..index {
+            if elements.next_element()?.is_none() {
+                return Ok(None);
+            }
+        }
+
+        Ok(elements.next_element()?.map(|(_, field)| field))
+    }
+
+    /// Decodes the elements into owned fields
+    pub fn to_vec(&self) -> Result<Vec<Field>> {
+        read_array(self.from, 0)
+    }
+}
+
+impl<'a> IntoIterator for ArrayRef<'a> {
+    type Item = Result<FieldRef<'a>>;
+    type IntoIter = Elements<'a>;
+
+    fn into_iter(self) -> Elements<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of an [`ArrayRef`], it stops after the first error
+#[derive(Debug, Clone)]
+pub struct Elements<'a> {
+    from: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> Elements<'a> {
+    /// The next element and its offset in the array
+    pub(crate) fn next_element(&mut self) -> Result<Option<(usize, FieldRef<'a>)>> {
+        if self.offset >= self.from.len() {
+            return Ok(None);
+        }
+
+        let offset = self.offset;
+        let (field, length) = FieldRef::read(&self.from[offset..]).map_err(|err| err.at(offset))?;
+        self.offset += length;
+
+        Ok(Some((offset, field)))
+    }
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = Result<FieldRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_element() {
+            Ok(element) => element.map(|(_, field)| Ok(field)),
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
@@ -1,3 +1,13 @@
+pub mod convert;
+pub mod decimal;
 pub mod field;
+pub mod field_ref;
+pub mod timestamp;
+pub mod uuid;
 
+pub use convert::{FromField, ToField};
+pub use decimal::*;
 pub use field::*;
+pub use field_ref::*;
+pub use timestamp::*;
+pub use uuid::*;
@@ -0,0 +1,63 @@
+use std::{
+    error::Error,
+    mem,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{deserialize::Deserialize, error, serialize::Serialize};
+
+/// A point in time as microseconds since the Unix epoch, without a timezone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    pub fn now() -> Self {
+        SystemTime::now().into()
+    }
+
+    pub fn as_micros(&self) -> i64 {
+        self.0
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    /// Saturates at the range of an `i64` of microseconds, about 292 thousand years
+    fn from(time: SystemTime) -> Self {
+        let micros = |duration: Duration| i64::try_from(duration.as_micros()).unwrap_or(i64::MAX);
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => Self(micros(after)),
+            Err(before) => Self(-micros(before.duration())),
+        }
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        let duration = Duration::from_micros(timestamp.0.unsigned_abs());
+        if timestamp.0 < 0 {
+            UNIX_EPOCH - duration
+        } else {
+            UNIX_EPOCH + duration
+        }
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
+        self.0.serialize()
+    }
+
+    fn size(&self) -> u32 {
+        mem::size_of::<i64>() as u32
+    }
+}
+
+impl Deserialize for Timestamp {
+    fn deserialize(from: &[u8]) -> error::Result<Self> {
+        Ok(Self(i64::deserialize(from)?))
+    }
+}
@@ -0,0 +1,92 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{deserialize::Deserialize, error, serialize::Serialize};
+
+pub const UUID_SIZE: usize = 16;
+
+/// A 128-bit identifier, ordered by its bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; UUID_SIZE]);
+
+impl Uuid {
+    pub fn from_bytes(bytes: [u8; UUID_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn nil() -> Self {
+        Self([0; UUID_SIZE])
+    }
+
+    pub fn as_bytes(&self) -> &[u8; UUID_SIZE] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Uuid {
+    /// The hyphenated form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, byte) in self.0.iter().enumerate() {
+            if matches!(idx, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A string is not 32 hex digits, optionally hyphenated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseUuidError;
+
+impl fmt::Display for ParseUuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid uuid")
+    }
+}
+
+impl Error for ParseUuidError {}
+
+impl FromStr for Uuid {
+    type Err = ParseUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .chars()
+            .filter(|&c| c != '-')
+            .map(|c| c.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ParseUuidError)?;
+        if digits.len() != UUID_SIZE * 2 {
+            return Err(ParseUuidError);
+        }
+
+        let mut bytes = [0; UUID_SIZE];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+            *byte = pair[0] << 4 | pair[1];
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for Uuid {
+    fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
+        Ok(Box::new(self.0))
+    }
+
+    fn size(&self) -> u32 {
+        UUID_SIZE as u32
+    }
+}
+
+impl Deserialize for Uuid {
+    fn deserialize(from: &[u8]) -> error::Result<Self> {
+        let bytes = from.try_into().map_err(|_| error::Error::InvalidLength {
+            offset: 0,
+            expected: UUID_SIZE,
+            found: from.len(),
+        })?;
+        Ok(Self(bytes))
+    }
+}
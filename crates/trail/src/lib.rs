@@ -1,3 +1,8 @@
 pub mod deserialize;
+pub mod error;
 pub mod field;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod serialize;
+
+pub use error::Error;
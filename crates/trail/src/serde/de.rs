@@ -0,0 +1,348 @@
+use std::{collections::HashMap, fmt};
+
+use ::serde::{
+    de::{
+        self, value::MapDeserializer, value::SeqDeserializer, DeserializeOwned, DeserializeSeed,
+        EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize,
+};
+
+use crate::field::{Field, FieldValue};
+
+use super::error::Error;
+
+/// Converts a field back into a serde value
+pub fn from_field<T: DeserializeOwned>(field: Field) -> Result<T, Error> {
+    T::deserialize(FieldDeserializer(field))
+}
+
+/// A serde `Deserializer` reading a [`Field`]
+///
+/// Timestamps are read as their microseconds, uuids and decimals as their text.
+#[derive(Debug, Clone)]
+pub struct FieldDeserializer(pub Field);
+
+impl<'de> IntoDeserializer<'de, Error> for Field {
+    type Deserializer = FieldDeserializer;
+
+    fn into_deserializer(self) -> FieldDeserializer {
+        FieldDeserializer(self)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.into_value() {
+            FieldValue::String(value) => visitor.visit_string(value),
+            FieldValue::Byte(value) => visitor.visit_i8(value),
+            FieldValue::UByte(value) => visitor.visit_u8(value),
+            FieldValue::Int32(value) => visitor.visit_i32(value),
+            FieldValue::UInt32(value) => visitor.visit_u32(value),
+            FieldValue::Int64(value) => visitor.visit_i64(value),
+            FieldValue::UInt64(value) => visitor.visit_u64(value),
+            FieldValue::Float32(value) => visitor.visit_f32(value),
+            FieldValue::Float64(value) => visitor.visit_f64(value),
+            FieldValue::Map(map) => {
+                let mut map =
+                    MapDeserializer::new(map.into_iter().map(|(k, v)| (KeyDeserializer(k), v)));
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            FieldValue::Array(elements) => {
+                let mut seq = SeqDeserializer::new(elements.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            FieldValue::Bool(value) => visitor.visit_bool(value),
+            FieldValue::Null => visitor.visit_unit(),
+            FieldValue::Binary(value) => visitor.visit_byte_buf(value),
+            FieldValue::Timestamp(value) => visitor.visit_i64(value.as_micros()),
+            FieldValue::Uuid(value) => visitor.visit_string(value.to_string()),
+            FieldValue::Decimal(value) => visitor.visit_string(value.to_string()),
+        }
+    }
+
+    /// 128-bit integers are stored as decimals without digits after the point
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.value_as_decimal() {
+            Some(value) if value.scale() == 0 => visitor.visit_i128(value.coefficient()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.value_as_decimal() {
+            Some(value) if value.scale() == 0 && value.coefficient() >= 0 => {
+                visitor.visit_u128(value.coefficient() as u128)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.is_null() {
+            true => visitor.visit_none(),
+            false => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let (variant, content) = match self.0.into_value() {
+            FieldValue::String(variant) => (variant, None),
+            FieldValue::Map(map) => {
+                let mut entries = map.into_iter();
+                match (entries.next(), entries.next()) {
+                    (Some((variant, content)), None) => (variant, Some(content)),
+                    _ => return Err(Error::new("a variant is a map of exactly one entry")),
+                }
+            }
+            value => {
+                return Err(Error::new(format!(
+                    "expected a variant, found a {:?} field",
+                    value.field_type()
+                )))
+            }
+        };
+
+        visitor.visit_enum(VariantDeserializer { variant, content })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// A map key, integer keys are read back from their decimal text
+struct KeyDeserializer(String);
+
+impl<'de> IntoDeserializer<'de, Error> for KeyDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// `deserialize_*` parsing the key as an integer
+macro_rules! deserialize_integer_key {
+    ($($method:ident => $visit:ident: $t:ty),+) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse::<$t>() {
+                    Ok(key) => visitor.$visit(key),
+                    Err(_) => visitor.visit_string(self.0),
+                }
+            }
+        )+
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_integer_key!(
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64
+    );
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        FieldDeserializer(Field::string(self.0)).deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct seq
+        tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// The name of an enum variant and its data, `None` for unit variants
+struct VariantDeserializer {
+    variant: String,
+    content: Option<Field>,
+}
+
+impl<'de> EnumAccess<'de> for VariantDeserializer {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant = seed.deserialize(self.variant.as_str().into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.content {
+            None => Ok(()),
+            Some(content) => Deserialize::deserialize(FieldDeserializer(content)),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        let content = self
+            .content
+            .ok_or_else(|| Error::new("a newtype variant needs its data"))?;
+        seed.deserialize(FieldDeserializer(content))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        let content = self
+            .content
+            .ok_or_else(|| Error::new("a tuple variant needs its data"))?;
+        de::Deserializer::deserialize_seq(FieldDeserializer(content), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let content = self
+            .content
+            .ok_or_else(|| Error::new("a struct variant needs its data"))?;
+        de::Deserializer::deserialize_map(FieldDeserializer(content), visitor)
+    }
+}
+
+/// Fields can be members of serde types, they are read back as the closest field type
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FieldVisitor)
+    }
+}
+
+struct FieldVisitor;
+
+impl<'de> Visitor<'de> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Field, E> {
+        Ok(Field::bool(v))
+    }
+
+    fn visit_i8<E: de::Error>(self, v: i8) -> Result<Field, E> {
+        Ok(Field::byte(v))
+    }
+
+    fn visit_i32<E: de::Error>(self, v: i32) -> Result<Field, E> {
+        Ok(Field::int32(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Field, E> {
+        Ok(Field::int64(v))
+    }
+
+    fn visit_u8<E: de::Error>(self, v: u8) -> Result<Field, E> {
+        Ok(Field::ubyte(v))
+    }
+
+    fn visit_u32<E: de::Error>(self, v: u32) -> Result<Field, E> {
+        Ok(Field::uint32(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Field, E> {
+        Ok(Field::uint64(v))
+    }
+
+    fn visit_f32<E: de::Error>(self, v: f32) -> Result<Field, E> {
+        Ok(Field::float32(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Field, E> {
+        Ok(Field::float64(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+        Ok(Field::from(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Field, E> {
+        Ok(Field::string(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Field, E> {
+        Ok(Field::binary(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Field, E> {
+        Ok(Field::binary(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Field, E> {
+        Ok(Field::null())
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Field, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Field, E> {
+        Ok(Field::null())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Field, A::Error> {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(Field::array(elements))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Field, A::Error> {
+        let mut members = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry()? {
+            members.insert(key, value);
+        }
+        Ok(Field::map(members))
+    }
+}
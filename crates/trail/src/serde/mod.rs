@@ -0,0 +1,13 @@
+//! A bridge between serde and trail, enabled by the `serde` feature
+//!
+//! Any `serde::Serialize` type becomes a [`Field`](crate::field::Field) tree and back. Structs
+//! and maps become map fields, sequences and tuples arrays, and enums follow serde's external
+//! tagging: unit variants are their name, the others a map of their name to their data.
+
+pub mod de;
+pub mod error;
+pub mod ser;
+
+pub use de::{from_field, FieldDeserializer};
+pub use error::Error;
+pub use ser::{to_field, FieldSerializer};
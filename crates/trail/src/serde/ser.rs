@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use ::serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+
+use crate::field::{convert::tagged, Decimal, Field, FieldValue};
+
+use super::error::Error;
+
+/// Converts a serde value into a field
+pub fn to_field<T: Serialize + ?Sized>(value: &T) -> Result<Field, Error> {
+    value.serialize(FieldSerializer)
+}
+
+/// A serde `Serializer` building a [`Field`]
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSerializer;
+
+impl ser::Serializer for FieldSerializer {
+    type Ok = Field;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeFieldMap;
+    type SerializeStruct = SerializeFieldMap;
+    type SerializeStructVariant = SerializeFieldMap;
+
+    fn serialize_bool(self, v: bool) -> Result<Field, Error> {
+        Ok(Field::bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Field, Error> {
+        Ok(Field::byte(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Field, Error> {
+        Ok(Field::int32(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Field, Error> {
+        Ok(Field::int32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Field, Error> {
+        Ok(Field::int64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Field, Error> {
+        Ok(Field::decimal(Decimal::from(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Field, Error> {
+        Ok(Field::ubyte(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Field, Error> {
+        Ok(Field::uint32(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Field, Error> {
+        Ok(Field::uint32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Field, Error> {
+        Ok(Field::uint64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Field, Error> {
+        let v = i128::try_from(v).map_err(|_| Error::new("u128 is out of range"))?;
+        self.serialize_i128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Field, Error> {
+        Ok(Field::float32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Field, Error> {
+        Ok(Field::float64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Field, Error> {
+        Ok(Field::string(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Field, Error> {
+        Ok(Field::from(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Field, Error> {
+        Ok(Field::binary(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Field, Error> {
+        Ok(Field::null())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Field, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Field, Error> {
+        Ok(Field::null())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Field, Error> {
+        Ok(Field::null())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Field, Error> {
+        Ok(Field::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Field, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Field, Error> {
+        Ok(tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray {
+            variant: None,
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeFieldMap, Error> {
+        Ok(SerializeFieldMap {
+            variant: None,
+            members: HashMap::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeFieldMap, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeFieldMap, Error> {
+        Ok(SerializeFieldMap {
+            variant: Some(variant),
+            members: HashMap::with_capacity(len),
+            key: None,
+        })
+    }
+}
+
+/// Sequences, tuples and the data of tuple variants
+pub struct SerializeArray {
+    variant: Option<&'static str>,
+    elements: Vec<Field>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.elements.push(to_field(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Field {
+        let array = Field::array(self.elements);
+        match self.variant {
+            Some(variant) => tagged(variant, array),
+            None => array,
+        }
+    }
+}
+
+impl SerializeSeq for SerializeArray {
+    type Ok = Field;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTuple for SerializeArray {
+    type Ok = Field;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleStruct for SerializeArray {
+    type Ok = Field;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleVariant for SerializeArray {
+    type Ok = Field;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+/// Maps, structs and the data of struct variants
+pub struct SerializeFieldMap {
+    variant: Option<&'static str>,
+    members: HashMap<String, Field>,
+    key: Option<String>,
+}
+
+impl SerializeFieldMap {
+    fn finish(self) -> Field {
+        let map = Field::map(self.members);
+        match self.variant {
+            Some(variant) => tagged(variant, map),
+            None => map,
+        }
+    }
+}
+
+impl SerializeMap for SerializeFieldMap {
+    type Ok = Field;
+    type Error = Error;
+
+    /// Keys are strings, integer keys are stored as their decimal text
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = match to_field(key)?.into_value() {
+            FieldValue::String(key) => key,
+            FieldValue::Byte(key) => key.to_string(),
+            FieldValue::UByte(key) => key.to_string(),
+            FieldValue::Int32(key) => key.to_string(),
+            FieldValue::UInt32(key) => key.to_string(),
+            FieldValue::Int64(key) => key.to_string(),
+            FieldValue::UInt64(key) => key.to_string(),
+            value => {
+                return Err(Error::new(format!(
+                    "map keys must be strings, found a {:?}",
+                    value.field_type()
+                )))
+            }
+        };
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::new("a map value was serialized before its key"))?;
+        self.members.insert(key, to_field(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeStruct for SerializeFieldMap {
+    type Ok = Field;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.members.insert(key.to_string(), to_field(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeStructVariant for SerializeFieldMap {
+    type Ok = Field;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.members.insert(key.to_string(), to_field(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Field, Error> {
+        Ok(self.finish())
+    }
+}
+
+/// Fields can be members of serde types, timestamps are written as their microseconds,
+/// uuids and decimals as their text
+impl Serialize for Field {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value() {
+            FieldValue::String(value) => serializer.serialize_str(value),
+            FieldValue::Byte(value) => serializer.serialize_i8(*value),
+            FieldValue::UByte(value) => serializer.serialize_u8(*value),
+            FieldValue::Int32(value) => serializer.serialize_i32(*value),
+            FieldValue::UInt32(value) => serializer.serialize_u32(*value),
+            FieldValue::Int64(value) => serializer.serialize_i64(*value),
+            FieldValue::UInt64(value) => serializer.serialize_u64(*value),
+            FieldValue::Float32(value) => serializer.serialize_f32(*value),
+            FieldValue::Float64(value) => serializer.serialize_f64(*value),
+            FieldValue::Map(map) => serializer.collect_map(map),
+            FieldValue::Array(elements) => serializer.collect_seq(elements),
+            FieldValue::Bool(value) => serializer.serialize_bool(*value),
+            FieldValue::Null => serializer.serialize_unit(),
+            FieldValue::Binary(value) => serializer.serialize_bytes(value),
+            FieldValue::Timestamp(value) => serializer.serialize_i64(value.as_micros()),
+            FieldValue::Uuid(value) => serializer.collect_str(value),
+            FieldValue::Decimal(value) => serializer.collect_str(value),
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use std::{collections::HashMap, mem, ptr, rc::Rc};
+use std::{collections::HashMap, io::Write, mem, rc::Rc};
 
-use crate::{deserialize::Deserialize, field::Field};
+use crate::field::Field;
 
-use super::macro_impl::serializable_number;
+use super::{macro_impl::serializable_number, serialize_buffered};
 
 serializable_number!(for u128, u64, u32, u16, u8, i128, i64, i32, i16, i8, f64, f32);
 
@@ -14,6 +14,11 @@ impl Serialize for String {
     fn size(&self) -> u32 {
         self.len() as u32
     }
+
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        w.write_all(self.as_bytes())?;
+        Ok(())
+    }
 }
 
 impl Serialize for bool {
@@ -28,100 +33,82 @@ impl Serialize for bool {
 
 /*
 
-binary document field repr:
+binary map repr:
+
+marker (u8) - always 0
+version (u8)
+for every member:
+    field_name_length (u32)
+    field_name (String)
+    field_type (u8)
+    field_value_length (u32)
+    field_value ([u8])
 
-field_name (String) + \0
-field_type (u8)
-field_value_length (u32)
-field_value ([u8])
+maps written before the version are a run of field_name + \0 + field, their names are never
+empty so they never start with the marker
 
 */
 
+/// The map encoding written by this build
+pub const MAP_VERSION: u8 = 1;
+/// The bytes every versioned map starts with
+pub const MAP_HEADER: [u8; 2] = [0, MAP_VERSION];
+
+fn map_size<'a>(members: impl Iterator<Item = (&'a str, &'a Field)>) -> u32 {
+    members.fold(MAP_HEADER.len() as u32, |acc, (key, v)| {
+        // acc + field_name_length + field_name + field size
+        acc + mem::size_of::<u32>() as u32 + key.len() as u32 + v.size()
+    })
+}
+
+/// Writes the map header and the members, every field is written by `field`
+pub(crate) fn serialize_map<'a>(
+    w: &mut dyn Write,
+    members: impl Iterator<Item = (&'a str, &'a Field)>,
+    mut field: impl FnMut(&mut dyn Write, &'a Field) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    w.write_all(&MAP_HEADER)?;
+
+    for (key, value) in members {
+        w.write_all(&(key.len() as u32).to_le_bytes())?;
+        w.write_all(key.as_bytes())?;
+        field(w, value)?;
+    }
+
+    Ok(())
+}
+
 impl Serialize for HashMap<&str, Field> {
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
-        let size = self.size();
-        let mut buffer = vec![0u8; size as usize].into_boxed_slice();
-
-        let mut bytes_written: u32 = 0;
-        for (key, value) in self.iter() {
-            // append a null terminator
-            let key = format!("{key}\0");
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    key.as_ptr(),
-                    buffer.as_mut_ptr().add(bytes_written as usize),
-                    key.len(),
-                );
-            };
-
-            bytes_written += key.len() as u32;
-
-            let value = value.serialize()?;
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    value.as_ptr(),
-                    buffer.as_mut_ptr().add(bytes_written as usize),
-                    value.len(),
-                );
-            };
-
-            bytes_written += value.len() as u32;
-        }
-
-        Ok(buffer)
+        serialize_buffered(self)
     }
 
     fn size(&self) -> u32 {
-        self.iter()
-            // acc + field_name + \0 + field size
-            .fold(0, |acc, (key, v)| acc + (key.len() as u32) + 1 + v.size())
+        map_size(self.iter().map(|(key, v)| (*key, v)))
+    }
+
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        serialize_map(w, self.iter().map(|(key, v)| (*key, v)), |w, field| {
+            field.serialize_into(w)
+        })
     }
 }
 
 impl Serialize for HashMap<String, Field> {
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
-        let size = self.size();
-        let mut buffer = vec![0u8; size as usize].into_boxed_slice();
-
-        let mut bytes_written: u32 = 0;
-        for (key, value) in self.iter() {
-            // append a null terminator
-            let key = format!("{key}\0");
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    key.as_ptr(),
-                    buffer.as_mut_ptr().add(bytes_written as usize),
-                    key.len(),
-                );
-            };
-
-            bytes_written += key.len() as u32;
-
-            let value = value.serialize()?;
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    value.as_ptr(),
-                    buffer.as_mut_ptr().add(bytes_written as usize),
-                    value.len(),
-                );
-            };
-
-            bytes_written += value.len() as u32;
-        }
-
-        Ok(buffer)
+        serialize_buffered(self)
     }
 
     fn size(&self) -> u32 {
-        self.iter()
-            // acc + field_name + \0 + field_type + field_value_length + field_value
-            .fold(0, |acc, (key, v)| {
-                acc + (key.len() as u32)
-                    + 1
-                    + mem::size_of::<u8>() as u32
-                    + mem::size_of::<u32>() as u32
-                    + v.size()
-            })
+        map_size(self.iter().map(|(key, v)| (key.as_str(), v)))
+    }
+
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        serialize_map(
+            w,
+            self.iter().map(|(key, v)| (key.as_str(), v)),
+            |w, field| field.serialize_into(w),
+        )
     }
 }
 
@@ -131,24 +118,11 @@ impl Serialize for Vec<Field> {
     }
 
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
-        let size = self.size();
-        let mut buffer = vec![0u8; size as usize].into_boxed_slice();
-
-        let mut offset = 0;
-
-        for field in self.iter() {
-            let len = field.size();
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    field.serialize()?.as_ptr(),
-                    (&mut buffer[(offset as usize)..]).as_mut_ptr(),
-                    len as usize,
-                );
-            }
-            offset += len;
-        }
+        serialize_buffered(self)
+    }
 
-        Ok(buffer)
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        self.iter().try_for_each(|field| field.serialize_into(w))
     }
 }
 
@@ -158,23 +132,10 @@ impl Serialize for Vec<Rc<Field>> {
     }
 
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>> {
-        let size = self.size();
-        let mut buffer = vec![0u8; size as usize].into_boxed_slice();
-
-        let mut offset = 0;
-
-        for field in self.iter() {
-            let len = field.size();
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    field.serialize()?.as_ptr(),
-                    (&mut buffer[(offset as usize)..]).as_mut_ptr(),
-                    len as usize,
-                );
-            }
-            offset += len;
-        }
-
-        Ok(buffer)
+        serialize_buffered(self)
+    }
+
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        self.iter().try_for_each(|field| field.serialize_into(w))
     }
 }
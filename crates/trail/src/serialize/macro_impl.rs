@@ -10,6 +10,11 @@ macro_rules! serializable_number {
             fn size(&self) -> u32 {
               mem::size_of::<Self>() as u32
             }
+
+            fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+                w.write_all(&self.to_le_bytes())?;
+                Ok(())
+            }
         })*
     }
 }
@@ -3,3 +3,6 @@ pub mod macro_impl;
 pub mod serialize;
 
 pub use serialize::*;
+
+#[cfg(feature = "derive")]
+pub use trail_derive::Serialize;
@@ -1,7 +1,21 @@
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, io::Write};
 
 pub trait Serialize: fmt::Debug {
     fn serialize(&self) -> Result<Box<[u8]>, Box<dyn Error>>;
     /// Returns the size of this type in bytes
     fn size(&self) -> u32;
+
+    /// Writes the bytes of [`Serialize::serialize`] into `w`, containers write their children
+    /// straight into it instead of copying each one out of its own buffer
+    fn serialize_into(&self, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        w.write_all(&self.serialize()?)?;
+        Ok(())
+    }
+}
+
+/// `serialize` for the types that write themselves with `serialize_into`, in one allocation
+pub fn serialize_buffered<T: Serialize + ?Sized>(value: &T) -> Result<Box<[u8]>, Box<dyn Error>> {
+    let mut buffer = Vec::with_capacity(value.size() as usize);
+    value.serialize_into(&mut buffer)?;
+    Ok(buffer.into_boxed_slice())
 }
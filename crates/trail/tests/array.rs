@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use trail::{
+    deserialize::Deserialize,
+    field::{Field, FieldType},
+    serialize::Serialize,
+};
+
+#[test]
+fn array_serialization_works() {
+    let field = Field::array(vec![Field::ubyte(1), Field::string("a".to_string())]);
+    let buffer = field.serialize().unwrap();
+
+    /*
+    // type - 10 (Array)
+    // length - 12, 0, 0, 0 (12)
+    // elements - [2, 1, 0, 0, 0, 1] (UByte 1), [0, 1, 0, 0, 0, 97] (String "a")
+     */
+    assert_eq!(
+        &buffer[..],
+        [10, 12, 0, 0, 0, 2, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0, 97]
+    );
+    assert_eq!(Field::deserialize(&buffer).unwrap(), field);
+}
+
+#[test]
+fn nested_arrays_and_maps_round_trip() {
+    let mut author = HashMap::new();
+    author.insert("name".to_string(), Field::from("whyneet"));
+    author.insert(
+        "tags".to_string(),
+        Field::array(vec![Field::from("rust"), Field::from("db")]),
+    );
+
+    let field = Field::array(vec![
+        Field::map(author),
+        Field::array(vec![Field::int32(1), Field::array(Vec::new())]),
+        Field::float64(0.5),
+    ]);
+    let buffer = field.serialize().unwrap();
+    assert_eq!(buffer.len(), field.size() as usize);
+
+    let field = Field::deserialize(&buffer).unwrap();
+    let elements = field.value_as_array().unwrap();
+    assert_eq!(elements.len(), 3);
+
+    let author = elements[0].value_as_map().unwrap();
+    assert_eq!(
+        author["tags"].value_as_array().unwrap()[1].value_as_string(),
+        Some("db")
+    );
+    let inner = elements[1].value_as_array().unwrap();
+    assert_eq!(inner[0].value_as_int32(), Some(&1));
+    assert_eq!(inner[1].value_as_array().map(Vec::len), Some(0));
+    assert_eq!(elements[2].value_as_float64(), Some(&0.5));
+}
+
+#[test]
+fn arrays_are_compared_element_by_element() {
+    let tags =
+        |tags: &[&str]| Field::from(tags.iter().map(|&tag| Field::from(tag)).collect::<Vec<_>>());
+
+    assert_eq!(tags(&["a", "b"]), tags(&["a", "b"]));
+    assert_ne!(tags(&["a", "b"]), tags(&["b", "a"]));
+    assert!(tags(&["a", "b"]) < tags(&["a", "c"]));
+    assert!(tags(&["a"]) < tags(&["a", "b"]));
+    assert!(tags(&[]) < tags(&["a"]));
+
+    // elements of different types are not ordered, neither are arrays and scalars
+    let mixed = Field::array(vec![Field::int32(1)]);
+    assert_eq!(tags(&["a"]).partial_cmp(&mixed), None);
+    assert_eq!(mixed.partial_cmp(&Field::int32(1)), None);
+}
+
+#[test]
+fn array_accessors_check_the_field_type() {
+    let field = Field::from(vec![Field::uint32(3)]);
+    assert_eq!(field.field_type(), FieldType::Array);
+    assert_eq!(field.get::<&Vec<Field>>().unwrap(), &vec![Field::uint32(3)]);
+    assert_eq!(field.value_as_map(), None);
+    assert_eq!(Field::uint32(3).value_as_array(), None);
+    assert!(Field::uint32(3).get::<&Vec<Field>>().is_err());
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use trail::{
+    deserialize::Deserialize,
+    error::{Error, MAX_DEPTH},
+    field::{Decimal, Field, Timestamp, Uuid},
+    serialize::Serialize,
+};
+
+/// A field holding one of every type, nested in maps and arrays
+fn sample() -> Field {
+    let members = HashMap::from([
+        ("name".to_string(), Field::from("trail")),
+        ("byte".to_string(), Field::byte(-3)),
+        ("count".to_string(), Field::uint64(7)),
+        ("ratio".to_string(), Field::float32(0.5)),
+        ("flag".to_string(), Field::bool(true)),
+        ("none".to_string(), Field::null()),
+        ("raw".to_string(), Field::binary(vec![0, 1, 2])),
+        (
+            "at".to_string(),
+            Field::timestamp(Timestamp::from_micros(42)),
+        ),
+        ("id".to_string(), Field::uuid(Uuid::from_bytes([9; 16]))),
+        ("price".to_string(), Field::decimal(Decimal::new(1999, 2))),
+    ]);
+
+    Field::array(vec![
+        Field::map(members),
+        Field::int32(-1),
+        Field::array(vec![Field::from("nested"), Field::float64(2.5)]),
+    ])
+}
+
+/// Reads `buffer` every way a field can be read, any result but a panic is fine
+fn read_all(buffer: &[u8]) {
+    let _ = Field::deserialize(buffer);
+    let _ = HashMap::<String, Field>::deserialize(buffer);
+    let _ = Vec::<Field>::deserialize(buffer);
+}
+
+/// A small xorshift generator, the sweeps need to be repeatable rather than good
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn truncated_input_is_an_error() {
+    let buffer = sample().serialize().unwrap();
+
+    for len in 0..buffer.len() {
+        assert!(Field::deserialize(&buffer[..len]).is_err(), "length {len}");
+    }
+    assert_eq!(Field::deserialize(&buffer).unwrap(), sample());
+
+    assert_eq!(
+        Field::deserialize(&[]).unwrap_err(),
+        Error::UnexpectedEof {
+            offset: 0,
+            needed: 1
+        }
+    );
+    assert_eq!(
+        Field::deserialize(&buffer[..3]).unwrap_err(),
+        Error::UnexpectedEof {
+            offset: 1,
+            needed: 4
+        }
+    );
+}
+
+#[test]
+fn corrupted_input_does_not_panic() {
+    let buffer = sample().serialize().unwrap();
+
+    for idx in 0..buffer.len() {
+        for bit in 0..8 {
+            let mut corrupted = buffer.to_vec();
+            corrupted[idx] ^= 1 << bit;
+            read_all(&corrupted);
+        }
+        for byte in [0x00, 0x7f, 0x80, 0xff] {
+            let mut corrupted = buffer.to_vec();
+            corrupted[idx] = byte;
+            read_all(&corrupted);
+            read_all(&corrupted[idx..]);
+        }
+    }
+
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..2000 {
+        let len = (xorshift(&mut state) % 64) as usize;
+        let random: Vec<u8> = (0..len).map(|_| xorshift(&mut state) as u8).collect();
+        read_all(&random);
+
+        // random bytes behind a valid header reach the value parsers
+        for tag in 0..=17u8 {
+            let mut field = vec![tag];
+            field.extend_from_slice(&(len as u32).to_le_bytes());
+            field.extend_from_slice(&random);
+            read_all(&field);
+        }
+    }
+}
+
+#[test]
+fn errors_report_their_offset() {
+    assert_eq!(
+        Field::deserialize(&[17, 0, 0, 0, 0]).unwrap_err(),
+        Error::UnknownFieldType { offset: 0, tag: 17 }
+    );
+    assert_eq!(
+        Field::deserialize(&[0, 255, 255, 255, 255, b'a']).unwrap_err(),
+        Error::LengthOverflow {
+            offset: 1,
+            length: u32::MAX as u64
+        }
+    );
+    assert_eq!(
+        Field::deserialize(&[0, 3, 0, 0, 0, b'a', 0xc3, b'b']).unwrap_err(),
+        Error::InvalidUtf8 { offset: 6 }
+    );
+    assert_eq!(
+        Field::deserialize(&[3, 2, 0, 0, 0, 1, 2]).unwrap_err(),
+        Error::InvalidLength {
+            offset: 5,
+            expected: 4,
+            found: 2
+        }
+    );
+    assert!(matches!(
+        Field::deserialize(&[12, 1, 0, 0, 0, 0]).unwrap_err(),
+        Error::InvalidLength { offset: 5, .. }
+    ));
+
+    // the second element of an array is cut short
+    let mut array = vec![10, 0, 0, 0, 0];
+    array.extend_from_slice(&Field::int32(1).serialize().unwrap());
+    array.extend_from_slice(&[3, 4, 0]);
+    let len = (array.len() - 5) as u32;
+    array[1..5].copy_from_slice(&len.to_le_bytes());
+    assert_eq!(
+        Field::deserialize(&array).unwrap_err(),
+        Error::UnexpectedEof {
+            offset: 15,
+            needed: 4
+        }
+    );
+
+    // a map member name without its null terminator
+    let err = HashMap::<String, Field>::deserialize(b"name").unwrap_err();
+    assert!(matches!(err, Error::InvalidData { offset: 0, .. }));
+    assert_eq!(err.offset(), 0);
+}
+
+#[test]
+fn deep_nesting_is_an_error() {
+    let nested =
+        |depth: usize| (0..depth).fold(Field::null(), |field, _| Field::array(vec![field]));
+
+    let buffer = nested(MAX_DEPTH).serialize().unwrap();
+    assert_eq!(Field::deserialize(&buffer).unwrap(), nested(MAX_DEPTH));
+
+    let buffer = nested(MAX_DEPTH + 1).serialize().unwrap();
+    assert_eq!(
+        Field::deserialize(&buffer).unwrap_err(),
+        Error::NestingTooDeep {
+            offset: 5 * (MAX_DEPTH + 1)
+        }
+    );
+
+    // headers alone are enough to nest past the limit
+    let mut buffer = Vec::new();
+    for level in 0..100_000u32 {
+        buffer.push(10);
+        buffer.extend_from_slice(&((100_000 - level - 1) * 5).to_le_bytes());
+    }
+    assert!(matches!(
+        Field::deserialize(&buffer).unwrap_err(),
+        Error::NestingTooDeep { .. }
+    ));
+}
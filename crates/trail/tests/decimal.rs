@@ -0,0 +1,175 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use trail::{
+    deserialize::Deserialize,
+    field::{Decimal, Field, FieldType},
+    serialize::Serialize,
+};
+
+fn decimal(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+fn hash(decimal: &Decimal) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    decimal.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn decimals_are_parsed_and_printed_exactly() {
+    let price = decimal("-12.050");
+    assert_eq!((price.coefficient(), price.scale()), (-12050, 3));
+    assert_eq!(price.to_string(), "-12.050");
+
+    assert_eq!(decimal("0.07").to_string(), "0.07");
+    assert_eq!(decimal("-.5").to_string(), "-0.5");
+    assert_eq!(decimal("+3").to_string(), "3");
+    assert_eq!(decimal("19.").to_string(), "19");
+    assert_eq!(
+        Decimal::new(i128::MIN, 38).to_string(),
+        "-1.70141183460469231731687303715884105728"
+    );
+
+    for invalid in [
+        "",
+        "-",
+        ".",
+        "1.2.3",
+        "1,5",
+        "1e5",
+        "0.000000000000000000000000000000000000001",
+    ] {
+        assert!(invalid.parse::<Decimal>().is_err(), "{invalid:?}");
+    }
+    assert!("170141183460469231731687303715884105728"
+        .parse::<Decimal>()
+        .is_err());
+}
+
+#[test]
+fn decimals_compare_by_value() {
+    assert_eq!(decimal("1.5"), decimal("1.500"));
+    assert_eq!(hash(&decimal("1.5")), hash(&decimal("1.500")));
+    assert_eq!(decimal("1.500").normalize().scale(), 1);
+
+    assert!(decimal("0.1") < decimal("0.10000000000000000000000000000000000001"));
+    assert!(decimal("-1.5") < decimal("-1.2"));
+    assert!(decimal("-0.5") < decimal("0.3"));
+    assert!(decimal("-2") < decimal("-1.99"));
+    assert!(Decimal::new(i128::MAX, 0) > Decimal::new(i128::MAX, 38));
+}
+
+#[test]
+fn decimal_arithmetic_is_exact() {
+    // 0.1 + 0.2 is not 0.3 in binary floating point
+    assert_eq!(
+        decimal("0.1").checked_add(&decimal("0.2")),
+        Some(decimal("0.3"))
+    );
+    let total = decimal("19.99").checked_add(&decimal("5")).unwrap();
+    assert_eq!(total.to_string(), "24.99");
+    assert_eq!(
+        total.checked_sub(&decimal("25.000")).unwrap().to_string(),
+        "-0.010"
+    );
+
+    assert_eq!(Decimal::new(i128::MAX, 0).checked_add(&decimal("1")), None);
+    assert_eq!(
+        Decimal::new(i128::MAX, 0).checked_add(&decimal("0.1")),
+        None
+    );
+    assert_eq!(
+        Decimal::new(i128::MIN, 2).checked_sub(&decimal("0.01")),
+        None
+    );
+}
+
+#[test]
+fn decimal_serialization_works() {
+    let field = Field::decimal(decimal("-2.5"));
+    let buffer = field.serialize().unwrap();
+
+    /*
+    // type - 16 (Decimal)
+    // length - 17, 0, 0, 0 (17)
+    // coefficient - -25 as a little-endian i128
+    // scale - 1
+     */
+    assert_eq!(&buffer[..5], [16, 17, 0, 0, 0]);
+    assert_eq!(&buffer[5..21], (-25i128).to_le_bytes());
+    assert_eq!(buffer[21], 1);
+
+    let field = Field::deserialize(&buffer).unwrap();
+    assert_eq!(field.field_type(), FieldType::Decimal);
+    assert_eq!(field.get::<Decimal>().unwrap().to_string(), "-2.5");
+
+    let mut buffer = buffer.to_vec();
+    buffer[21] = 39;
+    assert!(Field::deserialize(&buffer).is_err());
+}
+
+#[test]
+fn decimal_fields_compare_against_integer_fields() {
+    assert_eq!(Field::from(decimal("2.00")), Field::int32(2));
+    assert_eq!(
+        Field::uint64(u64::MAX),
+        Field::from(Decimal::from(u64::MAX))
+    );
+    assert_ne!(Field::from(decimal("2.01")), Field::int64(2));
+
+    assert!(Field::from(decimal("1.99")) < Field::byte(2));
+    assert!(Field::ubyte(255) > Field::from(decimal("-300")));
+    assert!(Field::from(decimal("4294967295.5")) > Field::uint32(u32::MAX));
+
+    // decimals against other types are still not ordered
+    assert_eq!(
+        Field::from(decimal("2")).partial_cmp(&Field::float64(2.0)),
+        None
+    );
+    assert_ne!(Field::from(decimal("2")), Field::from("2"));
+}
+
+#[test]
+fn integer_fields_compare_by_value_across_types() {
+    assert_eq!(Field::int32(2), Field::int64(2));
+    assert_eq!(Field::ubyte(200), Field::uint64(200));
+    assert_ne!(Field::byte(-1), Field::uint32(u32::MAX));
+    assert!(Field::byte(-1) < Field::ubyte(0));
+    assert!(Field::int64(i64::MAX) < Field::uint64(u64::MAX));
+    assert_eq!(Field::int32(2).partial_cmp(&Field::float64(2.0)), None);
+
+    // equality is transitive through decimals
+    let numbers = [
+        Field::byte(2),
+        Field::ubyte(2),
+        Field::int32(2),
+        Field::uint32(2),
+        Field::int64(2),
+        Field::uint64(2),
+        Field::from(decimal("2.000")),
+    ];
+    for a in &numbers {
+        for b in &numbers {
+            assert_eq!(a, b);
+            assert_eq!(a.value(), b.value());
+        }
+    }
+
+    let ordered = [
+        Field::int64(-300),
+        Field::from(decimal("-1.5")),
+        Field::byte(-1),
+        Field::uint32(0),
+        Field::from(decimal("0.5")),
+        Field::ubyte(1),
+    ];
+    for (i, a) in ordered.iter().enumerate() {
+        for (j, b) in ordered.iter().enumerate() {
+            assert_eq!(a.partial_cmp(b), Some(i.cmp(&j)));
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use std::{collections::HashMap, ffi::c_void, ptr};
+use std::collections::HashMap;
 
-use trail::{deserialize::Deserialize, field::Field, serialize::Serialize};
+use trail::{
+    deserialize::Deserialize,
+    field::{Field, FieldType, FieldValue, TypeMismatch},
+    serialize::Serialize,
+};
 
 #[test]
 fn string_serialization_works() {
@@ -30,61 +34,130 @@ fn map_serialization_works() {
 
     let buffer = field.serialize().unwrap();
 
-    assert_eq!(buffer.len(), 37);
-    assert_eq!(&buffer[..5], [9, 32, 0, 0, 0]);
+    assert_eq!(buffer.len(), 45);
+    // type, length, then the map marker and version
+    assert_eq!(&buffer[..7], [9, 40, 0, 0, 0, 0, 1]);
 
-    if buffer[5] == 115 {
+    if buffer[11] == 115 {
         assert_eq!(
             &buffer[..],
             [
-                9, 32, 0, 0, 0, 115, 116, 97, 114, 115, 0, 3, 4, 0, 0, 0, 100, 0, 0, 0, 110, 97,
-                109, 101, 0, 0, 7, 0, 0, 0, 119, 104, 121, 110, 101, 101, 116
+                9, 40, 0, 0, 0, 0, 1, 5, 0, 0, 0, 115, 116, 97, 114, 115, 3, 4, 0, 0, 0, 100, 0, 0,
+                0, 4, 0, 0, 0, 110, 97, 109, 101, 0, 7, 0, 0, 0, 119, 104, 121, 110, 101, 101, 116
             ]
         );
     } else {
         assert_eq!(
             &buffer[..],
             [
-                9, 32, 0, 0, 0, 110, 97, 109, 101, 0, 0, 7, 0, 0, 0, 119, 104, 121, 110, 101, 101,
-                116, 115, 116, 97, 114, 115, 0, 3, 4, 0, 0, 0, 100, 0, 0, 0
+                9, 40, 0, 0, 0, 0, 1, 4, 0, 0, 0, 110, 97, 109, 101, 0, 7, 0, 0, 0, 119, 104, 121,
+                110, 101, 101, 116, 5, 0, 0, 0, 115, 116, 97, 114, 115, 3, 4, 0, 0, 0, 100, 0, 0,
+                0
             ]
         );
     }
 }
 
+#[test]
+fn map_sizes_match_the_serialized_maps() {
+    // every member counts its name, its length prefix and the whole field once
+    let members = HashMap::from([
+        ("a".to_string(), Field::int32(1)),
+        ("name".to_string(), Field::from("trail")),
+        (
+            "nested".to_string(),
+            Field::map(HashMap::from([("x".to_string(), Field::null())])),
+        ),
+    ]);
+    let size = 2 + (4 + 1 + 5 + 4) + (4 + 4 + 5 + 5) + (4 + 6 + 5 + (2 + 4 + 1 + 5));
+    assert_eq!(members.size(), size);
+    assert_eq!(members.serialize().unwrap().len(), size as usize);
+
+    let borrowed: HashMap<&str, Field> = members
+        .iter()
+        .map(|(key, field)| (key.as_str(), field.clone()))
+        .collect();
+    assert_eq!(borrowed.size(), size);
+    assert_eq!(borrowed.serialize().unwrap().len(), size as usize);
+
+    assert_eq!(HashMap::<String, Field>::new().size(), 2);
+}
+
 #[test]
 fn string_deserialization_works() {
     let buffer = [0, 5, 0, 0, 0, 119, 111, 114, 108, 100];
     let field = Field::deserialize(&buffer).unwrap();
 
-    assert_eq!(format!("{:?}", field.value()), "\"world\"");
+    assert_eq!(field.value(), &FieldValue::String("world".to_string()));
 }
 
 #[test]
 fn map_deserialization_works() {
+    // written before maps had a version, with null-terminated names
     let buffer = [
         9, 32, 0, 0, 0, 115, 116, 97, 114, 115, 0, 3, 4, 0, 0, 0, 100, 0, 0, 0, 110, 97, 109, 101,
         0, 0, 7, 0, 0, 0, 119, 104, 121, 110, 101, 101, 116,
     ];
     let field = Field::deserialize(&buffer).unwrap();
 
-    let read_box_ref = |b: &Box<dyn Serialize>| {
-        let value = Box::into_raw(unsafe { ptr::read(b as *const Box<dyn Serialize>) });
-        let value = value as *mut c_void;
-        value
-    };
+    let map = field.value_as_map().unwrap();
+    assert_eq!(map["name"].value_as_string(), Some("whyneet"));
+    assert_eq!(map["stars"].value_as_int32(), Some(&100));
+}
+
+#[test]
+fn map_keys_round_trip() {
+    let keys = ["", "nul\0inside", "ends with\\", "\\\0", "ключ"];
+    let map: HashMap<String, Field> = keys
+        .iter()
+        .enumerate()
+        .map(|(idx, key)| (key.to_string(), Field::uint32(idx as u32)))
+        .collect();
+    let field = Field::map(map.clone());
 
-    let value = read_box_ref(field.value()) as *mut HashMap<String, Field>;
+    let buffer = field.serialize().unwrap();
+    assert_eq!(buffer.len(), field.size() as usize);
+    assert_eq!(
+        Field::deserialize(&buffer).unwrap().value_as_map(),
+        Some(&map)
+    );
 
-    unsafe {
-        assert!((*value).get("name").is_some());
+    let empty = Field::map(HashMap::new());
+    assert_eq!(&empty.serialize().unwrap()[..], [9, 2, 0, 0, 0, 0, 1]);
+    assert_eq!(
+        Field::deserialize(&empty.serialize().unwrap()).unwrap(),
+        empty
+    );
+}
 
-        let name = read_box_ref((*value).get("name").unwrap().value()) as *mut String;
-        assert_eq!(*name, "whyneet".to_string());
+#[test]
+fn legacy_and_unknown_map_versions() {
+    // a legacy map, nested in a current one
+    let legacy = [9, 12, 0, 0, 0, 105, 100, 0, 3, 4, 0, 0, 0, 7, 0, 0, 0];
+    let mut buffer = vec![9, 0, 0, 0, 0, 0, 1, 5, 0, 0, 0];
+    buffer.extend_from_slice(b"inner");
+    buffer.extend_from_slice(&legacy);
+    let len = (buffer.len() - 5) as u32;
+    buffer[1..5].copy_from_slice(&len.to_le_bytes());
 
-        let stars = read_box_ref((*value).get("stars").unwrap().value()) as *mut i32;
-        assert_eq!(*stars, 100);
-    }
+    let field = Field::deserialize(&buffer).unwrap();
+    let inner = field.value_as_map().unwrap()["inner"]
+        .value_as_map()
+        .unwrap();
+    assert_eq!(inner["id"], Field::int32(7));
+    assert_eq!(HashMap::<String, Field>::deserialize(&[]).unwrap().len(), 0);
+
+    assert!(matches!(
+        HashMap::<String, Field>::deserialize(&[0, 2]).unwrap_err(),
+        trail::Error::InvalidData { offset: 1, .. }
+    ));
+    assert_eq!(
+        HashMap::<String, Field>::deserialize(&[0, 1, 9, 0, 0, 0, b'a']).unwrap_err(),
+        trail::Error::LengthOverflow {
+            offset: 2,
+            length: 9
+        }
+    );
 }
 
 #[test]
@@ -144,3 +217,53 @@ fn field_eq_works() {
 
     assert_ne!(map2, map3);
 }
+
+#[test]
+fn accessors_check_the_field_type() {
+    let field = Field::int32(7);
+    assert_eq!(field.field_type(), FieldType::Int32);
+    assert_eq!(field.value_as_int32(), Some(&7));
+    assert_eq!(field.value_as_string(), None);
+    assert_eq!(field.value_as_int64(), None);
+    assert_eq!(field.value_as_map(), None);
+
+    let field = Field::string("hello".to_string());
+    assert_eq!(field.value_as_string(), Some("hello"));
+    assert_eq!(field.value_as_uint32(), None);
+}
+
+#[test]
+fn fields_convert_from_and_into_values() {
+    assert_eq!(Field::from(7i32), Field::int32(7));
+    assert_eq!(Field::from("hello"), Field::string("hello".to_string()));
+    assert_eq!(Field::from(2.5f64), Field::float64(2.5));
+
+    let field = Field::from(42u64);
+    assert_eq!(u64::try_from(&field), Ok(42));
+    assert_eq!(field.get::<u64>(), Ok(42));
+    assert_eq!(
+        i32::try_from(&field),
+        Err(TypeMismatch {
+            expected: FieldType::Int32,
+            found: FieldType::UInt64,
+        })
+    );
+
+    let field = Field::from("hello");
+    assert_eq!(<&str>::try_from(&field), Ok("hello"));
+    assert_eq!(String::try_from(&field), Ok("hello".to_string()));
+    let err = field.get::<f32>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected a Float32 field, found a String field"
+    );
+}
+
+#[test]
+fn fields_of_different_types_are_not_ordered() {
+    assert!(Field::int32(1) < Field::int32(2));
+    assert_eq!(Field::int32(1).partial_cmp(&Field::float32(2.0)), None);
+    assert_ne!(Field::int32(1), Field::from("1"));
+    // integers are the exception, they are ordered by value whatever their types
+    assert!(Field::int32(1) < Field::int64(2));
+}
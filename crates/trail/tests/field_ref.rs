@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use trail::{
+    deserialize::Deserialize,
+    error::Error,
+    field::{Field, FieldRef, FieldType, MapRef},
+    serialize::Serialize,
+};
+
+fn user() -> Field {
+    Field::map(HashMap::from([
+        ("name".to_string(), Field::from("whyneet")),
+        ("stars".to_string(), Field::int32(100)),
+        ("avatar".to_string(), Field::binary(vec![1, 2, 3])),
+        (
+            "address".to_string(),
+            Field::map(HashMap::from([("city".to_string(), Field::from("Riga"))])),
+        ),
+        (
+            "langs".to_string(),
+            Field::array(vec![Field::from("rust"), Field::null()]),
+        ),
+    ]))
+}
+
+#[test]
+fn fields_are_read_in_place() {
+    let buffer = user().serialize().unwrap();
+    let field = FieldRef::new(&buffer).unwrap();
+    assert_eq!(field.field_type(), FieldType::Map);
+    assert_eq!(field.value_bytes().len(), buffer.len() - 5);
+
+    let user = field.as_map().unwrap();
+    let name = user.get("name").unwrap().unwrap();
+    assert_eq!(name.as_str(), Some("whyneet"));
+    // the string borrows the buffer
+    assert!(buffer
+        .as_ptr_range()
+        .contains(&name.as_str().unwrap().as_ptr()));
+    assert_eq!(name.as_binary(), None);
+
+    let stars = user.get("stars").unwrap().unwrap();
+    assert_eq!(stars.as_str(), None);
+    assert_eq!(stars.to_field().unwrap(), Field::int32(100));
+    assert_eq!(
+        user.get("avatar").unwrap().unwrap().as_binary(),
+        Some(&[1, 2, 3][..])
+    );
+    assert_eq!(user.get("missing").unwrap(), None);
+
+    let city = user.get("address").unwrap().unwrap().as_map().unwrap();
+    assert_eq!(city.get("city").unwrap().unwrap().as_str(), Some("Riga"));
+
+    let langs = user.get("langs").unwrap().unwrap().as_array().unwrap();
+    assert_eq!(langs.get(0).unwrap().unwrap().as_str(), Some("rust"));
+    assert!(langs.get(1).unwrap().unwrap().is_null());
+    assert_eq!(langs.get(2).unwrap(), None);
+    assert_eq!(langs.iter().count(), 2);
+    assert_eq!(
+        langs.to_vec().unwrap(),
+        vec![Field::from("rust"), Field::null()]
+    );
+
+    let mut names: Vec<_> = user.iter().map(|member| member.unwrap().0).collect();
+    names.sort();
+    assert_eq!(names, ["address", "avatar", "langs", "name", "stars"]);
+    assert_eq!(&Field::map(user.to_map().unwrap()), &self::user());
+}
+
+#[test]
+fn legacy_maps_are_read_in_place() {
+    let legacy = [
+        115, 116, 97, 114, 115, 0, 3, 4, 0, 0, 0, 100, 0, 0, 0, 110, 97, 109, 101, 0, 0, 7, 0, 0,
+        0, 119, 104, 121, 110, 101, 101, 116,
+    ];
+    let map = MapRef::new(&legacy);
+    assert_eq!(
+        map.get("name").unwrap().and_then(|name| name.as_str()),
+        Some("whyneet")
+    );
+    assert_eq!(map.iter().count(), 2);
+}
+
+#[test]
+fn corrupted_members_are_errors() {
+    let mut buffer = user().serialize().unwrap().to_vec();
+    buffer.truncate(buffer.len() - 1);
+    assert!(matches!(
+        FieldRef::new(&buffer).unwrap_err(),
+        Error::LengthOverflow { offset: 1, .. }
+    ));
+
+    // a name length that runs past the map stops the iteration after one error
+    let map = MapRef::new(&[0, 1, 200, 0, 0, 0, b'a']);
+    let mut members = map.iter();
+    assert_eq!(
+        members.next().unwrap().unwrap_err(),
+        Error::LengthOverflow {
+            offset: 2,
+            length: 200
+        }
+    );
+    assert!(members.next().is_none());
+    assert!(map.get("a").is_err());
+
+    assert!(MapRef::new(&[0, 9]).get("a").is_err());
+}
+
+#[test]
+fn the_first_of_duplicate_members_wins() {
+    let mut map = vec![0, 1];
+    for value in [1, 2] {
+        map.extend_from_slice(&1u32.to_le_bytes());
+        map.push(b'a');
+        map.extend_from_slice(&Field::int32(value).serialize().unwrap());
+    }
+
+    let members = MapRef::new(&map);
+    assert_eq!(members.iter().count(), 2);
+    let first = members.get("a").unwrap().unwrap();
+    assert_eq!(first.to_field().unwrap(), Field::int32(1));
+
+    // decoding the whole map keeps the same member
+    assert_eq!(members.to_map().unwrap()["a"], Field::int32(1));
+    assert_eq!(
+        HashMap::<String, Field>::deserialize(&map).unwrap(),
+        HashMap::from([("a".to_string(), Field::int32(1))])
+    );
+}
+
+#[test]
+fn skipped_duplicate_members_are_still_validated() {
+    let mut map = vec![0, 1];
+    for value in ["ok", "no"] {
+        map.extend_from_slice(&1u32.to_le_bytes());
+        map.push(b'a');
+        map.extend_from_slice(&Field::from(value).serialize().unwrap());
+    }
+    // the duplicate is not valid UTF-8
+    *map.last_mut().unwrap() = 0xff;
+
+    assert!(MapRef::new(&map).to_map().is_err());
+    assert!(HashMap::<String, Field>::deserialize(&map).is_err());
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "serde")]
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use trail::{
+    deserialize::Deserialize as TrailDeserialize,
+    field::{Decimal, Field, Timestamp},
+    serde::{from_field, to_field},
+    serialize::Serialize as TrailSerialize,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Account {
+    id: u64,
+    #[serde(rename = "displayName")]
+    name: String,
+    email: Option<String>,
+    roles: Vec<Role>,
+    #[serde(flatten)]
+    audit: Audit,
+    #[serde(with = "serde_bytes_like")]
+    avatar: Vec<u8>,
+    limits: BTreeMap<u32, i16>,
+    balance: i128,
+    extra: Field,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Audit {
+    created_by: String,
+    revision: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Role {
+    Admin,
+    Member(char),
+    Guest { until: i64 },
+    Scoped(String, u8),
+}
+
+/// Serializes a `Vec<u8>` as bytes, the way `serde_bytes` does
+mod serde_bytes_like {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let field = trail::field::Field::deserialize(deserializer)?;
+        field
+            .value_as_binary()
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| serde::de::Error::custom("expected bytes"))
+    }
+}
+
+fn account() -> Account {
+    Account {
+        id: 7,
+        name: "whyneet".to_string(),
+        email: None,
+        roles: vec![
+            Role::Admin,
+            Role::Member('m'),
+            Role::Guest { until: -1 },
+            Role::Scoped("billing".to_string(), 2),
+        ],
+        audit: Audit {
+            created_by: "root".to_string(),
+            revision: 3,
+        },
+        avatar: vec![0, 1, 2],
+        limits: BTreeMap::from([(1, -5), (20, 300)]),
+        balance: -(1 << 100),
+        extra: Field::array(vec![Field::bool(true), Field::null()]),
+    }
+}
+
+#[test]
+fn serde_types_become_field_trees() {
+    let field = to_field(&account()).unwrap();
+    let members = field.value_as_map().unwrap();
+
+    assert_eq!(members["id"], Field::uint64(7));
+    assert_eq!(members["displayName"], Field::from("whyneet"));
+    assert!(members["email"].is_null());
+    // flattened members sit next to the others
+    assert_eq!(members["created_by"], Field::from("root"));
+    assert!(!members.contains_key("audit"));
+    assert_eq!(members["avatar"], Field::binary(vec![0, 1, 2]));
+    assert_eq!(
+        members["balance"],
+        Field::from(Decimal::from(-(1i128 << 100)))
+    );
+
+    let roles = members["roles"].value_as_array().unwrap();
+    assert_eq!(roles[0], Field::from("Admin"));
+    assert_eq!(
+        roles[3],
+        Field::map_str(HashMap::from([(
+            "Scoped",
+            Field::array(vec![Field::from("billing"), Field::ubyte(2)])
+        )]))
+    );
+
+    let limits = members["limits"].value_as_map().unwrap();
+    assert_eq!(limits["20"], Field::int32(300));
+}
+
+#[test]
+fn field_trees_become_serde_types() {
+    let account = account();
+    let field = to_field(&account).unwrap();
+
+    // through the stored bytes too
+    let buffer = TrailSerialize::serialize(&field).unwrap();
+    let field = <Field as TrailDeserialize>::deserialize(&buffer).unwrap();
+    assert_eq!(from_field::<Account>(field).unwrap(), account);
+
+    let timestamp = Field::timestamp(Timestamp::from_micros(12));
+    assert_eq!(from_field::<i64>(timestamp).unwrap(), 12);
+    let decimal = Field::from("1.25".parse::<Decimal>().unwrap());
+    assert_eq!(from_field::<String>(decimal).unwrap(), "1.25");
+    assert_eq!(from_field::<u16>(Field::uint32(9)).unwrap(), 9);
+}
+
+#[test]
+fn mismatched_serde_fields_are_reported() {
+    let mut field = to_field(&account())
+        .unwrap()
+        .value_as_map()
+        .unwrap()
+        .clone();
+    field.insert("id".to_string(), Field::from("seven"));
+    let err = from_field::<Account>(Field::map(field)).unwrap_err();
+    assert!(
+        err.to_string().contains("invalid type: string \"seven\""),
+        "{err}"
+    );
+
+    assert!(from_field::<u8>(Field::uint32(300)).is_err());
+    assert!(from_field::<Role>(Field::from("Owner")).is_err());
+    assert!(from_field::<Role>(Field::from("Member")).is_err());
+    assert!(from_field::<(u8, u8)>(Field::array(vec![Field::ubyte(1)])).is_err());
+
+    let keys = HashMap::from([(vec![1u8], 1u8)]);
+    assert!(to_field(&keys).is_err());
+}
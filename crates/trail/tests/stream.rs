@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use trail::{
+    deserialize::Deserialize,
+    field::{Decimal, Field, Timestamp},
+    serialize::Serialize,
+};
+
+fn nested(depth: usize) -> Field {
+    (0..depth).fold(Field::from("leaf"), |field, level| {
+        Field::map(HashMap::from([
+            ("child".to_string(), field),
+            (
+                "values".to_string(),
+                Field::array(vec![
+                    Field::int64(level as i64),
+                    Field::timestamp(Timestamp::from_micros(level as i64)),
+                    Field::decimal(Decimal::new(level as i128, 1)),
+                    Field::binary(vec![level as u8; 3]),
+                    Field::null(),
+                ]),
+            ),
+        ]))
+    })
+}
+
+#[test]
+fn serialize_into_writes_the_serialized_bytes() {
+    for depth in [0, 1, 5, 40] {
+        let field = nested(depth);
+        let buffer = field.serialize().unwrap();
+        assert_eq!(buffer.len(), field.size() as usize);
+
+        let mut streamed = Vec::new();
+        field.serialize_into(&mut streamed).unwrap();
+        assert_eq!(&streamed[..], &buffer[..]);
+        assert_eq!(Field::deserialize(&streamed).unwrap(), field);
+    }
+
+    let members = nested(3).value_as_map().unwrap().clone();
+    let mut streamed = Vec::new();
+    members.serialize_into(&mut streamed).unwrap();
+    assert_eq!(&streamed[..], &members.serialize().unwrap()[..]);
+
+    let elements = vec![Field::from("a"), nested(2)];
+    let mut streamed = Vec::new();
+    elements.serialize_into(&mut streamed).unwrap();
+    assert_eq!(&streamed[..], &elements.serialize().unwrap()[..]);
+}
+
+#[test]
+fn serialize_into_a_slice() {
+    let field = nested(4);
+    let mut buffer = vec![0u8; field.size() as usize + 3];
+
+    let mut slice = &mut buffer[..];
+    field.serialize_into(&mut slice).unwrap();
+    assert_eq!(slice.len(), 3);
+    assert_eq!(Field::deserialize(&buffer).unwrap(), field);
+
+    // a slice that is too small is an error, not a panic
+    let mut buffer = vec![0u8; field.size() as usize - 1];
+    assert!(field.serialize_into(&mut &mut buffer[..]).is_err());
+}
+
+#[test]
+fn deeply_nested_fields_are_sized_once() {
+    let depth = 2000;
+    let field = (0..depth).fold(Field::null(), |field, _| Field::array(vec![field]));
+
+    let mut streamed = Vec::new();
+    field.serialize_into(&mut streamed).unwrap();
+    assert_eq!(streamed.len(), 5 * (depth + 1));
+    assert_eq!(&streamed[..], &field.serialize().unwrap()[..]);
+
+    // every level is prefixed with the size of the levels below it
+    for (level, header) in streamed.chunks(5).enumerate().take(depth) {
+        assert_eq!(header[0], 10);
+        let len = u32::from_le_bytes(header[1..].try_into().unwrap());
+        assert_eq!(len as usize, 5 * (depth - level));
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use trail::{
+    deserialize::Deserialize,
+    field::{Field, FieldType, Timestamp, Uuid},
+    serialize::Serialize,
+};
+
+fn round_trip(field: &Field) -> Field {
+    let buffer = field.serialize().unwrap();
+    assert_eq!(buffer.len(), field.size() as usize);
+    Field::deserialize(&buffer).unwrap()
+}
+
+#[test]
+fn bool_and_null_serialization_works() {
+    assert_eq!(
+        &Field::bool(true).serialize().unwrap()[..],
+        [11, 1, 0, 0, 0, 1]
+    );
+    assert_eq!(&Field::null().serialize().unwrap()[..], [12, 0, 0, 0, 0]);
+
+    let field = round_trip(&Field::from(false));
+    assert_eq!(field.field_type(), FieldType::Bool);
+    assert_eq!(field.value_as_bool(), Some(&false));
+
+    let field = round_trip(&Field::null());
+    assert!(field.is_null());
+    assert_eq!(field.value_as_bool(), None);
+}
+
+#[test]
+fn binary_serialization_works() {
+    let field = Field::binary(vec![0, 255, 7]);
+    assert_eq!(&field.serialize().unwrap()[..], [13, 3, 0, 0, 0, 0, 255, 7]);
+
+    let field = round_trip(&field);
+    assert_eq!(field.value_as_binary(), Some(&[0, 255, 7][..]));
+    assert_eq!(field.get::<&[u8]>().unwrap(), [0, 255, 7]);
+    assert_eq!(
+        round_trip(&Field::binary(Vec::new())).get::<Vec<u8>>(),
+        Ok(Vec::new())
+    );
+}
+
+#[test]
+fn timestamps_round_trip_through_system_time() {
+    let field = Field::timestamp(Timestamp::from_micros(1_500_000));
+    assert_eq!(
+        &field.serialize().unwrap()[..],
+        [14, 8, 0, 0, 0, 96, 227, 22, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        round_trip(&field).value_as_timestamp(),
+        Some(&Timestamp::from_micros(1_500_000))
+    );
+
+    let time = UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456);
+    assert_eq!(Timestamp::from(time).as_micros(), 1_700_000_000_123_456);
+    assert_eq!(SystemTime::from(Timestamp::from(time)), time);
+
+    let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+    assert_eq!(Timestamp::from(before_epoch).as_micros(), -1_000_000);
+    assert_eq!(
+        SystemTime::from(Timestamp::from_micros(-1_000_000)),
+        before_epoch
+    );
+
+    assert!(Timestamp::now() > Timestamp::from(time));
+}
+
+#[test]
+fn uuids_are_parsed_printed_and_serialized() {
+    let text = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    let uuid = text.parse::<Uuid>().unwrap();
+    assert_eq!(uuid.to_string(), text);
+    assert_eq!(uuid.as_bytes()[..4], [0x67, 0xe5, 0x50, 0x44]);
+    assert_eq!("67E5504410B1426F9247BB680E5FE0C8".parse::<Uuid>(), Ok(uuid));
+    assert!("67e55044-10b1-426f-9247".parse::<Uuid>().is_err());
+    assert!("67e55044-10b1-426f-9247-bb680e5fe0cg"
+        .parse::<Uuid>()
+        .is_err());
+
+    let field = Field::from(uuid);
+    let buffer = field.serialize().unwrap();
+    assert_eq!(&buffer[..5], [15, 16, 0, 0, 0]);
+    assert_eq!(&buffer[5..], uuid.as_bytes());
+    assert_eq!(round_trip(&field).get::<Uuid>(), Ok(uuid));
+    assert_eq!(
+        Uuid::nil().to_string(),
+        "00000000-0000-0000-0000-000000000000"
+    );
+}
+
+#[test]
+fn new_types_are_ordered_within_their_type() {
+    assert!(Field::bool(false) < Field::bool(true));
+    assert_eq!(
+        Field::null().partial_cmp(&Field::null()),
+        Some(std::cmp::Ordering::Equal)
+    );
+    assert!(Field::binary(vec![1, 2]) < Field::binary(vec![1, 3]));
+    assert!(Field::binary(vec![1]) < Field::binary(vec![1, 0]));
+    assert!(
+        Field::timestamp(Timestamp::from_micros(-5)) < Field::timestamp(Timestamp::from_micros(3))
+    );
+    assert!(Field::uuid(Uuid::nil()) < Field::uuid(Uuid::from_bytes([1; 16])));
+
+    // booleans are no longer bytes and nulls are not a value of any other type
+    assert_eq!(Field::bool(true).partial_cmp(&Field::ubyte(1)), None);
+    assert_eq!(Field::null().partial_cmp(&Field::int32(0)), None);
+    assert!(Field::bool(true).get::<u8>().is_err());
+}
@@ -1,5 +1,3 @@
-use std::ptr;
-
 use trail::{deserialize::Deserialize, field::Field, serialize::Serialize};
 
 #[test]
@@ -35,20 +33,9 @@ pub fn vec_deserialization_works() {
     let vec = vec.unwrap();
 
     assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(vec[0].value() as *const Box<dyn Serialize>)) as *const String)
-                .as_ref()
-                .unwrap()
-        },
+        vec[0].value_as_string().unwrap(),
         &"Hello, world!".to_string()
     );
 
-    assert_eq!(
-        unsafe {
-            (Box::into_raw(ptr::read(vec[1].value() as *const Box<dyn Serialize>)) as *const u32)
-                .as_ref()
-                .unwrap()
-        },
-        &10
-    );
+    assert_eq!(vec[1].value_as_uint32().unwrap(), &10);
 }